@@ -0,0 +1,234 @@
+use crate::diagnostic::Diagnostic;
+use crate::json::{self, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The version of the baseline format, written in the file to tell it from later ones.
+const VERSION: f64 = 1.0;
+
+/// What a diagnostic is recognized by in a baseline: not its line, which changes whenever entries
+/// are added above it, but the rule and the message it is about.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    /// The path of the catalog, relative to the folder that is checked
+    path: String,
+    rule: String,
+    msgctxt: Option<String>,
+    /// The msgid of the entry, or none for the problems of a whole file
+    msgid: Option<String>,
+}
+
+/// The diagnostics known when the tool was adopted, which are no longer reported so that only new
+/// ones fail a run.
+///
+/// The same diagnostic can be recorded several times for an entry, and as many of them are then
+/// accepted.
+pub struct Baseline {
+    dir: PathBuf,
+    counts: BTreeMap<Key, usize>,
+}
+
+impl Baseline {
+    /// Returns an empty baseline of the catalogs of a folder.
+    pub fn new(dir: &Path) -> Baseline {
+        Baseline {
+            dir: dir.to_path_buf(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn load(path: &Path, dir: &Path) -> io::Result<Baseline> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        let invalid = |reason: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), reason),
+            )
+        };
+        let value = json::parse(&text).map_err(invalid)?;
+        if value.get("version").and_then(Value::as_f64) != Some(VERSION) {
+            return Err(invalid(format!("expected version {}", VERSION)));
+        }
+        let mut baseline = Baseline::new(dir);
+        let diagnostics = value
+            .get("diagnostics")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid(String::from("expected an array of diagnostics")))?;
+        for (index, diagnostic) in diagnostics.iter().enumerate() {
+            let string = |name| {
+                diagnostic
+                    .get(name)
+                    .and_then(Value::as_str)
+                    .map(String::from)
+            };
+            let key = Key {
+                path: string("path").unwrap_or_default(),
+                rule: string("rule").unwrap_or_default(),
+                msgctxt: string("msgctxt"),
+                msgid: string("msgid"),
+            };
+            if key.path.is_empty() || key.rule.is_empty() {
+                return Err(invalid(format!(
+                    "diagnostic {} has no path or rule",
+                    index + 1
+                )));
+            }
+            let count = diagnostic
+                .get("count")
+                .and_then(Value::as_f64)
+                .unwrap_or(1.0);
+            *baseline.counts.entry(key).or_default() += count as usize;
+        }
+        Ok(baseline)
+    }
+
+    fn key(&self, diagnostic: &Diagnostic) -> Key {
+        let path = diagnostic
+            .path
+            .strip_prefix(&self.dir)
+            .unwrap_or(&diagnostic.path);
+        Key {
+            path: path.to_string_lossy().replace('\\', "/"),
+            rule: diagnostic.rule().code.to_string(),
+            msgctxt: diagnostic
+                .context
+                .as_ref()
+                .and_then(|context| context.msgctxt.clone()),
+            msgid: diagnostic
+                .context
+                .as_ref()
+                .map(|context| context.msgid.clone()),
+        }
+    }
+
+    pub fn record(&mut self, diagnostic: &Diagnostic) {
+        *self.counts.entry(self.key(diagnostic)).or_default() += 1;
+    }
+
+    /// Whether a diagnostic is one of the baseline, each recorded one accepting a single
+    /// diagnostic.
+    pub fn accepts(&mut self, diagnostic: &Diagnostic) -> bool {
+        match self.counts.get_mut(&self.key(diagnostic)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns how many diagnostics the baseline has, less those it accepted: once a run is over,
+    /// the recorded problems that were fixed since.
+    pub fn count(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Writes the baseline, its diagnostics sorted so that it changes as little as they do.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let diagnostics = self
+            .counts
+            .iter()
+            .map(|(key, count)| {
+                Value::Object(vec![
+                    (String::from("path"), key.path.clone().into()),
+                    (String::from("rule"), key.rule.clone().into()),
+                    (String::from("msgctxt"), key.msgctxt.clone().into()),
+                    (String::from("msgid"), key.msgid.clone().into()),
+                    (String::from("count"), (*count).into()),
+                ])
+            })
+            .collect();
+        let value = Value::Object(vec![
+            (String::from("version"), Value::Number(VERSION)),
+            (String::from("diagnostics"), Value::Array(diagnostics)),
+        ]);
+        fs::write(path, value.pretty() + "\n")
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{Context, Severity};
+
+    /// Returns a missing interpolation of `msgid` in `dir/fr.po`, at a line.
+    fn missing(dir: &Path, msgid: &str, line_index: u32) -> Diagnostic {
+        Diagnostic {
+            message: "Missing interpolation",
+            severity: Severity::Error,
+            context: Some(Context {
+                msgid: String::from(msgid),
+                ..Context::default()
+            }),
+            missing: vec![String::from("{user}")],
+            unexpected: Vec::new(),
+            path: dir.join("fr.po"),
+            line_index,
+            column: 1,
+            lines: Vec::new(),
+            rule: None,
+        }
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "po-parser-baseline-{}-{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn accepts_the_recorded_diagnostics_wherever_they_moved() {
+        let dir = Path::new("locales");
+        let path = temp_file("saved.json");
+        let mut recorded = Baseline::new(dir);
+        recorded.record(&missing(dir, "Hello {user}", 3));
+        recorded.record(&missing(dir, "Hello {user}", 3));
+        recorded.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("\"path\": \"fr.po\""), "{}", text);
+        assert!(text.contains("\"count\": 2"), "{}", text);
+
+        let mut baseline = Baseline::load(&path, dir).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(baseline.count(), 2);
+        assert!(!baseline.accepts(&missing(dir, "Bye {user}", 3)));
+        assert!(baseline.accepts(&missing(dir, "Hello {user}", 10)));
+        assert!(baseline.accepts(&missing(dir, "Hello {user}", 12)));
+        assert!(!baseline.accepts(&missing(dir, "Hello {user}", 14)));
+        assert_eq!(baseline.count(), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_baselines() {
+        for (name, text, reason) in [
+            (
+                "version",
+                "{\"version\": 2, \"diagnostics\": []}",
+                "expected version 1",
+            ),
+            (
+                "array",
+                "{\"version\": 1}",
+                "expected an array of diagnostics",
+            ),
+            (
+                "rule",
+                "{\"version\": 1, \"diagnostics\": [{\"path\": \"fr.po\"}]}",
+                "diagnostic 1 has no path or rule",
+            ),
+        ] {
+            let path = temp_file(name);
+            fs::write(&path, text).unwrap();
+            let error = Baseline::load(&path, Path::new(".")).err().unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().ends_with(reason), "{}", error);
+        }
+    }
+}
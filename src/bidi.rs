@@ -0,0 +1,141 @@
+use crate::punctuation::base_language;
+
+/// The languages written from right to left.
+const RTL_LANGUAGES: [&str; 10] = ["ar", "he", "fa", "ur", "ps", "yi", "dv", "ckb", "sd", "ug"];
+
+pub fn is_rtl_language(language: &str) -> bool {
+    RTL_LANGUAGES.contains(&base_language(language))
+}
+
+/// Whether a character is of a script written from right to left.
+pub fn is_rtl(c: char) -> bool {
+    matches!(c,
+        '\u{590}'..='\u{8ff}' | '\u{fb1d}'..='\u{fdff}' | '\u{fe70}'..='\u{feff}')
+        && !c.is_whitespace()
+        && !is_control(c)
+}
+
+/// Whether a character is a directional mark, embedding, override or isolate.
+pub fn is_control(c: char) -> bool {
+    matches!(c, '\u{200e}' | '\u{200f}' | '\u{61c}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Returns the name of a directional control character.
+pub fn name(c: char) -> &'static str {
+    match c {
+        '\u{202a}' => "LRE",
+        '\u{202b}' => "RLE",
+        '\u{202c}' => "PDF",
+        '\u{202d}' => "LRO",
+        '\u{202e}' => "RLO",
+        '\u{2066}' => "LRI",
+        '\u{2067}' => "RLI",
+        '\u{2068}' => "FSI",
+        '\u{2069}' => "PDI",
+        '\u{200e}' => "LRM",
+        '\u{200f}' => "RLM",
+        '\u{61c}' => "ALM",
+        _ => "",
+    }
+}
+
+/// A directional control that is not closed, or that closes nothing.
+pub struct Unbalanced {
+    pub control: char,
+    pub offset: usize,
+    /// Whether the control closes an embedding or isolate, rather than opening one
+    pub closing: bool,
+}
+
+/// Returns the embeddings, overrides and isolates of a string that are left open, and the `PDF`
+/// and `PDI` controls that have nothing to close, in order.
+///
+/// As in the Unicode bidirectional algorithm, a `PDF` does not close an embedding outside of the
+/// isolate it is in, and a `PDI` closes the embeddings opened in its isolate.
+pub fn unbalanced(value: &str) -> Vec<Unbalanced> {
+    let mut open: Vec<(char, usize)> = Vec::new();
+    let mut unbalanced = Vec::new();
+    for (offset, c) in value.char_indices() {
+        match c {
+            '\u{202a}' | '\u{202b}' | '\u{202d}' | '\u{202e}' | '\u{2066}'..='\u{2068}' => {
+                open.push((c, offset))
+            }
+            '\u{202c}' => match open.last() {
+                Some((last, _)) if !matches!(last, '\u{2066}'..='\u{2068}') => {
+                    open.pop();
+                }
+                _ => unbalanced.push(Unbalanced {
+                    control: c,
+                    offset,
+                    closing: true,
+                }),
+            },
+            '\u{2069}' => {
+                match open
+                    .iter()
+                    .rposition(|(last, _)| matches!(last, '\u{2066}'..='\u{2068}'))
+                {
+                    Some(index) => {
+                        // Embeddings left open in the isolate are closed with it.
+                        for (control, offset) in open.drain(index..).skip(1) {
+                            unbalanced.push(Unbalanced {
+                                control,
+                                offset,
+                                closing: false,
+                            });
+                        }
+                    }
+                    None => unbalanced.push(Unbalanced {
+                        control: c,
+                        offset,
+                        closing: true,
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+    unbalanced.extend(open.into_iter().map(|(control, offset)| Unbalanced {
+        control,
+        offset,
+        closing: false,
+    }));
+    unbalanced.sort_by_key(|unbalanced| unbalanced.offset);
+    unbalanced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(value: &str) -> Vec<(&'static str, bool)> {
+        unbalanced(value)
+            .iter()
+            .map(|unbalanced| (name(unbalanced.control), unbalanced.closing))
+            .collect()
+    }
+
+    #[test]
+    fn tells_right_to_left_languages_and_characters() {
+        assert!(is_rtl_language("ar_EG"));
+        assert!(is_rtl_language("ckb"));
+        assert!(!is_rtl_language("fr"));
+        assert!(is_rtl('ש'));
+        assert!(is_rtl('ب'));
+        assert!(!is_rtl('a'));
+        assert!(!is_rtl('\u{61c}'));
+    }
+
+    #[test]
+    fn finds_controls_left_open_or_closing_nothing() {
+        assert!(names("\u{2067}שלום\u{2069} \u{202b}x\u{202c}").is_empty());
+        assert_eq!(names("\u{2067}שלום"), [("RLI", false)]);
+        assert_eq!(names("x\u{202c}"), [("PDF", true)]);
+        assert_eq!(names("\u{2069}"), [("PDI", true)]);
+        assert_eq!(names("\u{2066}\u{202b}x\u{2069}"), [("RLE", false)]);
+        assert_eq!(
+            names("\u{202b}\u{2066}\u{202c}\u{2069}\u{202c}"),
+            [("PDF", true)]
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use crate::parser::{escape, Entry, Parser};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// The width gettext tools wrap long strings at.
+const DEFAULT_WIDTH: usize = 79;
+
+/// A parsed catalog that can be written back to disk.
+///
+/// Unchanged entries are written exactly as they were read, so that a catalog is reproduced
+/// byte-for-byte when nothing changed. Changed and new entries are formatted the way gettext
+/// tools do, wrapped at the width of the catalog.
+pub struct Catalog {
+    pub entries: Vec<Entry>,
+    /// The comments and blank lines after the last entry
+    pub trailer: String,
+    pub encoding: &'static Encoding,
+    pub bom: bool,
+    /// The width the strings of changed entries are wrapped at
+    pub width: usize,
+    /// The line terminator of changed entries
+    pub line_ending: &'static str,
+    /// The entries as they were parsed, by raw text, to tell the unchanged ones apart
+    parsed: HashMap<String, Entry>,
+}
+
+impl Catalog {
+    pub fn parse<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut parser = Parser::new(reader);
+        let entries = parser.by_ref().collect::<io::Result<Vec<_>>>()?;
+        let raw_lines = || entries.iter().flat_map(|entry| entry.raw.lines());
+        let line_ending = match entries.first() {
+            Some(entry) if entry.raw.contains("\r\n") => "\r\n",
+            _ => "\n",
+        };
+        // Catalogs written without wrapping have lines longer than the default width.
+        let width = raw_lines()
+            .filter(|line| line.trim_start_matches("#~ ").contains('"'))
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(DEFAULT_WIDTH);
+        let parsed = entries
+            .iter()
+            .map(|entry| (entry.raw.clone(), entry.clone()))
+            .collect();
+        Ok(Catalog {
+            entries,
+            trailer: parser.trailer().to_string(),
+            encoding: parser.encoding(),
+            bom: parser.has_bom(),
+            width,
+            line_ending,
+            parsed,
+        })
+    }
+
+    /// Whether an entry is the same as when the catalog was parsed.
+    pub fn is_unchanged(&self, entry: &Entry) -> bool {
+        self.parsed.get(&entry.raw) == Some(entry)
+    }
+
+    /// Changes the encoding the catalog is written in, and the charset its header declares to
+    /// the name it is given by, returning whether it was written in another one.
+    pub fn set_encoding(&mut self, encoding: &'static Encoding, charset: &str) -> bool {
+        if self.encoding == encoding {
+            return false;
+        }
+        self.encoding = encoding;
+        self.bom = false;
+        let content_type = Regex::new(r"(?im)^(Content-Type:.*charset=)[^\s;]+").unwrap();
+        let header = self.entries.iter_mut().find(|entry| entry.is_header());
+        if let Some(msgstr) = header.and_then(|entry| entry.msgstr.first_mut()) {
+            msgstr.value = content_type
+                .replace(&msgstr.value, |captures: &Captures| {
+                    format!("{}{}", &captures[1], charset)
+                })
+                .into_owned();
+        }
+        true
+    }
+
+    /// Returns the text of the catalog.
+    pub fn serialize(&self) -> String {
+        let mut text = String::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if self.is_unchanged(entry) {
+                text.push_str(&entry.raw);
+                continue;
+            }
+            // Keep the blank lines the entry was separated by, or separate a new entry.
+            let content = entry.raw.trim_start_matches(['\r', '\n']);
+            let separator = &entry.raw[..entry.raw.len() - content.len()];
+            if !separator.is_empty() {
+                text.push_str(separator);
+            } else if index > 0 {
+                text.push_str(self.line_ending);
+            }
+            self.format_entry(entry, &mut text);
+        }
+        text.push_str(&self.trailer);
+        text
+    }
+
+    /// Writes the catalog in its original encoding.
+    ///
+    /// UTF-16 catalogs, which encoding_rs has no encoder for, are encoded by hand, after a byte
+    /// order mark without which they could not be read back.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let text = self.serialize();
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let mut bytes = Vec::with_capacity(2 * text.len() + 2);
+            for unit in std::iter::once(0xfeff).chain(text.encode_utf16()) {
+                if self.encoding == UTF_16LE {
+                    bytes.extend(unit.to_le_bytes());
+                } else {
+                    bytes.extend(unit.to_be_bytes());
+                }
+            }
+            return writer.write_all(&bytes);
+        }
+        let encoding = self.encoding.output_encoding();
+        let (bytes, _, unmappable) = encoding.encode(&text);
+        if unmappable {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the catalog cannot be encoded in {}", encoding.name()),
+            ));
+        }
+        if self.bom && encoding == encoding_rs::UTF_8 {
+            writer.write_all(b"\xef\xbb\xbf")?;
+        }
+        writer.write_all(&bytes)
+    }
+
+    /// Writes the catalog to its file, through a file next to it that then replaces it, so that a
+    /// write that is interrupted leaves the catalog as it was.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temporary = path.with_file_name(format!(".{}.po-parser", name));
+        let written = (|| {
+            let mut file = File::create(&temporary)?;
+            self.write(&mut file)?;
+            file.sync_all()?;
+            if let Ok(metadata) = fs::metadata(path) {
+                fs::set_permissions(&temporary, metadata.permissions())?;
+            }
+            fs::rename(&temporary, path)
+        })();
+        if written.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        written.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+    }
+
+    fn format_entry(&self, entry: &Entry, text: &mut String) {
+        let mut line = |line: &str| {
+            text.push_str(line);
+            text.push_str(self.line_ending);
+        };
+        for comment in &entry.translator_comments {
+            line(format!("# {}", comment).trim_end());
+        }
+        for comment in &entry.extracted_comments {
+            line(&format!("#. {}", comment));
+        }
+        if !entry.references.is_empty() {
+            line(&format!("#: {}", entry.references.join(" ")));
+        }
+        if !entry.flags.is_empty() {
+            line(&format!("#, {}", entry.flags.join(", ")));
+        }
+        let prefix = if entry.obsolete { "#~ " } else { "" };
+        let previous_prefix = if entry.obsolete { "#~| " } else { "#| " };
+        let mut strings = Vec::new();
+        let previous = [
+            ("msgctxt", &entry.previous_msgctxt),
+            ("msgid", &entry.previous_msgid),
+            ("msgid_plural", &entry.previous_msgid_plural),
+        ];
+        for (keyword, value) in previous {
+            if let Some(value) = value {
+                strings.push((previous_prefix, keyword.to_string(), value.as_str()));
+            }
+        }
+        if let Some(msgctxt) = &entry.msgctxt {
+            strings.push((prefix, String::from("msgctxt"), msgctxt));
+        }
+        strings.push((prefix, String::from("msgid"), &entry.msgid));
+        if let Some(msgid_plural) = &entry.msgid_plural {
+            strings.push((prefix, String::from("msgid_plural"), msgid_plural));
+        }
+        for msgstr in &entry.msgstr {
+            strings.push((prefix, entry.msgstr_keyword(msgstr), &msgstr.value));
+        }
+        for (prefix, keyword, value) in strings {
+            for string in self.wrap(prefix, &keyword, value) {
+                line(&format!("{}{}", prefix, string));
+            }
+        }
+    }
+
+    /// Formats a keyword and its value as lines of quoted strings, wrapped like gettext does:
+    /// after each newline, and at spaces to fit the width of the catalog with the prefix the
+    /// lines are written after, such as `#~ `.
+    fn wrap(&self, prefix: &str, keyword: &str, value: &str) -> Vec<String> {
+        let width = self.width.saturating_sub(prefix.len());
+        let escaped = escape(value);
+        let single = format!("{} \"{}\"", keyword, escaped);
+        let pieces: Vec<_> = escaped.split_inclusive("\\n").collect();
+        if pieces.len() <= 1 && single.chars().count() <= width {
+            return vec![single];
+        }
+        let mut lines = vec![format!("{} \"\"", keyword)];
+        let max = width.saturating_sub(2);
+        for piece in pieces {
+            let mut current = String::new();
+            for word in piece.split_inclusive(' ') {
+                let length = current.chars().count() + word.chars().count();
+                if length > max && !current.is_empty() {
+                    lines.push(format!("\"{}\"", current));
+                    current.clear();
+                }
+                current.push_str(word);
+            }
+            lines.push(format!("\"{}\"", current));
+        }
+        lines
+    }
+}
+
+/// Rewrites a catalog file in a charset, by one of its labels, returning whether it was in another
+/// one.
+pub fn convert(path: &Path, charset: &str) -> io::Result<bool> {
+    let encoding = Encoding::for_label(charset.as_bytes()).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unknown charset {}", charset),
+        )
+    })?;
+    // Labels such as `replacement` name encodings only meant for decoding, which would be
+    // written as UTF-8 under a header naming another charset.
+    if encoding != UTF_16LE && encoding != UTF_16BE && encoding.output_encoding() != encoding {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} cannot be written", charset),
+        ));
+    }
+    let file = File::open(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    let mut catalog = Catalog::parse(io::BufReader::new(file))?;
+    if !catalog.set_encoding(encoding, charset) {
+        return Ok(false);
+    }
+    catalog.save(path)?;
+    Ok(true)
+}
+
+/// A message of a catalog, by its context and source.
+pub type Message = (Option<String>, String);
+
+/// Returns the messages of a catalog file, the header and obsolete entries aside.
+fn messages(path: &Path) -> io::Result<Vec<Message>> {
+    let file = File::open(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    let mut messages = Vec::new();
+    for entry in Parser::new(io::BufReader::new(file)) {
+        let entry =
+            entry.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        if !entry.obsolete && !entry.is_header() {
+            messages.push((entry.msgctxt, entry.msgid));
+        }
+    }
+    Ok(messages)
+}
+
+/// Returns the messages of a template that a catalog lacks, and those of the catalog the template
+/// does not have.
+pub fn compare(catalog: &Path, template: &Path) -> io::Result<(Vec<Message>, Vec<Message>)> {
+    let translated = messages(catalog)?;
+    let extracted = messages(template)?;
+    let missing = extracted
+        .iter()
+        .filter(|message| !translated.contains(message))
+        .cloned()
+        .collect();
+    let extra = translated
+        .iter()
+        .filter(|message| !extracted.contains(message))
+        .cloned()
+        .collect();
+    Ok((missing, extra))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog(text: &str) -> Catalog {
+        Catalog::parse(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn wraps_long_strings_at_newlines_and_spaces() {
+        let catalog = Catalog {
+            width: 20,
+            ..catalog("")
+        };
+        assert_eq!(catalog.wrap("", "msgid", "Short"), ["msgid \"Short\""]);
+        assert_eq!(
+            catalog.wrap("", "msgid", "One\nTwo"),
+            ["msgid \"\"", "\"One\\n\"", "\"Two\""]
+        );
+        assert_eq!(
+            catalog.wrap("", "msgstr", "a few words that do not fit"),
+            ["msgstr \"\"", "\"a few words that \"", "\"do not fit\""]
+        );
+        // Obsolete lines are wrapped with their prefix.
+        assert_eq!(
+            catalog.wrap("#~ ", "msgid", "Sixteen chars!!!"),
+            ["msgid \"\"", "\"Sixteen \"", "\"chars!!!\""]
+        );
+    }
+
+    #[test]
+    fn formats_changed_entries_like_gettext() {
+        let mut catalog = catalog("msgid \"a\"\nmsgstr \"b\"\n\n#~ msgid \"c\"\n#~ msgstr \"d\"\n");
+        catalog.entries[0].flags.push(String::from("fuzzy"));
+        catalog.entries[0].msgstr[0].value = String::from("say \"hi\"\t");
+        catalog.entries[1].msgstr[0].value = String::from("e");
+        assert_eq!(
+            catalog.serialize(),
+            "#, fuzzy\nmsgid \"a\"\nmsgstr \"say \\\"hi\\\"\\t\"\n\n#~ msgid \"c\"\n#~ msgstr \"e\"\n"
+        );
+    }
+
+    #[test]
+    fn converts_catalogs_to_utf_16_and_back() {
+        let path =
+            std::env::temp_dir().join(format!("po-parser-convert-{}.po", std::process::id()));
+        let text = "msgid \"\"\nmsgstr \"Content-Type: text/plain; charset=UTF-8\\n\"\n\n\
+                    msgid \"Hello\"\nmsgstr \"你好 😀\"\n";
+        std::fs::write(&path, text).unwrap();
+        for (charset, bom) in [("UTF-16", b"\xff\xfe"), ("UTF-16BE", b"\xfe\xff")] {
+            assert!(convert(&path, charset).unwrap());
+            let bytes = std::fs::read(&path).unwrap();
+            assert_eq!(&bytes[..2], bom);
+            let catalog = Catalog::parse(&bytes[..]).unwrap();
+            assert_eq!(catalog.entries[1].msgstr[0].value, "你好 😀");
+            assert!(catalog.entries[0].msgstr[0].value.contains(charset));
+        }
+        assert!(convert(&path, "UTF-8").unwrap());
+        let converted = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(converted, text);
+        let e = convert(&path, "replacement").unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn saves_catalogs_through_a_file_it_renames() {
+        let dir = std::env::temp_dir().join(format!("po-parser-save-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("fr.po");
+        fs::write(&path, "msgid \"a\"\nmsgstr \"b\"\n").unwrap();
+        let mut catalog = catalog(&fs::read_to_string(&path).unwrap());
+        catalog.entries[0].msgstr[0].value = String::from("c");
+        catalog.save(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "msgid \"a\"\nmsgstr \"c\"\n"
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        // A catalog that cannot be encoded is left as it was.
+        catalog.encoding = encoding_rs::WINDOWS_1252;
+        catalog.entries[0].msgstr[0].value = String::from("😀");
+        assert!(catalog.save(&path).is_err());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "msgid \"a\"\nmsgstr \"c\"\n"
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn takes_the_width_of_catalogs_written_without_wrapping() {
+        let long = "x".repeat(100);
+        let text = format!("msgid \"{}\"\nmsgstr \"\"\n", long);
+        assert_eq!(catalog(&text).width, 108);
+        assert_eq!(catalog("msgid \"a\"\nmsgstr \"\"\n").width, DEFAULT_WIDTH);
+    }
+}
@@ -0,0 +1,3087 @@
+use crate::consistency::Translation;
+use crate::diagnostic::{located, Context, Diagnostic, Severity};
+use crate::format::Format;
+use crate::glossary::Glossary;
+use crate::header::{Header, HeaderProblem};
+use crate::interactive::{Decision, Prompt, Proposal};
+use crate::parser::{self, escape, quoted, Entry, Field};
+use crate::punctuation::base_language;
+use crate::rules::{self, RuleInfo};
+use crate::spelling::Spellchecker;
+use crate::stats::Stats;
+use crate::{
+    bidi, catalog, diff, format, invisible, links, markdown, markup, mojibake, normalization,
+    numbers, punctuation, spelling, strftime, suppressions, typography,
+};
+use clap::ValueEnum;
+use regex::Regex;
+use std::collections::{hash_map, HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Returns the pattern matching the placeholders of any of several patterns, or the one given.
+pub fn combined<S: AsRef<str>>(patterns: &[S]) -> io::Result<Regex> {
+    let pattern = match patterns {
+        [pattern] => pattern.as_ref().to_string(),
+        patterns => patterns
+            .iter()
+            .map(|pattern| format!("(?:{})", pattern.as_ref()))
+            .collect::<Vec<_>>()
+            .join("|"),
+    };
+    // Patterns that are valid on their own may still name the same group.
+    Regex::new(&pattern).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+/// A term translations must keep as it is, such as a product name.
+#[derive(Clone)]
+pub struct ProtectedTerm {
+    pub term: String,
+    /// Matches the term as a whole word
+    regex: Regex,
+    /// Matches the term as a whole word, whatever its case, to find it altered
+    any_case: Regex,
+}
+
+impl ProtectedTerm {
+    pub fn new(term: &str) -> io::Result<ProtectedTerm> {
+        let pattern = format!(r"\b{}\b", regex::escape(term));
+        let regex = |pattern: &str| {
+            Regex::new(pattern).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+        };
+        Ok(ProtectedTerm {
+            term: term.to_string(),
+            regex: regex(&pattern)?,
+            any_case: regex(&format!("(?i){}", pattern))?,
+        })
+    }
+}
+
+/// The pattern matching placeholders when none is given and no preset is used.
+pub const DEFAULT_PATTERN: &str = r"\{\{[^{}]*\}\}|\{[^{}]*\}";
+
+/// The placeholder syntaxes of common frameworks, checked on entries whether or not they are
+/// flagged with their format.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Preset {
+    /// `{{name}}`, `{{count, number}}` interpolations and `$t(key)` nestings of i18next
+    I18next,
+    /// `%s`, `%1$d` directives of C's printf, as xgettext extracts them
+    GettextC,
+    /// `%s`, `%(name)s` directives of Python's `%` operator
+    Python,
+    /// `%1`, `%L2` markers of `QString::arg`
+    Qt,
+    /// `{name}`, `{count, plural, ...}` arguments of ICU MessageFormat
+    Icu,
+    /// `{{ name }}`, `{{ date | short }}` interpolations of Angular templates
+    Angular,
+    /// `%{name}`, `%<name>d` interpolations of Ruby and Rails
+    Ruby,
+    /// `{0}`, `{1,number}` arguments of Java's `MessageFormat`
+    Java,
+    /// `{0}`, `{0:d}` items of C#'s `String.Format`
+    #[value(name = "csharp")]
+    CSharp,
+}
+
+impl Preset {
+    /// The format language every entry is checked in, for frameworks that have one.
+    pub fn format(self) -> Option<Format> {
+        match self {
+            Preset::GettextC => Some(Format::C),
+            Preset::Python => Some(Format::Python),
+            Preset::Qt => Some(Format::Qt),
+            Preset::Icu => Some(Format::Icu),
+            Preset::Ruby => Some(Format::Ruby),
+            Preset::Java => Some(Format::Java),
+            Preset::CSharp => Some(Format::CSharp),
+            Preset::I18next | Preset::Angular => None,
+        }
+    }
+
+    /// The pattern of placeholders, for frameworks whose interpolations are not a format language.
+    pub fn pattern(self) -> Option<&'static str> {
+        match self {
+            Preset::I18next => Some(r"\{\{[^{}]*\}\}|\$t\([^()]*\)"),
+            Preset::Angular => Some(r"\{\{[^{}]*\}\}"),
+            _ => None,
+        }
+    }
+}
+
+/// The options controlling which checks are run on each file.
+#[derive(Clone)]
+pub struct CheckOptions {
+    /// The pattern of placeholders, if they are checked by pattern
+    pub pattern: Option<Regex>,
+    /// The format language of entries not flagged with one
+    pub default_format: Option<Format>,
+    pub fail_on_fuzzy: bool,
+    pub fail_on_untranslated: bool,
+    pub include_obsolete: bool,
+    pub strict_syntax: bool,
+    pub ignore_repeats: bool,
+    pub accelerators: Vec<char>,
+    pub punctuation: bool,
+    pub links: bool,
+    pub same_as_source: bool,
+    pub brands: Vec<String>,
+    /// The range of percentages of the length of their source translations must be in
+    pub length_ratio: Option<(f64, f64)>,
+    /// Whether the translations of each file are kept to compare them across files
+    pub cross_file: bool,
+    pub glossary: Option<Arc<Glossary>>,
+    /// The terms translations must keep
+    pub protected_terms: Vec<ProtectedTerm>,
+    pub spellchecker: Option<Arc<Spellchecker>>,
+    pub markdown: bool,
+    pub numbers: bool,
+    pub nfc: bool,
+    /// The typography rules checked in the catalogs of languages that have some
+    pub typography: Vec<typography::Rule>,
+    pub normalize: bool,
+    /// Whether the catalogs are rewritten with the corrections of the fix command
+    pub fix: bool,
+    /// Whether the corrections are printed rather than written
+    pub dry_run: bool,
+    /// Whether the corrections are printed as a unified diff rather than written
+    pub diff: bool,
+    /// Whether each correction is asked for
+    pub interactive: bool,
+    /// The mark of the place of the placeholders a translation lacks
+    pub cursor: String,
+    /// The pattern of the msgid or msgctxt of the entries that are checked
+    pub filter: Option<Regex>,
+    /// Which rules are reported, in the catalogs of each language
+    pub settings: Arc<rules::Settings>,
+    /// The rules checking entries that implement [`Rule`], those of `default_rules` and those of
+    /// library users
+    pub rules: Vec<Arc<dyn Rule>>,
+}
+
+impl Default for CheckOptions {
+    /// The options of a run given no flags: placeholders are matched by the default pattern, and
+    /// the rules that are on by default are reported.
+    fn default() -> Self {
+        CheckOptions {
+            pattern: Some(Regex::new(DEFAULT_PATTERN).unwrap()),
+            default_format: None,
+            fail_on_fuzzy: false,
+            fail_on_untranslated: false,
+            include_obsolete: false,
+            strict_syntax: false,
+            ignore_repeats: false,
+            accelerators: Vec::new(),
+            punctuation: true,
+            links: true,
+            same_as_source: false,
+            brands: Vec::new(),
+            length_ratio: None,
+            cross_file: false,
+            glossary: None,
+            protected_terms: Vec::new(),
+            spellchecker: None,
+            markdown: false,
+            numbers: false,
+            nfc: true,
+            typography: typography::Rule::ALL.to_vec(),
+            normalize: false,
+            fix: false,
+            dry_run: false,
+            diff: false,
+            interactive: false,
+            cursor: String::from("@@"),
+            filter: None,
+            settings: Arc::default(),
+            rules: default_rules(),
+        }
+    }
+}
+
+/// What is known of a catalog once it has been checked.
+pub struct Summary {
+    /// The language of the catalog, or its file name when it declares none
+    pub language: String,
+    pub stats: Stats,
+    /// The translations of the catalog, if they are compared across files
+    pub translations: Vec<Translation>,
+}
+
+struct InterpolationParams<'a> {
+    path: &'a Path,
+    pattern: &'a Regex,
+    msgctxt: Option<&'a str>,
+    /// The other source form of a plural entry, whose placeholders a translation may also use
+    other_source: Option<&'a str>,
+    msgid: Field<'a>,
+    msgstr: Field<'a>,
+    ignore_repeats: bool,
+}
+
+impl InterpolationParams<'_> {
+    fn context(&self) -> Context {
+        Context {
+            msgctxt: self.msgctxt.map(String::from),
+            msgid: self.msgid.value.to_string(),
+            msgstr: Some(self.msgstr.value.to_string()),
+            references: Vec::new(),
+        }
+    }
+}
+
+/// A distinct placeholder of a string: its text, how many times it occurs and where it first does.
+struct Placeholder<'a> {
+    text: &'a str,
+    /// The text placeholders are compared by
+    key: String,
+    count: usize,
+    offset: usize,
+}
+
+/// Returns the text a placeholder is compared by, without the whitespace and the formatters of
+/// i18next and Vue, so that `{{ count, number }}` and `{{name | capitalize}}` are the same as
+/// `{{count}}` and `{{name}}`.
+fn normalize(placeholder: &str) -> String {
+    let open = placeholder.len() - placeholder.trim_start_matches('{').len();
+    let close = placeholder.len() - placeholder.trim_end_matches('}').len();
+    if open == 0 || open != close || open * 2 >= placeholder.len() {
+        return placeholder.to_string();
+    }
+    let inner = &placeholder[open..placeholder.len() - close];
+    let name = inner.split([',', '|']).next().unwrap_or(inner).trim();
+    format!("{}{}{}", "{".repeat(open), name, "}".repeat(close))
+}
+
+/// Tallies the placeholders of a value, in the order they first appear.
+fn placeholders<'a>(regex: &Regex, value: &'a str) -> Vec<Placeholder<'a>> {
+    let mut placeholders: Vec<Placeholder> = Vec::new();
+    for cap in regex.find_iter(value) {
+        let key = normalize(cap.as_str());
+        match placeholders.iter_mut().find(|p| p.key == key) {
+            Some(placeholder) => placeholder.count += 1,
+            None => placeholders.push(Placeholder {
+                text: cap.as_str(),
+                key,
+                count: 1,
+                offset: cap.start(),
+            }),
+        }
+    }
+    placeholders
+}
+
+/// Returns how many times a placeholder occurs, counting a repeated one once when repeats are
+/// ignored.
+fn occurrences(placeholders: &[Placeholder], key: &str, ignore_repeats: bool) -> usize {
+    let count = placeholders
+        .iter()
+        .find(|p| p.key == key)
+        .map_or(0, |p| p.count);
+    if ignore_repeats {
+        count.min(1)
+    } else {
+        count
+    }
+}
+
+/// Returns the placeholders of `expected` that `actual` has fewer of.
+fn unmatched<'a, 'b>(
+    expected: &'b [Placeholder<'a>],
+    actual: &[Placeholder],
+    ignore_repeats: bool,
+) -> Vec<&'b Placeholder<'a>> {
+    expected
+        .iter()
+        .filter(|p| {
+            occurrences(expected, &p.key, ignore_repeats)
+                > occurrences(actual, &p.key, ignore_repeats)
+        })
+        .collect()
+}
+
+/// Returns the characters placeholders open and close with, such as `{` and `}` for `{name}`.
+fn delimiters(placeholder: &str) -> (&str, &str) {
+    let open = placeholder.len()
+        - placeholder
+            .trim_start_matches(|c: char| !c.is_alphanumeric())
+            .len();
+    let close = placeholder.len()
+        - placeholder
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .len();
+    (
+        &placeholder[..open],
+        &placeholder[placeholder.len() - close..],
+    )
+}
+
+/// Returns the number of characters to insert, delete or replace to turn one string into another.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut previous: Vec<_> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(a != *b);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Returns the placeholders of a translation that are not in its source, each with the missing
+/// placeholder of the source with the same delimiters and the closest name, which it most likely
+/// is a translation or misspelling of.
+fn renamed<'a, 'b>(
+    extra: &[&'b Placeholder<'a>],
+    missing: &[&'b Placeholder<'a>],
+) -> Vec<(&'b Placeholder<'a>, &'b Placeholder<'a>)> {
+    let mut remaining = missing.to_vec();
+    let mut renamed = Vec::new();
+    for placeholder in extra {
+        let closest = remaining
+            .iter()
+            .enumerate()
+            .filter(|(_, source)| delimiters(&source.key) == delimiters(&placeholder.key))
+            .min_by_key(|(_, source)| edit_distance(&source.key, &placeholder.key))
+            .map(|(index, _)| index);
+        if let Some(index) = closest {
+            renamed.push((*placeholder, remaining.remove(index)));
+        }
+    }
+    renamed
+}
+
+fn find_missing_interpolations(params: InterpolationParams) -> Option<Diagnostic> {
+    if params.msgstr.value.is_empty() {
+        return None;
+    }
+    let msgid_placeholders = placeholders(params.pattern, params.msgid.value);
+    let msgstr_placeholders = placeholders(params.pattern, params.msgstr.value);
+    let missing = unmatched(
+        &msgid_placeholders,
+        &msgstr_placeholders,
+        params.ignore_repeats,
+    );
+    let first = missing.first()?;
+    let mut lines = Vec::new();
+    if let Some(msgctxt) = params.msgctxt {
+        lines.push(quoted("msgctxt", msgctxt));
+    }
+    let (line_index, column) = params.msgstr.position(0);
+    lines.push(located(
+        params.path,
+        params.msgid.position(first.offset),
+        &params.msgid.quoted(),
+    ));
+    lines.push(located(
+        params.path,
+        (line_index, column),
+        &params.msgstr.quoted(),
+    ));
+    let names: Vec<_> = missing.iter().map(|p| p.text).collect();
+    lines.push(format!("missing: {}", names.join(", ")));
+    Some(Diagnostic {
+        message: "Missing interpolation",
+        severity: Severity::Error,
+        context: Some(params.context()),
+        missing: names.iter().map(|name| name.to_string()).collect(),
+        unexpected: Vec::new(),
+        path: params.path.to_path_buf(),
+        line_index,
+        column,
+        lines,
+        rule: None,
+    })
+}
+
+/// Reports placeholders of the translation that the source does not have, such as misspelt ones,
+/// which fail at runtime like missing ones.
+fn find_extra_interpolations(params: InterpolationParams) -> Option<Diagnostic> {
+    let msgstr_placeholders = placeholders(params.pattern, params.msgstr.value);
+    // The translation may use as many of a placeholder as either source form of a plural entry.
+    let sources: Vec<_> = std::iter::once(params.msgid.value)
+        .chain(params.other_source)
+        .map(|source| placeholders(params.pattern, source))
+        .collect();
+    let extra: Vec<_> = msgstr_placeholders
+        .iter()
+        .filter(|p| {
+            let allowed = sources
+                .iter()
+                .map(|source| occurrences(source, &p.key, params.ignore_repeats))
+                .max()
+                .unwrap_or(0);
+            occurrences(&msgstr_placeholders, &p.key, params.ignore_repeats) > allowed
+        })
+        .collect();
+    let first = extra.first()?;
+    let mut lines = Vec::new();
+    if let Some(msgctxt) = params.msgctxt {
+        lines.push(quoted("msgctxt", msgctxt));
+    }
+    let (line_index, column) = params.msgstr.position(first.offset);
+    lines.push(located(
+        params.path,
+        params.msgid.position(0),
+        &params.msgid.quoted(),
+    ));
+    lines.push(located(
+        params.path,
+        (line_index, column),
+        &params.msgstr.quoted(),
+    ));
+    let names: Vec<_> = extra.iter().map(|p| p.text.to_string()).collect();
+    lines.push(format!("unexpected: {}", names.join(", ")));
+    let msgid_placeholders = placeholders(params.pattern, params.msgid.value);
+    let missing = unmatched(
+        &msgid_placeholders,
+        &msgstr_placeholders,
+        params.ignore_repeats,
+    );
+    for (placeholder, source) in renamed(&extra, &missing) {
+        lines.push(format!(
+            "{} looks like a translation or misspelling of {}, names must be kept as they are",
+            placeholder.text, source.text
+        ));
+    }
+    Some(Diagnostic {
+        message: "Extra interpolation",
+        severity: Severity::Error,
+        context: Some(params.context()),
+        missing: Vec::new(),
+        unexpected: names,
+        path: params.path.to_path_buf(),
+        line_index,
+        column,
+        lines,
+        rule: None,
+    })
+}
+
+/// What a check of entries is given besides the entry.
+pub struct CheckContext<'a> {
+    /// The path of the catalog
+    pub path: &'a Path,
+    pub options: &'a CheckOptions,
+    /// The language the header of the catalog declares
+    pub language: Option<&'a str>,
+}
+
+/// A rule checking the entries of catalogs, which library users can implement and add to
+/// `CheckOptions::rules`.
+///
+/// Entries are checked unless they are fuzzy. Diagnostics are reported with the code and name of
+/// the rule, whose settings and suppressions apply to them.
+pub trait Rule: Send + Sync {
+    /// The code, name and severity the diagnostics of the rule are reported with, one of
+    /// `rules::RULES` or its own
+    fn info(&self) -> &'static RuleInfo;
+    fn check(&self, entry: &Entry, ctx: &CheckContext) -> Vec<Diagnostic>;
+}
+
+/// Checks the placeholders of each translation of an entry with the pattern of the options.
+fn check_interpolations(
+    entry: &Entry,
+    ctx: &CheckContext,
+    find: fn(InterpolationParams) -> Option<Diagnostic>,
+) -> Vec<Diagnostic> {
+    let Some(pattern) = &ctx.options.pattern else {
+        return Vec::new();
+    };
+    if !is_checked_by_pattern(ctx.options, entry) {
+        return Vec::new();
+    }
+    entry
+        .msgstr
+        .iter()
+        .filter_map(|msgstr| {
+            find(InterpolationParams {
+                path: ctx.path,
+                pattern,
+                msgctxt: entry.msgctxt.as_deref(),
+                other_source: match msgstr.index {
+                    0 => entry.msgid_plural.as_deref(),
+                    _ => entry.msgid_plural.as_ref().map(|_| entry.msgid.as_str()),
+                },
+                msgid: entry.source(msgstr),
+                msgstr: entry.translation(msgstr),
+                ignore_repeats: ctx.options.ignore_repeats,
+            })
+        })
+        .collect()
+}
+
+/// Reports the placeholders of the source that a translation lacks, with the pattern of the
+/// options.
+pub struct MissingInterpolations;
+
+impl Rule for MissingInterpolations {
+    fn info(&self) -> &'static RuleInfo {
+        // PO001 missing-interpolation
+        &rules::RULES[0]
+    }
+
+    fn check(&self, entry: &Entry, ctx: &CheckContext) -> Vec<Diagnostic> {
+        check_interpolations(entry, ctx, find_missing_interpolations)
+    }
+}
+
+/// Reports the placeholders of a translation that its source lacks, with the pattern of the
+/// options.
+pub struct ExtraInterpolations;
+
+impl Rule for ExtraInterpolations {
+    fn info(&self) -> &'static RuleInfo {
+        // PO002 extra-interpolation
+        &rules::RULES[1]
+    }
+
+    fn check(&self, entry: &Entry, ctx: &CheckContext) -> Vec<Diagnostic> {
+        check_interpolations(entry, ctx, find_extra_interpolations)
+    }
+}
+
+/// Whether the placeholders of an entry are checked with the pattern of the options.
+///
+/// Entries in a format language whose directives are in braces have their placeholders checked by
+/// its rules instead, which avoids reporting them twice, or taking the branches of ICU arguments
+/// for placeholders. Those of other languages, such as c-format, may still have placeholders the
+/// pattern matches besides their directives.
+fn is_checked_by_pattern(options: &CheckOptions, entry: &Entry) -> bool {
+    !Format::ALL
+        .iter()
+        .any(|format| format.uses_braces() && format.applies(entry, options.default_format))
+}
+
+/// Returns the rules checking entries that implement [`Rule`] and every run applies.
+pub fn default_rules() -> Vec<Arc<dyn Rule>> {
+    vec![
+        Arc::new(MissingInterpolations),
+        Arc::new(ExtraInterpolations),
+    ]
+}
+
+/// Reports the directives of a translation that do not match those of its source, for the format
+/// string languages the entry is flagged with.
+fn check_formats(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if msgstr.value.is_empty() {
+        return diagnostics;
+    }
+    let msgid = entry.source(msgstr);
+    let translation = entry.translation(msgstr);
+    let mut lines = Vec::new();
+    if let Some(msgctxt) = &entry.msgctxt {
+        lines.push(quoted("msgctxt", msgctxt));
+    }
+    lines.push(located(path, msgid.position(0), &msgid.quoted()));
+    for format in Format::ALL {
+        if !format.applies(entry, options.default_format) {
+            continue;
+        }
+        // The source may be the invalid one, when the entry is wrongly flagged.
+        let parsed = match format.parse(msgid.value) {
+            Ok(source) => match format.parse(translation.value) {
+                Ok(arguments) => Ok((source, arguments)),
+                Err(invalid) => Err((translation.position(invalid.offset), invalid)),
+            },
+            Err(invalid) => Err((msgid.position(invalid.offset), invalid)),
+        };
+        let (source, arguments) = match parsed {
+            Ok(parsed) => parsed,
+            Err(((line_index, column), invalid)) => {
+                let mut lines = lines.clone();
+                lines.push(located(
+                    path,
+                    translation.position(0),
+                    &translation.quoted(),
+                ));
+                lines.push(format!(
+                    "{}: {}: {}",
+                    format.flag(),
+                    invalid.directive,
+                    invalid.reason
+                ));
+                diagnostics.push(Diagnostic {
+                    message: "Invalid format directive",
+                    severity: Severity::Error,
+                    context: None,
+                    missing: Vec::new(),
+                    unexpected: Vec::new(),
+                    path: path.to_path_buf(),
+                    line_index,
+                    column,
+                    lines,
+                    rule: None,
+                });
+                continue;
+            }
+        };
+        let mismatches = format::compare(&source, &arguments, entry.msgid_plural.is_some());
+        let Some(first) = mismatches.first() else {
+            continue;
+        };
+        let (line_index, column) = translation.position(first.offset.unwrap_or(0));
+        let mut lines = lines.clone();
+        lines.push(located(path, (line_index, column), &translation.quoted()));
+        for mismatch in &mismatches {
+            lines.push(format!("{}: {}", format.flag(), mismatch.description));
+        }
+        diagnostics.push(Diagnostic {
+            message: "Format mismatch",
+            severity: Severity::Error,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: path.to_path_buf(),
+            line_index,
+            column,
+            lines,
+            rule: None,
+        });
+    }
+    diagnostics
+}
+
+/// Reports the HTML tags a translation dropped, added or left unbalanced.
+fn check_tags(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Option<Diagnostic> {
+    if msgstr.value.is_empty() {
+        return None;
+    }
+    let msgid = entry.source(msgstr);
+    let translation = entry.translation(msgstr);
+    let source_tags = markup::tags(msgid.value);
+    let tags = markup::tags(translation.value);
+    let removed = markup::difference(&tags, &source_tags);
+    let added = markup::difference(&source_tags, &tags);
+    // Sources may hold fragments of markup, whose translations are unbalanced just as much.
+    let unbalanced = if markup::unbalanced(&source_tags).is_empty() {
+        markup::unbalanced(&tags)
+    } else {
+        Vec::new()
+    };
+    let first = added.iter().chain(&unbalanced).map(|tag| tag.offset).min();
+    if removed.is_empty() && first.is_none() {
+        return None;
+    }
+    let mut details = Vec::new();
+    for (label, tags) in [
+        ("removed", removed),
+        ("added", added),
+        ("unbalanced", unbalanced),
+    ] {
+        if !tags.is_empty() {
+            let tags: Vec<_> = tags.iter().map(|tag| tag.text).collect();
+            details.push(format!("{}: {}", label, tags.join(" ")));
+        }
+    }
+    let offset = first.unwrap_or(0);
+    Some(translation_diagnostic(
+        "Tag mismatch",
+        path,
+        entry,
+        msgstr,
+        offset,
+        details,
+    ))
+}
+
+/// Returns what the detail lines starting with a prefix, such as `missing: `, list.
+fn prefixed(lines: &[String], prefix: &str) -> Vec<String> {
+    lines
+        .iter()
+        .filter_map(|line| line.strip_prefix(prefix))
+        .map(String::from)
+        .collect()
+}
+
+/// Reports a problem of a translation at an offset of it, showing the entry it is in, with the
+/// severity of the rule of the message.
+fn translation_diagnostic(
+    message: &'static str,
+    path: &Path,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+    offset: usize,
+    details: Vec<String>,
+) -> Diagnostic {
+    let msgid = entry.source(msgstr);
+    let translation = entry.translation(msgstr);
+    let mut lines = Vec::new();
+    if let Some(msgctxt) = &entry.msgctxt {
+        lines.push(quoted("msgctxt", msgctxt));
+    }
+    let (line_index, column) = translation.position(offset);
+    lines.push(located(path, msgid.position(0), &msgid.quoted()));
+    lines.push(located(path, (line_index, column), &translation.quoted()));
+    lines.extend(details);
+    Diagnostic {
+        message,
+        severity: rules::of(message).map_or(Severity::Error, |rule| rule.severity),
+        context: Some(Context {
+            msgctxt: entry.msgctxt.clone(),
+            msgid: msgid.value.to_string(),
+            msgstr: Some(translation.value.to_string()),
+            references: Vec::new(),
+        }),
+        missing: prefixed(&lines, "missing: "),
+        unexpected: prefixed(&lines, "unexpected: "),
+        path: path.to_path_buf(),
+        line_index,
+        column,
+        lines,
+        rule: None,
+    }
+}
+
+/// Reports a translation that does not keep the Markdown structure of its source: its code spans
+/// and link destinations unchanged, as much emphasis, and its lines starting with the same list,
+/// heading and quote markers.
+fn check_markdown(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Option<Diagnostic> {
+    if msgstr.value.is_empty() {
+        return None;
+    }
+    let source = entry.source(msgstr).value;
+    let expected = markdown::elements(source);
+    let actual = markdown::elements(&msgstr.value);
+    let missing = markdown::difference(&actual, &expected);
+    let unexpected = markdown::difference(&expected, &actual);
+    let mut details: Vec<_> = missing
+        .iter()
+        .map(|element| format!("missing: {}", element.describe()))
+        .chain(
+            unexpected
+                .iter()
+                .map(|element| format!("unexpected: {}", element.describe())),
+        )
+        .collect();
+    let expected_markers = markdown::block_markers(source);
+    let actual_markers = markdown::block_markers(&msgstr.value);
+    if expected_markers != actual_markers {
+        let list = |markers: &[String]| {
+            if markers.is_empty() {
+                String::from("no marker")
+            } else {
+                markers.join(", ")
+            }
+        };
+        details.push(format!(
+            "the lines of the source start with {}, but those of the translation with {}",
+            list(&expected_markers),
+            list(&actual_markers)
+        ));
+    }
+    if details.is_empty() {
+        return None;
+    }
+    let offset = unexpected.first().map_or(0, |element| element.offset);
+    Some(translation_diagnostic(
+        "Markdown mismatch",
+        path,
+        entry,
+        msgstr,
+        offset,
+        details,
+    ))
+}
+
+/// Reports a translation that drops or changes the URLs and email addresses of its source.
+fn check_links(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Option<Diagnostic> {
+    if msgstr.value.is_empty() {
+        return None;
+    }
+    let expected = links::links(entry.source(msgstr).value);
+    let actual = links::links(&msgstr.value);
+    let missing = links::difference(&actual, &expected);
+    let unexpected = links::difference(&expected, &actual);
+    if missing.is_empty() && unexpected.is_empty() {
+        return None;
+    }
+    let details = missing
+        .iter()
+        .map(|link| format!("missing: {}", link.text))
+        .chain(
+            unexpected
+                .iter()
+                .map(|link| format!("unexpected: {}", link.text)),
+        )
+        .collect();
+    let offset = unexpected.first().map_or(0, |link| link.offset);
+    Some(translation_diagnostic(
+        "Link mismatch",
+        path,
+        entry,
+        msgstr,
+        offset,
+        details,
+    ))
+}
+
+/// Reports a translation that drops or changes the numbers of its source, such as prices, versions
+/// or durations, those of placeholders aside.
+fn check_numbers(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+) -> Option<Diagnostic> {
+    if msgstr.value.is_empty() {
+        return None;
+    }
+    let source = prose(options, entry, entry.source(msgstr).value);
+    let translation = prose(options, entry, &msgstr.value);
+    let expected = numbers::numbers(&source);
+    let actual = numbers::numbers(&translation);
+    let missing = numbers::difference(&actual, &expected);
+    let unexpected = numbers::difference(&expected, &actual);
+    if missing.is_empty() && unexpected.is_empty() {
+        return None;
+    }
+    let details = missing
+        .iter()
+        .map(|number| format!("missing: {}", number.text))
+        .chain(
+            unexpected
+                .iter()
+                .map(|number| format!("unexpected: {}", number.text)),
+        )
+        .collect();
+    let offset = unexpected.first().map_or(0, |number| number.offset);
+    Some(translation_diagnostic(
+        "Number mismatch",
+        path,
+        entry,
+        msgstr,
+        offset,
+        details,
+    ))
+}
+
+/// Reports a translation of a date format that drops, adds or breaks `strftime` directives, such
+/// as `%Y` or `%H:%M`, which the locale may only reorder, pad or write with other digits.
+///
+/// Entries in a format language using `%` directives of their own are left to its rules.
+fn check_strftime(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+) -> Option<Diagnostic> {
+    let source = entry.source(msgstr).value;
+    if msgstr.value.is_empty() || !strftime::is_date_format(source) {
+        return None;
+    }
+    let percent = [Format::C, Format::Python, Format::Qt, Format::Ruby];
+    if percent
+        .iter()
+        .any(|format| format.applies(entry, options.default_format))
+    {
+        return None;
+    }
+    let expected = strftime::tokens(source);
+    let actual = strftime::tokens(&msgstr.value);
+    if let Some(invalid) = actual.iter().find(|token| !token.is_valid()) {
+        let details = vec![format!("{}: unknown conversion", invalid.text)];
+        return Some(translation_diagnostic(
+            "Invalid date format directive",
+            path,
+            entry,
+            msgstr,
+            invalid.offset,
+            details,
+        ));
+    }
+    let missing = strftime::difference(&actual, &expected);
+    let unexpected = strftime::difference(&expected, &actual);
+    if missing.is_empty() && unexpected.is_empty() {
+        return None;
+    }
+    let details = missing
+        .iter()
+        .map(|token| format!("missing: {}", token.text))
+        .chain(
+            unexpected
+                .iter()
+                .map(|token| format!("unexpected: {}", token.text)),
+        )
+        .collect();
+    let offset = unexpected.first().map_or(0, |token| token.offset);
+    Some(translation_diagnostic(
+        "Date format mismatch",
+        path,
+        entry,
+        msgstr,
+        offset,
+        details,
+    ))
+}
+
+/// Reports the invisible characters of a translation that its source does not have, which are
+/// often pasted in from translation tools.
+fn check_invisible(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Option<Diagnostic> {
+    let source = entry.source(msgstr).value;
+    let found: Vec<_> = msgstr
+        .value
+        .char_indices()
+        .filter(|(_, c)| !source.contains(*c))
+        .filter_map(|(offset, c)| Some((offset, c, invisible::name(c)?)))
+        .collect();
+    let (offset, _, _) = found.first()?;
+    let details = found
+        .iter()
+        .map(|(_, c, name)| format!("U+{:04X} ({})", u32::from(*c), name))
+        .collect();
+    Some(translation_diagnostic(
+        "Invisible character",
+        path,
+        entry,
+        msgstr,
+        *offset,
+        details,
+    ))
+}
+
+/// Reports a translation that is not in Normalization Form C, such as one with accents written as
+/// combining marks, which compares unequal to the same text in that form.
+fn check_nfc(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Option<Diagnostic> {
+    let normalized = normalization::nfc(&msgstr.value);
+    if normalized == msgstr.value {
+        return None;
+    }
+    let mut details = vec![String::from(
+        "the translation is not in Normalization Form C",
+    )];
+    if normalization::has_precomposed(&msgstr.value) {
+        details.push(String::from(
+            "it mixes precomposed and decomposed characters",
+        ));
+    }
+    let offset = normalization::first_difference(&msgstr.value, &normalized);
+    Some(translation_diagnostic(
+        "Unnormalized translation",
+        path,
+        entry,
+        msgstr,
+        offset,
+        details,
+    ))
+}
+
+/// A correction made to an entry of a catalog.
+pub struct Repair {
+    pub line_index: u32,
+    pub description: String,
+}
+
+/// Replaces the cursor marks of a translation with the placeholders of its source that it lacks,
+/// in their order in the source, returning those that were inserted.
+fn insert_placeholders(options: &CheckOptions, source: &str, value: &mut String) -> Vec<String> {
+    let cursor = options.cursor.as_str();
+    let Some(regex) = options.pattern.as_ref().filter(|_| value.contains(cursor)) else {
+        return Vec::new();
+    };
+    let expected = placeholders(regex, source);
+    let actual = placeholders(regex, value);
+    let missing: Vec<String> = unmatched(&expected, &actual, options.ignore_repeats)
+        .iter()
+        .map(|placeholder| placeholder.text.to_string())
+        .collect();
+    let mut inserted = Vec::new();
+    for placeholder in missing {
+        let Some(offset) = value.find(cursor) else {
+            break;
+        };
+        value.replace_range(offset..offset + cursor.len(), &placeholder);
+        inserted.push(placeholder);
+    }
+    inserted
+}
+
+/// Returns a translation with the leading and trailing whitespace of its source, newlines
+/// included, if it has other whitespace.
+fn restore_whitespace(source: &str, value: &str) -> Option<String> {
+    if source.trim().is_empty() || value.trim().is_empty() {
+        return None;
+    }
+    let leading = &source[..source.len() - source.trim_start().len()];
+    let trailing = &source[source.trim_end().len()..];
+    let restored = format!("{}{}{}", leading, value.trim(), trailing);
+    (restored != value).then_some(restored)
+}
+
+/// Rewrites a catalog with the corrections of the fix command, or only with its translations in
+/// Normalization Form C for --normalize, returning them.
+///
+/// The entries that had errors and that no correction changed are marked fuzzy, so that gettext
+/// does not use their translations. Nothing is written with --dry-run, nor with --diff, which
+/// returns the diff of the catalog with the corrections instead. With --interactive, each
+/// correction is made as `prompt` decides.
+pub fn fix_file(
+    path: &Path,
+    options: &CheckOptions,
+    broken: &HashSet<(Option<String>, String)>,
+    mut prompt: Option<&mut Prompt>,
+) -> io::Result<(Vec<Repair>, String)> {
+    let bytes = std::fs::read(path)?;
+    let mut catalog = catalog::Catalog::parse(bytes.as_slice())?;
+    let mut repairs = Vec::new();
+    for entry in &mut catalog.entries {
+        // The rest of a malformed entry is not parsed, and would be lost if it was written again.
+        if entry.malformed {
+            continue;
+        }
+        let repairable = options.fix
+            && !entry.is_header()
+            && !entry.obsolete
+            && !entry.is_fuzzy()
+            && is_filtered(options, entry);
+        let formatted = !is_checked_by_pattern(options, entry);
+        let mut changed = false;
+        // Why the entry is marked fuzzy, if it is.
+        let mut fuzzy = None;
+        let mut quit = false;
+        for index in 0..entry.msgstr.len() {
+            let keyword = entry.msgstr_keyword(&entry.msgstr[index]);
+            let source = entry.source(&entry.msgstr[index]).value.to_string();
+            let mut value = entry.msgstr[index].value.clone();
+            let mut descriptions = Vec::new();
+            if repairable && !value.is_empty() {
+                if !formatted {
+                    for placeholder in insert_placeholders(options, &source, &mut value) {
+                        descriptions
+                            .push(format!("inserted {} at {}", placeholder, options.cursor));
+                    }
+                }
+                if let Some(restored) = restore_whitespace(&source, &value) {
+                    value = restored;
+                    descriptions.push(String::from("restored the whitespace of the source"));
+                }
+            }
+            let normalized = normalization::nfc(&value);
+            if normalized != value {
+                value = normalized;
+                descriptions.push(String::from("normalized to NFC"));
+            }
+            if descriptions.is_empty() {
+                continue;
+            }
+            let mut description = format!("{}: {}", keyword, descriptions.join(", "));
+            if let Some(prompt) = prompt.as_mut() {
+                let proposal = Proposal {
+                    path,
+                    line_index: entry.line_index,
+                    msgctxt: entry.msgctxt.as_deref(),
+                    msgid: &entry.msgid,
+                    keyword: &keyword,
+                    old: &entry.msgstr[index].value,
+                    new: Some(&value),
+                    description: &description,
+                };
+                match prompt(&proposal)? {
+                    Decision::Accept => {}
+                    Decision::Skip => continue,
+                    Decision::Edit(translation) => {
+                        value = translation;
+                        description = format!("{}: edited", keyword);
+                    }
+                    Decision::Fuzzy => {
+                        fuzzy = Some("marked fuzzy");
+                        continue;
+                    }
+                    Decision::Quit => {
+                        quit = true;
+                        break;
+                    }
+                }
+            }
+            entry.msgstr[index].value = value;
+            changed = true;
+            repairs.push(Repair {
+                line_index: entry.line_index,
+                description,
+            });
+        }
+        let key = (entry.msgctxt.clone(), entry.msgid.clone());
+        let first = entry
+            .msgstr
+            .first()
+            .map(|msgstr| entry.msgstr_keyword(msgstr));
+        if let Some(keyword) = first
+            .filter(|_| repairable && !changed && !quit && fuzzy.is_none() && broken.contains(&key))
+        {
+            let description = "marked fuzzy, as it has errors";
+            let decision = match prompt.as_mut() {
+                Some(prompt) => prompt(&Proposal {
+                    path,
+                    line_index: entry.line_index,
+                    msgctxt: entry.msgctxt.as_deref(),
+                    msgid: &entry.msgid,
+                    keyword: &keyword,
+                    old: &entry.msgstr[0].value,
+                    new: None,
+                    description,
+                })?,
+                None => Decision::Accept,
+            };
+            match decision {
+                Decision::Accept => fuzzy = Some(description),
+                Decision::Edit(translation) => {
+                    entry.msgstr[0].value = translation;
+                    repairs.push(Repair {
+                        line_index: entry.line_index,
+                        description: format!("{}: edited", keyword),
+                    });
+                }
+                Decision::Quit => quit = true,
+                Decision::Skip | Decision::Fuzzy => {}
+            }
+        }
+        if let Some(description) = fuzzy {
+            entry.flags.push(String::from("fuzzy"));
+            repairs.push(Repair {
+                line_index: entry.line_index,
+                description: String::from(description),
+            });
+        }
+        if quit {
+            break;
+        }
+    }
+    if repairs.is_empty() || options.dry_run {
+        return Ok((repairs, String::new()));
+    }
+    if options.diff {
+        let (original, _) = catalog.encoding.decode_with_bom_removal(&bytes);
+        let path = path.to_string_lossy().replace('\\', "/");
+        let patch = diff::unified(&path, &original, &catalog.serialize());
+        return Ok((repairs, patch));
+    }
+    catalog.save(path)?;
+    Ok((repairs, String::new()))
+}
+
+/// Reports the sequences of a translation that look like UTF-8 text decoded with the wrong
+/// encoding, with the characters they were meant to be.
+fn check_mojibake(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Option<Diagnostic> {
+    let source = entry.source(msgstr).value;
+    let suspects: Vec<_> = mojibake::suspects(&msgstr.value)
+        .into_iter()
+        .filter(|suspect| !source.contains(suspect.text))
+        .collect();
+    let mut details: Vec<_> = suspects
+        .iter()
+        .map(|suspect| {
+            format!(
+                "'{}' looks like '{}' encoded twice",
+                suspect.text, suspect.original
+            )
+        })
+        .collect();
+    let replacement = msgstr
+        .value
+        .find('\u{fffd}')
+        .filter(|_| !source.contains('\u{fffd}'));
+    if replacement.is_some() {
+        details.push(String::from(
+            "U+FFFD (replacement character) stands for text that could not be decoded",
+        ));
+    }
+    let offset = suspects
+        .first()
+        .map(|suspect| suspect.offset)
+        .or(replacement)?;
+    Some(translation_diagnostic(
+        "Mojibake", path, entry, msgstr, offset, details,
+    ))
+}
+
+/// Reports the directional embeddings, overrides and isolates that a translation leaves open or
+/// closes without opening them.
+fn check_bidi_controls(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Option<Diagnostic> {
+    let unbalanced = bidi::unbalanced(&msgstr.value);
+    let first = unbalanced.first()?;
+    let details = unbalanced
+        .iter()
+        .map(|unbalanced| {
+            let state = if unbalanced.closing {
+                "closes nothing"
+            } else {
+                "is not closed"
+            };
+            format!(
+                "{} (U+{:04X}) {}",
+                bidi::name(unbalanced.control),
+                u32::from(unbalanced.control),
+                state
+            )
+        })
+        .collect();
+    Some(translation_diagnostic(
+        "Unbalanced directional control",
+        path,
+        entry,
+        msgstr,
+        first.offset,
+        details,
+    ))
+}
+
+/// Warns of the placeholders of a right-to-left translation that are next to right-to-left text
+/// without being isolated from it, such as with `FSI` and `PDI`, so that their value may be
+/// displayed in the wrong place when it is written from left to right.
+fn check_bidi_placeholders(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+) -> Option<Diagnostic> {
+    let value = &msgstr.value;
+    let unisolated: Vec<_> = placeholder_ranges(options, entry, value)
+        .into_iter()
+        .filter(|range| {
+            let before = value[..range.start].trim_end().chars().next_back();
+            let after = value[range.end..].trim_start().chars().next();
+            let isolated =
+                before.is_some_and(bidi::is_control) || after.is_some_and(bidi::is_control);
+            !isolated && (before.is_some_and(bidi::is_rtl) || after.is_some_and(bidi::is_rtl))
+        })
+        .collect();
+    let first = unisolated.first()?;
+    let details = unisolated
+        .iter()
+        .map(|range| {
+            format!(
+                "{} is next to right-to-left text, but not isolated from it",
+                &value[range.clone()]
+            )
+        })
+        .collect();
+    Some(translation_diagnostic(
+        "Unisolated placeholder",
+        path,
+        entry,
+        msgstr,
+        first.start,
+        details,
+    ))
+}
+
+/// Reports the departures of a French translation from the typography rules that are enabled.
+fn check_typography(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+    language: &str,
+) -> Option<Diagnostic> {
+    let placeholders = placeholder_ranges(options, entry, &msgstr.value);
+    let mut issues: Vec<_> = options
+        .typography
+        .iter()
+        .flat_map(|&rule| typography::check(rule, &msgstr.value, language, &placeholders))
+        .collect();
+    issues.sort_by_key(|issue| issue.offset);
+    let first = issues.first()?;
+    let details = issues
+        .iter()
+        .map(|issue| issue.description.clone())
+        .collect();
+    Some(translation_diagnostic(
+        "Typography mismatch",
+        path,
+        entry,
+        msgstr,
+        first.offset,
+        details,
+    ))
+}
+
+/// Returns the offsets of the accelerator markers of a string, a doubled marker being a literal
+/// one.
+fn accelerators(value: &str, marker: char) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut chars = value.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        if c != marker {
+            continue;
+        }
+        match chars.peek() {
+            Some(&(_, next)) if next == marker => {
+                chars.next();
+            }
+            Some(&(_, next)) if !next.is_whitespace() => offsets.push(offset),
+            _ => {}
+        }
+    }
+    offsets
+}
+
+/// Reports a translation that starts or ends with a newline or whitespace that its source does not
+/// have, or the other way around.
+///
+/// Newlines are reported apart, as gettext tools refuse to compile catalogs that differ on them.
+fn check_whitespace(path: &Path, entry: &Entry, msgstr: &parser::Msgstr) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if msgstr.value.is_empty() {
+        return diagnostics;
+    }
+    let msgid = entry.source(msgstr).value;
+    let translation = msgstr.value.as_str();
+    let leading = |value: &str| value.len() - value.trim_start().len();
+    let trailing = |value: &str| value.len() - value.trim_end().len();
+    let sides = [
+        (
+            "starts",
+            &msgid[..leading(msgid)],
+            &translation[..leading(translation)],
+            0,
+        ),
+        (
+            "ends",
+            &msgid[msgid.len() - trailing(msgid)..],
+            &translation[translation.len() - trailing(translation)..],
+            translation.len() - trailing(translation),
+        ),
+    ];
+    for (side, source, whitespace, offset) in sides {
+        let newline = |whitespace: &str| match side {
+            "starts" => whitespace.starts_with('\n'),
+            _ => whitespace.ends_with('\n'),
+        };
+        let (message, detail) = if newline(source) != newline(whitespace) {
+            let (with, without) = if newline(source) {
+                ("the source", "the translation")
+            } else {
+                ("the translation", "the source")
+            };
+            let detail = format!("{} {} with \\n, but {} does not", with, side, without);
+            ("Newline mismatch", detail)
+        } else if source != whitespace {
+            let detail = format!(
+                "{} with \"{}\", but with \"{}\" in the source",
+                side,
+                escape(whitespace),
+                escape(source)
+            );
+            ("Whitespace mismatch", detail)
+        } else {
+            continue;
+        };
+        diagnostics.push(translation_diagnostic(
+            message,
+            path,
+            entry,
+            msgstr,
+            offset,
+            vec![detail],
+        ));
+    }
+    diagnostics
+}
+
+/// Reports a translation that does not end with the punctuation mark of its source.
+fn check_punctuation(
+    path: &Path,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+    language: Option<&str>,
+) -> Option<Diagnostic> {
+    if msgstr.value.is_empty() {
+        return None;
+    }
+    let source = punctuation::ending(entry.source(msgstr).value, None);
+    let translation = punctuation::ending(&msgstr.value, language);
+    let detail = match (source, translation) {
+        (Some(source), Some(translation)) if source != translation => format!(
+            "the translation ends with '{}', but the source with '{}'",
+            translation, source
+        ),
+        (Some(source), None) if !punctuation::may_drop(source, language) => {
+            format!(
+                "the source ends with '{}', but the translation does not",
+                source
+            )
+        }
+        (None, Some(translation)) => {
+            format!(
+                "the translation ends with '{}', but the source does not",
+                translation
+            )
+        }
+        _ => return None,
+    };
+    let offset = msgstr.value.trim_end().len();
+    let offset = msgstr.value[..offset]
+        .char_indices()
+        .last()
+        .map_or(0, |(offset, _)| offset);
+    Some(translation_diagnostic(
+        "Punctuation mismatch",
+        path,
+        entry,
+        msgstr,
+        offset,
+        vec![detail],
+    ))
+}
+
+/// Returns a string with its placeholders, tags, brand names and protected terms blanked out,
+/// the rest of it keeping its offsets.
+fn prose(options: &CheckOptions, entry: &Entry, value: &str) -> String {
+    let blank = |text: &str| " ".repeat(text.len());
+    let mut text = value.to_string();
+    if let Some(regex) = &options.pattern {
+        text = regex
+            .replace_all(&text, |captures: &regex::Captures| blank(&captures[0]))
+            .into_owned();
+    }
+    for format in Format::ALL {
+        if !format.applies(entry, options.default_format) {
+            continue;
+        }
+        for argument in format.parse(value).unwrap_or_default() {
+            text = text.replace(&argument.directive, &blank(&argument.directive));
+        }
+    }
+    for tag in markup::tags(value) {
+        text = text.replace(tag.text, &blank(tag.text));
+    }
+    for brand in &options.brands {
+        text = text.replace(brand.as_str(), &blank(brand));
+    }
+    for protected in &options.protected_terms {
+        text = protected
+            .regex
+            .replace_all(&text, |captures: &regex::Captures| blank(&captures[0]))
+            .into_owned();
+    }
+    text
+}
+
+/// Returns where the placeholders of a string are, found with the pattern and the format languages
+/// of the entry.
+fn placeholder_ranges(
+    options: &CheckOptions,
+    entry: &Entry,
+    value: &str,
+) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    if let Some(regex) = &options.pattern {
+        ranges.extend(regex.find_iter(value).map(|found| found.range()));
+    }
+    for format in Format::ALL {
+        // The directives of ICU arguments are written in a normalized form.
+        if format == Format::Icu || !format.applies(entry, options.default_format) {
+            continue;
+        }
+        for argument in format.parse(value).unwrap_or_default() {
+            ranges.push(argument.offset..argument.offset + argument.directive.len());
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+/// Whether a string has words to translate, once its placeholders, tags, numbers, brand names and
+/// protected terms are left out.
+fn has_words(options: &CheckOptions, entry: &Entry, value: &str) -> bool {
+    prose(options, entry, value)
+        .chars()
+        .any(char::is_alphabetic)
+}
+
+/// Reports a translation identical to its source, which is usually text copied through without
+/// being translated.
+fn check_same_as_source(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+) -> Option<Diagnostic> {
+    let msgid = entry.source(msgstr);
+    if msgstr.value.is_empty() || msgstr.value != msgid.value {
+        return None;
+    }
+    if !has_words(options, entry, msgid.value) {
+        return None;
+    }
+    Some(translation_diagnostic(
+        "Same as source",
+        path,
+        entry,
+        msgstr,
+        0,
+        vec![String::from("the translation is a copy of the source")],
+    ))
+}
+
+/// The length of the shortest sources whose translations have their length checked, the length of
+/// a few words varying too much between languages.
+const MIN_RATIO_SOURCE_LENGTH: usize = 10;
+
+/// Reports a translation much shorter or longer than its source, which is often truncated, or too
+/// long to fit where its source is displayed.
+fn check_length_ratio(
+    path: &Path,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+    (min, max): (f64, f64),
+) -> Option<Diagnostic> {
+    let source = entry.source(msgstr).value.chars().count();
+    let translation = msgstr.value.chars().count();
+    if translation == 0 || source < MIN_RATIO_SOURCE_LENGTH {
+        return None;
+    }
+    let ratio = translation as f64 * 100.0 / source as f64;
+    if (min..=max).contains(&ratio) {
+        return None;
+    }
+    let details = vec![format!(
+        "the translation is {:.0}% of the length of the source, outside of {}% to {}%",
+        ratio, min, max
+    )];
+    Some(translation_diagnostic(
+        "Length mismatch",
+        path,
+        entry,
+        msgstr,
+        0,
+        details,
+    ))
+}
+
+/// Reports the words of a translation its language's dictionary does not have, as a warning.
+fn check_spelling(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+    spellchecker: &Spellchecker,
+    dictionary: &spelling::Dictionary,
+) -> Option<Diagnostic> {
+    let text = prose(options, entry, &msgstr.value);
+    let misspelled = spellchecker.misspelled(dictionary, &text);
+    let (offset, _) = misspelled.first()?;
+    let details = misspelled
+        .iter()
+        .map(|(_, word)| format!("'{}' is not in the {} dictionary", word, dictionary.name))
+        .collect();
+    Some(translation_diagnostic(
+        "Misspelled word",
+        path,
+        entry,
+        msgstr,
+        *offset,
+        details,
+    ))
+}
+
+/// Reports the protected terms of a source that its translation drops or alters.
+fn check_protected_terms(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+) -> Option<Diagnostic> {
+    if msgstr.value.is_empty() {
+        return None;
+    }
+    let source = entry.source(msgstr).value;
+    let mut details = Vec::new();
+    let mut offset = None;
+    for ProtectedTerm {
+        term,
+        regex,
+        any_case,
+    } in &options.protected_terms
+    {
+        let expected = regex.find_iter(source).count();
+        if regex.find_iter(&msgstr.value).count() >= expected {
+            continue;
+        }
+        // A term of another case is likely the altered one.
+        let altered = any_case
+            .find_iter(&msgstr.value)
+            .find(|found| found.as_str() != term);
+        match altered {
+            Some(altered) => {
+                offset.get_or_insert(altered.start());
+                details.push(format!(
+                    "'{}' must be kept as it is, but is written '{}'",
+                    term,
+                    altered.as_str()
+                ));
+            }
+            None => details.push(format!("'{}' must be kept as it is", term)),
+        }
+    }
+    if details.is_empty() {
+        return None;
+    }
+    Some(translation_diagnostic(
+        "Protected term mismatch",
+        path,
+        entry,
+        msgstr,
+        offset.unwrap_or(0),
+        details,
+    ))
+}
+
+/// Reports the terms of a source whose translation, as mandated by the glossary, is missing from
+/// the translation.
+fn check_glossary(
+    path: &Path,
+    glossary: &Glossary,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+    language: &str,
+) -> Option<Diagnostic> {
+    if msgstr.value.is_empty() {
+        return None;
+    }
+    let violations = glossary.violations(language, entry.source(msgstr).value, &msgstr.value);
+    if violations.is_empty() {
+        return None;
+    }
+    let details = violations
+        .iter()
+        .map(|violation| {
+            format!(
+                "'{}' must be translated as '{}'",
+                violation.source, violation.translation
+            )
+        })
+        .collect();
+    Some(translation_diagnostic(
+        "Glossary mismatch",
+        path,
+        entry,
+        msgstr,
+        0,
+        details,
+    ))
+}
+
+/// Reports a translation that has no accelerator, or more than one, when its source has one.
+fn check_accelerators(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    msgstr: &parser::Msgstr,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if msgstr.value.is_empty() {
+        return diagnostics;
+    }
+    let msgid = entry.source(msgstr);
+    for &marker in &options.accelerators {
+        if accelerators(msgid.value, marker).len() != 1 {
+            continue;
+        }
+        let offsets = accelerators(&msgstr.value, marker);
+        let (message, offset) = match offsets.as_slice() {
+            [_] => continue,
+            [] => ("Missing accelerator", 0),
+            [_, second, ..] => ("Duplicate accelerator", *second),
+        };
+        let details = vec![format!(
+            "'{}' marks {} accelerators, but one in the source",
+            marker,
+            offsets.len()
+        )];
+        diagnostics.push(translation_diagnostic(
+            message, path, entry, msgstr, offset, details,
+        ));
+    }
+    diagnostics
+}
+
+/// Reports a plural entry whose msgstr forms are not the ones the Plural-Forms header declares.
+fn check_plural_forms(path: &Path, entry: &Entry, nplurals: usize) -> Option<Diagnostic> {
+    entry.msgid_plural.as_ref()?;
+    let missing =
+        (0..nplurals).filter(|&index| entry.msgstr.iter().all(|msgstr| msgstr.index != index));
+    let missing: Vec<_> = missing.collect();
+    let extra: Vec<_> = entry
+        .msgstr
+        .iter()
+        .filter(|msgstr| msgstr.index >= nplurals)
+        .collect();
+    if missing.is_empty() && extra.is_empty() {
+        return None;
+    }
+    let mut lines = entry.lines();
+    lines.push(format!(
+        "Plural-Forms declares {} forms, but the entry has {}",
+        nplurals,
+        entry.msgstr.len()
+    ));
+    for index in missing {
+        lines.push(format!("missing: msgstr[{}]", index));
+    }
+    for msgstr in &extra {
+        lines.push(format!("unexpected: msgstr[{}]", msgstr.index));
+    }
+    Some(Diagnostic {
+        message: "Plural form count mismatch",
+        severity: Severity::Error,
+        context: None,
+        missing: Vec::new(),
+        unexpected: Vec::new(),
+        path: path.to_path_buf(),
+        line_index: extra
+            .first()
+            .map_or(entry.line_index, |msgstr| msgstr.line_index),
+        column: 1,
+        lines,
+        rule: None,
+    })
+}
+
+fn check_entry(
+    path: &Path,
+    options: &CheckOptions,
+    header: Option<&Header>,
+    entry: &Entry,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let language = header.and_then(Header::language);
+    let dictionary = options
+        .spellchecker
+        .as_ref()
+        .zip(language)
+        .and_then(|(spellchecker, language)| spellchecker.dictionary(language));
+    if entry.is_fuzzy() {
+        if options.fail_on_fuzzy {
+            diagnostics.push(Diagnostic {
+                message: "Fuzzy entry",
+                severity: Severity::Error,
+                context: None,
+                missing: Vec::new(),
+                unexpected: Vec::new(),
+                path: path.to_path_buf(),
+                line_index: entry.line_index,
+                column: 1,
+                lines: entry.lines(),
+                rule: None,
+            });
+        }
+        return;
+    }
+    if let Some(nplurals) = header.and_then(Header::nplurals) {
+        diagnostics.extend(check_plural_forms(path, entry, nplurals));
+    }
+    let untranslated = entry.msgstr.iter().any(|msgstr| msgstr.value.is_empty());
+    if untranslated && options.fail_on_untranslated {
+        diagnostics.push(Diagnostic {
+            message: "Untranslated entry",
+            severity: Severity::Error,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: path.to_path_buf(),
+            line_index: entry.line_index,
+            column: 1,
+            lines: entry.lines(),
+            rule: None,
+        });
+    }
+    let context = CheckContext {
+        path,
+        options,
+        language,
+    };
+    for rule in &options.rules {
+        diagnostics.extend(
+            rule.check(entry, &context)
+                .into_iter()
+                .map(|diagnostic| Diagnostic {
+                    rule: Some(rule.info()),
+                    ..diagnostic
+                }),
+        );
+    }
+    for msgstr in &entry.msgstr {
+        diagnostics.extend(check_formats(path, options, entry, msgstr));
+        diagnostics.extend(check_tags(path, entry, msgstr));
+        diagnostics.extend(check_strftime(path, options, entry, msgstr));
+        diagnostics.extend(check_accelerators(path, options, entry, msgstr));
+        diagnostics.extend(check_whitespace(path, entry, msgstr));
+        diagnostics.extend(check_invisible(path, entry, msgstr));
+        diagnostics.extend(check_mojibake(path, entry, msgstr));
+        diagnostics.extend(check_bidi_controls(path, entry, msgstr));
+        if let Some(language) = language.filter(|language| base_language(language) == "fr") {
+            diagnostics.extend(check_typography(path, options, entry, msgstr, language));
+        }
+        if language.is_some_and(bidi::is_rtl_language) {
+            diagnostics.extend(check_bidi_placeholders(path, options, entry, msgstr));
+        }
+        if options.nfc {
+            diagnostics.extend(check_nfc(path, entry, msgstr));
+        }
+        if options.links {
+            diagnostics.extend(check_links(path, entry, msgstr));
+        }
+        if options.numbers {
+            diagnostics.extend(check_numbers(path, options, entry, msgstr));
+        }
+        if options.markdown {
+            diagnostics.extend(check_markdown(path, entry, msgstr));
+        }
+        if options.punctuation {
+            diagnostics.extend(check_punctuation(path, entry, msgstr, language));
+        }
+        if let Some(length_ratio) = options.length_ratio {
+            diagnostics.extend(check_length_ratio(path, entry, msgstr, length_ratio));
+        }
+        if let (Some(glossary), Some(language)) = (&options.glossary, language) {
+            diagnostics.extend(check_glossary(path, glossary, entry, msgstr, language));
+        }
+        diagnostics.extend(check_protected_terms(path, options, entry, msgstr));
+        if let (Some(spellchecker), Some(dictionary)) = (&options.spellchecker, &dictionary) {
+            diagnostics.extend(check_spelling(
+                path,
+                options,
+                entry,
+                msgstr,
+                spellchecker,
+                dictionary,
+            ));
+        }
+        if options.same_as_source {
+            diagnostics.extend(check_same_as_source(path, options, entry, msgstr));
+        }
+    }
+}
+
+fn check_syntax(path: &Path, entry: &Entry, diagnostics: &mut Vec<Diagnostic>) {
+    for problem in &entry.problems {
+        diagnostics.push(Diagnostic {
+            message: problem.message,
+            severity: Severity::Error,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: path.to_path_buf(),
+            line_index: problem.line_index,
+            column: problem.column,
+            lines: vec![problem.text.clone()],
+            rule: None,
+        });
+    }
+}
+
+fn check_header(path: &Path, entry: &Entry, header: &Header, diagnostics: &mut Vec<Diagnostic>) {
+    let msgstr = entry.msgstr.first();
+    for problem in header.problems() {
+        let (message, name, lines) = match problem {
+            HeaderProblem::Missing(name) => (
+                "Missing header field",
+                name.to_string(),
+                vec![name.to_string()],
+            ),
+            HeaderProblem::Invalid(name, value) => {
+                let line = format!("{}: {}", name, value);
+                ("Invalid header field", name, vec![line])
+            }
+            HeaderProblem::Inconsistent(name, value, reason) => {
+                let line = format!("{}: {}", name, value);
+                ("Inconsistent header field", name, vec![line, reason])
+            }
+        };
+        // The revision date is only informative, and tools that write catalogs may leave it out.
+        let severity = match message {
+            "Missing header field" if name == "PO-Revision-Date" => Severity::Warning,
+            _ => Severity::Error,
+        };
+        // Point at the field in the header msgstr, or at the header itself when it is missing.
+        let (line_index, column) = msgstr
+            .and_then(|msgstr| {
+                let offset = msgstr.value.find(&format!("{}:", name))?;
+                Some(msgstr.segments.position(offset))
+            })
+            .unwrap_or((entry.line_index, 1));
+        diagnostics.push(Diagnostic {
+            message,
+            severity,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: path.to_path_buf(),
+            line_index,
+            column,
+            lines,
+            rule: None,
+        });
+    }
+}
+
+/// Warns that the translations of a catalog cannot be spellchecked, having no dictionary for their
+/// language.
+fn check_dictionary(
+    path: &Path,
+    options: &CheckOptions,
+    entry: &Entry,
+    header: &Header,
+) -> Option<Diagnostic> {
+    let spellchecker = options.spellchecker.as_ref()?;
+    let language = header.language()?;
+    if spellchecker.dictionary(language).is_some() {
+        return None;
+    }
+    Some(Diagnostic {
+        message: "Missing dictionary",
+        severity: Severity::Warning,
+        context: None,
+        missing: Vec::new(),
+        unexpected: Vec::new(),
+        path: path.to_path_buf(),
+        line_index: entry.line_index,
+        column: 1,
+        lines: vec![format!(
+            "no Hunspell dictionary for {} in {}",
+            language,
+            spellchecker.dir().display()
+        )],
+        rule: None,
+    })
+}
+
+/// Reports an entry with the same msgctxt and msgid as one seen earlier in the file.
+///
+/// Entries without a msgid, a msgstr missing one, have no position to report and are left to
+/// `--strict-syntax`, rather than taken for duplicates of the header.
+fn check_duplicate(
+    path: &Path,
+    entry: &Entry,
+    seen: &mut HashMap<(Option<String>, String), (u32, u32)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if entry.msgid_segments.is_empty() {
+        return;
+    }
+    let position = entry.msgid_segments.position(0);
+    match seen.entry((entry.msgctxt.clone(), entry.msgid.clone())) {
+        hash_map::Entry::Vacant(vacant) => {
+            vacant.insert(position);
+        }
+        hash_map::Entry::Occupied(occupied) => {
+            let (line_index, column) = position;
+            let msgid = quoted("msgid", &entry.msgid);
+            let mut lines = Vec::new();
+            if let Some(msgctxt) = &entry.msgctxt {
+                lines.push(quoted("msgctxt", msgctxt));
+            }
+            lines.push(located(path, *occupied.get(), &msgid));
+            lines.push(located(path, position, &msgid));
+            diagnostics.push(Diagnostic {
+                message: "Duplicate entry",
+                severity: Severity::Error,
+                context: None,
+                missing: Vec::new(),
+                unexpected: Vec::new(),
+                path: path.to_path_buf(),
+                line_index,
+                column,
+                lines,
+                rule: None,
+            });
+        }
+    }
+}
+
+/// Reports an error that stopped the reading of a file.
+fn unreadable(path: &Path, line_index: u32, error: io::Error) -> Diagnostic {
+    Diagnostic {
+        message: "Unreadable file",
+        severity: Severity::Error,
+        context: None,
+        missing: Vec::new(),
+        unexpected: Vec::new(),
+        path: path.to_path_buf(),
+        line_index,
+        column: 1,
+        lines: vec![error.to_string()],
+        rule: None,
+    }
+}
+
+/// Checks a catalog, read from `input` rather than from its path when it is given, and returns
+/// its diagnostics sorted by line.
+pub fn check(path: &Path, input: Option<&[u8]>, options: &CheckOptions) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_file(path, input, options, &mut 0, &mut |diagnostic| {
+        diagnostics.push(diagnostic)
+    });
+    sort(&mut diagnostics);
+    diagnostics
+}
+
+/// Checks a file entry by entry, reporting diagnostics as they are found so that large catalogs
+/// are not held in memory.
+///
+/// The catalog is read from `input` rather than from its path when it is given, as it is for
+/// stdin. Returns the summary of the catalog, unless it is a template, and counts the entries
+/// checked, its header aside.
+pub fn check_file(
+    path: &Path,
+    input: Option<&[u8]>,
+    options: &CheckOptions,
+    entries: &mut usize,
+    report: &mut dyn FnMut(Diagnostic),
+) -> Option<Summary> {
+    let reader: Box<dyn BufRead> = match input {
+        Some(input) => Box::new(input),
+        None => match File::open(path) {
+            Ok(file) => Box::new(io::BufReader::new(file)),
+            Err(e) => {
+                report(unreadable(path, 1, e));
+                return None;
+            }
+        },
+    };
+    let mut stats = Stats {
+        files: 1,
+        ..Stats::default()
+    };
+    let mut translations = Vec::new();
+    let mut header = None;
+    // The language whose settings apply, that of the header once it is read.
+    let mut language = file_language(path);
+    let mut header_line_index = 1;
+    // The first plural entry, which needs the header to declare its plural forms.
+    let mut first_plural = None;
+    let mut seen = HashMap::new();
+    let mut line_index = 1;
+    // Templates have no translations yet, so only their syntax can be checked.
+    let template = path.extension().is_some_and(|extension| extension == "pot");
+    for entry in parser::Parser::new(reader).strict(options.strict_syntax) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let diagnostic = unreadable(path, line_index, e);
+                emit(options, language.as_deref(), vec![diagnostic], report);
+                return None;
+            }
+        };
+        line_index = entry.line_index;
+        // The header --filter leaves out is still read, for the entries it keeps.
+        let checked = is_filtered(options, &entry);
+        if entry.obsolete && !options.include_obsolete || !checked && !entry.is_header() {
+            continue;
+        }
+        // The header is the metadata of the catalog rather than one of its messages.
+        *entries += usize::from(checked && !entry.is_header());
+        let mut diagnostics = Vec::new();
+        check_syntax(path, &entry, &mut diagnostics);
+        if entry.malformed {
+            suppressions::apply(path, &entry, &mut diagnostics);
+            emit(options, language.as_deref(), diagnostics, report);
+            continue;
+        }
+        check_duplicate(path, &entry, &mut seen, &mut diagnostics);
+        if header.is_none() && entry.is_header() {
+            let msgstr = entry.msgstr.first().map_or("", |msgstr| &msgstr.value);
+            let parsed = Header::parse(msgstr);
+            if let Some(declared) = parsed.language().filter(|language| !language.is_empty()) {
+                language = Some(declared.to_string());
+            }
+            if !template {
+                check_header(path, &entry, &parsed, &mut diagnostics);
+                diagnostics.extend(check_dictionary(path, options, &entry, &parsed));
+            }
+            header = Some(parsed);
+            header_line_index = entry.line_index;
+        } else if !template {
+            if entry.msgid_plural.is_some() && first_plural.is_none() {
+                first_plural = Some(entry.line_index);
+            }
+            check_entry(path, options, header.as_ref(), &entry, &mut diagnostics);
+            if !entry.obsolete {
+                stats.count(&entry);
+                if options.cross_file {
+                    translations.extend(Translation::of(path, &entry));
+                }
+            }
+        }
+        suppressions::apply(path, &entry, &mut diagnostics);
+        for diagnostic in &mut diagnostics {
+            let context = diagnostic.context.get_or_insert_with(|| Context {
+                msgctxt: entry.msgctxt.clone(),
+                msgid: entry.msgid.clone(),
+                ..Context::default()
+            });
+            context.references = entry.references.clone();
+        }
+        if checked {
+            emit(options, language.as_deref(), diagnostics, report);
+        }
+    }
+    let mut diagnostics = Vec::new();
+    // Tools that extract messages do not always write a header, which gettext does without.
+    if header.is_none() && !template {
+        diagnostics.push(Diagnostic {
+            message: "Missing header",
+            severity: Severity::Warning,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: path.to_path_buf(),
+            line_index: 1,
+            column: 1,
+            lines: Vec::new(),
+            rule: None,
+        });
+    }
+    let plural_forms = header.as_ref().and_then(Header::nplurals);
+    let first_plural = first_plural.filter(|_| !template);
+    if let (Some(_), None, Some(plural_line_index)) = (&header, plural_forms, first_plural) {
+        diagnostics.push(Diagnostic {
+            message: "Missing header field",
+            severity: Severity::Error,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: path.to_path_buf(),
+            line_index: header_line_index,
+            column: 1,
+            lines: vec![
+                String::from("Plural-Forms"),
+                format!(
+                    "needed by the plural entry at {}:{}",
+                    path.display(),
+                    plural_line_index
+                ),
+            ],
+            rule: None,
+        });
+    }
+    // The problems of the header are not those of the entries --filter keeps.
+    if options.filter.is_none() {
+        emit(options, language.as_deref(), diagnostics, report);
+    }
+    if template {
+        return None;
+    }
+    Some(Summary {
+        language: language?,
+        stats,
+        translations,
+    })
+}
+
+/// Whether --filter keeps an entry, when its msgid or msgctxt matches.
+fn is_filtered(options: &CheckOptions, entry: &Entry) -> bool {
+    let Some(filter) = &options.filter else {
+        return true;
+    };
+    filter.is_match(&entry.msgid)
+        || entry
+            .msgctxt
+            .as_deref()
+            .is_some_and(|msgctxt| filter.is_match(msgctxt))
+}
+
+/// Reports the diagnostics of a catalog that the settings of its language keep.
+fn emit(
+    options: &CheckOptions,
+    language: Option<&str>,
+    diagnostics: Vec<Diagnostic>,
+    report: &mut dyn FnMut(Diagnostic),
+) {
+    diagnostics
+        .into_iter()
+        .filter_map(|diagnostic| options.settings.apply(diagnostic, language))
+        .for_each(report);
+}
+
+/// Returns the language of a catalog that declares none: the folder above `LC_MESSAGES` in the
+/// layout of gettext, as in `fr/LC_MESSAGES/app.po`, or else the name of the file.
+fn file_language(path: &Path) -> Option<String> {
+    let folder = path
+        .parent()
+        .filter(|parent| parent.file_name().is_some_and(|name| name == "LC_MESSAGES"))
+        .and_then(Path::parent)
+        .and_then(Path::file_name);
+    Some(folder.or(path.file_stem())?.to_string_lossy().into_owned())
+}
+
+/// Returns the Language header of a catalog, reading no further than its header, which is its
+/// first entry.
+fn header_language(path: &Path, input: Option<&[u8]>) -> Option<String> {
+    let reader: Box<dyn BufRead> = match input {
+        Some(input) => Box::new(input),
+        None => Box::new(io::BufReader::new(File::open(path).ok()?)),
+    };
+    let entry = parser::Parser::new(reader).next()?.ok()?;
+    let msgstr = entry.msgstr.first().filter(|_| entry.is_header())?;
+    Header::parse(&msgstr.value).language().map(String::from)
+}
+
+/// Whether a language is one given to --lang, which matches its regions when it has none, as
+/// `pt` does `pt_BR`, whether they are written with `-` or `_`.
+fn is_language(wanted: &str, language: &str) -> bool {
+    let normalized = |language: &str| language.replace('-', "_").to_lowercase();
+    let (wanted, language) = (normalized(wanted), normalized(language));
+    wanted == language || !wanted.contains('_') && base_language(&language) == wanted
+}
+
+/// Whether a catalog is of one of the languages given to --lang, by its Language header, or else
+/// by the folder above `LC_MESSAGES` or its name, as `fr` in `locales/fr/LC_MESSAGES/app.po` or
+/// `fr.po`.
+pub fn is_of_languages(path: &Path, input: Option<&[u8]>, languages: &[String]) -> bool {
+    let language = header_language(path, input)
+        .filter(|language| !language.is_empty())
+        .or_else(|| file_language(path));
+    language.is_some_and(|language| {
+        languages
+            .iter()
+            .any(|wanted| is_language(wanted, &language))
+    })
+}
+
+pub fn is_catalog(path: &Path) -> bool {
+    let extension = path.extension().and_then(|s| s.to_str());
+    path.is_file() && matches!(extension, Some("po" | "pot"))
+}
+
+/// Sorts diagnostics by path, line and column, those of a same place staying in the order they
+/// were found in.
+pub fn sort(diagnostics: &mut [Diagnostic]) {
+    diagnostics
+        .sort_by(|a, b| (&a.path, a.line_index, a.column).cmp(&(&b.path, b.line_index, b.column)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "msgid \"\"\nmsgstr \"\"\n\"Language: fr\\n\"\n\"PO-Revision-Date: 2024-01-01 00:00+0000\\n\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\n";
+
+    /// Returns the messages of the diagnostics of a catalog, the header above its entries.
+    fn messages(entries: &str, options: &CheckOptions) -> Vec<&'static str> {
+        let text = format!("{}{}", HEADER, entries);
+        check(Path::new("fr.po"), Some(text.as_bytes()), options)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn tells_the_language_of_catalogs_by_their_header_first() {
+        let languages = [String::from("de")];
+        let is_german = |path: &str, header: &str| {
+            let text = format!("msgid \"\"\nmsgstr \"{}\"\n", header);
+            is_of_languages(Path::new(path), Some(text.as_bytes()), &languages)
+        };
+        assert!(is_german("locales/fr.po", "Language: de_AT\\n"));
+        assert!(!is_german("de/app/fr.po", "Language: fr\\n"));
+        assert!(is_german("locales/de/LC_MESSAGES/app.po", ""));
+        assert!(is_german("de.po", "Language: \\n"));
+        assert!(!is_german("de/app/messages.po", ""));
+        assert!(!is_german("de-tools/fr.po", ""));
+    }
+
+    #[test]
+    fn reports_a_missing_placeholder() {
+        let options = CheckOptions::default();
+        let entries = "msgid \"Hello {user}\"\nmsgstr \"Bonjour\"\n";
+        assert_eq!(messages(entries, &options), ["Missing interpolation"]);
+    }
+
+    #[test]
+    fn checks_the_pattern_of_c_format_entries() {
+        let options = CheckOptions::default();
+        let entries = "#, c-format\nmsgid \"%s sent {user}\"\nmsgstr \"%s envoyé\"\n";
+        assert_eq!(messages(entries, &options), ["Missing interpolation"]);
+    }
+
+    #[test]
+    fn reports_printf_directives_that_differ_from_the_source() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| {
+            format!(
+                "#, c-format\nmsgid \"%s has %d files\"\nmsgstr \"{}\"\n",
+                msgstr
+            )
+        };
+        assert!(messages(&entry("%s a %d fichiers"), &options).is_empty());
+        assert!(messages(&entry("%2$d fichiers dans %1$s"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("%s a %s fichiers"), &options),
+            ["Format mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("%s a %d fichiers à 100%"), &options),
+            ["Invalid format directive"]
+        );
+        let entry = "msgid \"%s has %d files\"\nmsgstr \"%s a %s fichiers\"\n";
+        assert!(messages(entry, &options).is_empty());
+    }
+
+    #[test]
+    fn reports_python_directives_that_differ_from_the_source() {
+        let options = CheckOptions::default();
+        let entry = |flag: &str, msgid: &str, msgstr: &str| {
+            format!("#, {}\nmsgid \"{}\"\nmsgstr \"{}\"\n", flag, msgid, msgstr)
+        };
+        let python = |msgstr| entry("python-format", "%(count)d new", msgstr);
+        assert!(messages(&python("%(count)d nouveaux"), &options).is_empty());
+        assert_eq!(
+            messages(&python("%(nombre)d nouveaux"), &options),
+            ["Format mismatch"]
+        );
+        assert_eq!(
+            messages(&python("%(count)d nouveaux, %s"), &options),
+            ["Invalid format directive"]
+        );
+        let brace = |msgstr| entry("python-brace-format", "{count} new", msgstr);
+        assert!(messages(&brace("{count} nouveaux"), &options).is_empty());
+        assert_eq!(
+            messages(&brace("{nombre} nouveaux"), &options),
+            ["Format mismatch"]
+        );
+        assert_eq!(
+            messages(&brace("{count nouveaux"), &options),
+            ["Invalid format directive"]
+        );
+    }
+
+    #[test]
+    fn reports_icu_arguments_that_differ_from_the_source() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| {
+            format!(
+                "msgid \"{{n, plural, one {{# file}} other {{# files}}}}\"\nmsgstr \"{}\"\n",
+                msgstr
+            )
+        };
+        let french = "{n, plural, one {# fichier} many {# de fichiers} other {# fichiers}}";
+        assert!(messages(&entry(french), &options).is_empty());
+        assert_eq!(
+            messages(
+                &entry("{n, select, one {# fichier} other {# fichiers}}"),
+                &options
+            ),
+            ["Format mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("{n, plural, one {# fichier}}"), &options),
+            ["Invalid format directive"]
+        );
+    }
+
+    #[test]
+    fn reports_tags_a_translation_drops_adds_or_unbalances() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"<b>Save</b> now\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("<b>Enregistrer</b> maintenant"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Enregistrer maintenant"), &options),
+            ["Tag mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("<i>Enregistrer</i> maintenant"), &options),
+            ["Tag mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("</b>Enregistrer<b> maintenant"), &options),
+            ["Tag mismatch"]
+        );
+        let fragment = "msgid \"<b>Note\"\nmsgstr \"<b>Remarque\"\n";
+        assert!(messages(fragment, &options).is_empty());
+    }
+
+    #[test]
+    fn skips_fuzzy_entries_unless_they_fail_the_run() {
+        let mut options = CheckOptions::default();
+        let entries = "#, fuzzy\nmsgid \"Hello {user}\"\nmsgstr \"Bonjour\"\n";
+        assert!(messages(entries, &options).is_empty());
+        options.fail_on_fuzzy = true;
+        assert_eq!(messages(entries, &options), ["Fuzzy entry"]);
+        assert!(messages("msgid \"Hello\"\nmsgstr \"Bonjour\"\n", &options).is_empty());
+    }
+
+    #[test]
+    fn checks_qt_markers_of_flagged_entries_or_of_the_qt_preset() {
+        let mut options = CheckOptions::default();
+        let entry = |flag: &str, msgstr: &str| {
+            format!("{}msgid \"%1 of %2\"\nmsgstr \"{}\"\n", flag, msgstr)
+        };
+        let qt = |msgstr| entry("#, qt-format\n", msgstr);
+        assert!(messages(&qt("%2 sur %L1"), &options).is_empty());
+        assert_eq!(messages(&qt("%1 sur %3"), &options), ["Format mismatch"]);
+        assert!(messages(&entry("", "%1 sur %3"), &options).is_empty());
+        options.default_format = Preset::Qt.format();
+        assert_eq!(
+            messages(&entry("", "%1 sur %3"), &options),
+            ["Format mismatch"]
+        );
+    }
+
+    #[test]
+    fn checks_ruby_interpolations_of_the_ruby_preset() {
+        let options = CheckOptions {
+            pattern: None,
+            default_format: Preset::Ruby.format(),
+            ..CheckOptions::default()
+        };
+        let entry = |msgstr: &str| format!("msgid \"Hello %{{user}}\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Bonjour %{user}"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Bonjour %{utilisateur}"), &options),
+            ["Format mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("Bonjour %{user} %s"), &options),
+            ["Invalid format directive"]
+        );
+    }
+
+    #[test]
+    fn checks_java_and_csharp_arguments_of_flagged_entries() {
+        let options = CheckOptions::default();
+        let entry = |flag: &str, msgstr: &str| {
+            format!(
+                "#, {}\nmsgid \"{{0}} of {{1}}\"\nmsgstr \"{}\"\n",
+                flag, msgstr
+            )
+        };
+        for flag in ["java-format", "csharp-format"] {
+            assert!(messages(&entry(flag, "{1} de {0}"), &options).is_empty());
+            assert_eq!(
+                messages(&entry(flag, "{0} sur {2}"), &options),
+                ["Format mismatch"]
+            );
+            assert_eq!(
+                messages(&entry(flag, "{0} sur {un}"), &options),
+                ["Invalid format directive"]
+            );
+        }
+        assert_eq!(
+            messages(&entry("java-format", "l'un {0} sur {1}"), &options),
+            ["Invalid format directive"]
+        );
+        assert!(messages(&entry("csharp-format", "l'un {0} sur {1}"), &options).is_empty());
+    }
+
+    #[test]
+    fn reports_translations_without_exactly_one_accelerator() {
+        let mut options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"&Save\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Enregistrer"), &options).is_empty());
+        options.accelerators = vec!['&'];
+        assert!(messages(&entry("&Enregistrer"), &options).is_empty());
+        assert!(messages(&entry("Enregistrer && &fermer"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Enregistrer"), &options),
+            ["Missing accelerator"]
+        );
+        assert_eq!(
+            messages(&entry("&Enregistrer &tout"), &options),
+            ["Duplicate accelerator"]
+        );
+        let entry = "msgid \"Save & close\"\nmsgstr \"Enregistrer\"\n";
+        assert!(messages(entry, &options).is_empty());
+    }
+
+    #[test]
+    fn reports_whitespace_and_newlines_that_differ_from_the_source() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \" Save\\n\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry(" Enregistrer\\n"), &options).is_empty());
+        assert_eq!(
+            messages(&entry(" Enregistrer"), &options),
+            ["Newline mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("Enregistrer\\n"), &options),
+            ["Whitespace mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("\\nEnregistrer \\n"), &options),
+            ["Newline mismatch", "Whitespace mismatch"]
+        );
+    }
+
+    #[test]
+    fn reports_translations_ending_with_other_punctuation() {
+        let mut options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"Saved.\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Enregistré."), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Enregistré"), &options),
+            ["Punctuation mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("Enregistré…"), &options),
+            ["Punctuation mismatch"]
+        );
+        let entry = "msgid \"Saved\"\nmsgstr \"Enregistré.\"\n";
+        assert_eq!(messages(entry, &options), ["Punctuation mismatch"]);
+        options.punctuation = false;
+        assert!(messages(entry, &options).is_empty());
+    }
+
+    #[test]
+    fn reports_untranslated_entries_when_they_fail_the_run() {
+        let mut options = CheckOptions::default();
+        let entries = "msgid \"Save\"\nmsgstr \"\"\n\nmsgid \"Open\"\nmsgstr \"Ouvrir\"\n";
+        assert!(messages(entries, &options).is_empty());
+        options.fail_on_untranslated = true;
+        assert_eq!(messages(entries, &options), ["Untranslated entry"]);
+    }
+
+    #[test]
+    fn reports_translations_copied_from_their_source() {
+        let mut options = CheckOptions::default();
+        let copy = "msgid \"Settings\"\nmsgstr \"Settings\"\n";
+        assert!(messages(copy, &options).is_empty());
+        options.same_as_source = true;
+        assert_eq!(messages(copy, &options), ["Same as source"]);
+        assert!(messages("msgid \"Settings\"\nmsgstr \"Réglages\"\n", &options).is_empty());
+        assert!(messages(
+            "msgid \"{count} / 100\"\nmsgstr \"{count} / 100\"\n",
+            &options
+        )
+        .is_empty());
+        options.brands = vec![String::from("GitHub")];
+        assert!(messages("msgid \"GitHub\"\nmsgstr \"GitHub\"\n", &options).is_empty());
+    }
+
+    #[test]
+    fn reports_translations_much_shorter_or_longer_than_their_source() {
+        let mut options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"Delete all files\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Effacer"), &options).is_empty());
+        options.length_ratio = Some((50.0, 200.0));
+        assert!(messages(&entry("Supprimer les fichiers"), &options).is_empty());
+        assert_eq!(messages(&entry("Effacer"), &options), ["Length mismatch"]);
+        assert_eq!(
+            messages(
+                &entry("Supprimer définitivement tous les fichiers du dossier"),
+                &options
+            ),
+            ["Length mismatch"]
+        );
+        let short = "msgid \"Delete\"\nmsgstr \"Supprimer définitivement\"\n";
+        assert!(messages(short, &options).is_empty());
+    }
+
+    #[test]
+    fn reports_plural_entries_without_the_declared_forms() {
+        let options = CheckOptions::default();
+        let header = HEADER.replace(
+            "\"Language: fr\\n\"\n",
+            "\"Language: fr\\n\"\n\"Plural-Forms: nplurals=2; plural=(n > 1);\\n\"\n",
+        );
+        let messages = |forms: &str| {
+            let text = format!(
+                "{}msgid \"{{n}} file\"\nmsgid_plural \"{{n}} files\"\n{}",
+                header, forms
+            );
+            check(Path::new("fr.po"), Some(text.as_bytes()), &options)
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect::<Vec<_>>()
+        };
+        let two = "msgstr[0] \"{n} fichier\"\nmsgstr[1] \"{n} fichiers\"\n";
+        assert!(messages(two).is_empty());
+        assert_eq!(
+            messages("msgstr[0] \"{n} fichier\"\n"),
+            ["Plural form count mismatch"]
+        );
+        let three = format!("{}msgstr[2] \"{{n}} de fichiers\"\n", two);
+        assert_eq!(messages(&three), ["Plural form count mismatch"]);
+    }
+
+    #[test]
+    fn reports_terms_translated_otherwise_than_the_glossary_mandates() {
+        let path =
+            std::env::temp_dir().join(format!("po-parser-glossary-{}.csv", std::process::id()));
+        std::fs::write(&path, "source,fr\nfile,fichier\n").unwrap();
+        let glossary = Glossary::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let options = CheckOptions {
+            glossary: Some(Arc::new(glossary)),
+            ..CheckOptions::default()
+        };
+        let entry = |msgstr: &str| format!("msgid \"Open a file\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Ouvrir un fichier"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Ouvrir un document"), &options),
+            ["Glossary mismatch"]
+        );
+    }
+
+    #[test]
+    fn reports_protected_terms_a_translation_drops_or_alters() {
+        let options = CheckOptions {
+            protected_terms: vec![ProtectedTerm::new("GitHub").unwrap()],
+            ..CheckOptions::default()
+        };
+        let details = |msgstr: &str| {
+            let text = format!(
+                "{}msgid \"Sign in with GitHub\"\nmsgstr \"{}\"\n",
+                HEADER, msgstr
+            );
+            let diagnostics = check(Path::new("fr.po"), Some(text.as_bytes()), &options);
+            diagnostics
+                .iter()
+                .flat_map(|diagnostic| &diagnostic.lines)
+                .filter(|line| line.contains("must be kept"))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert!(details("Se connecter avec GitHub").is_empty());
+        assert_eq!(
+            details("Se connecter avec Github"),
+            ["'GitHub' must be kept as it is, but is written 'Github'"]
+        );
+        assert_eq!(
+            details("Se connecter avec un compte"),
+            ["'GitHub' must be kept as it is"]
+        );
+        let entry = "msgid \"Sign in with GitHub\"\nmsgstr \"Se connecter avec Github\"\n";
+        assert_eq!(messages(entry, &options), ["Protected term mismatch"]);
+    }
+
+    #[test]
+    fn reports_misspelled_words_and_missing_dictionaries() {
+        let dir = std::env::temp_dir().join(format!("po-parser-spelling-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("fr.aff"), "SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n").unwrap();
+        std::fs::write(dir.join("fr.dic"), "2\nouvrir\nfichier/S\n").unwrap();
+        let options = CheckOptions {
+            spellchecker: Some(Arc::new(Spellchecker::new(dir.clone(), None).unwrap())),
+            ..CheckOptions::default()
+        };
+        let entry = |msgstr: &str| format!("msgid \"Open files\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Ouvrir fichiers"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Ouvrir fichers"), &options),
+            ["Misspelled word"]
+        );
+        let text = format!("{}{}", HEADER.replace("fr", "de"), entry("Dateien öffnen"));
+        let diagnostics = check(Path::new("de.po"), Some(text.as_bytes()), &options);
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message).collect();
+        assert_eq!(messages, ["Missing dictionary"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_translations_that_break_their_markdown() {
+        let mut options = CheckOptions::default();
+        let entry =
+            |msgstr: &str| format!("msgid \"See **the** [docs](/en)\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Voir **la** doc"), &options).is_empty());
+        options.markdown = true;
+        assert!(messages(&entry("Voir **la** [doc](/en)"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Voir **la** doc"), &options),
+            ["Markdown mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("Voir la [doc](/en)"), &options),
+            ["Markdown mismatch"]
+        );
+        let list = "msgid \"- Save\"\nmsgstr \"Enregistrer\"\n";
+        assert_eq!(messages(list, &options), ["Markdown mismatch"]);
+    }
+
+    #[test]
+    fn reports_translations_that_drop_or_change_links() {
+        let mut options = CheckOptions::default();
+        let entry = |msgstr: &str| {
+            format!(
+                "msgid \"Write to help@example.com\"\nmsgstr \"{}\"\n",
+                msgstr
+            )
+        };
+        assert!(messages(&entry("Écrivez à help@example.com"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Écrivez-nous"), &options),
+            ["Link mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("Écrivez à aide@example.com"), &options),
+            ["Link mismatch"]
+        );
+        options.links = false;
+        assert!(messages(&entry("Écrivez-nous"), &options).is_empty());
+    }
+
+    #[test]
+    fn reports_translations_that_drop_or_change_numbers() {
+        let mut options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"Up to 1,000 files\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Jusqu’à 100 fichiers"), &options).is_empty());
+        options.numbers = true;
+        assert!(messages(&entry("Jusqu’à 1\u{202f}000 fichiers"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Jusqu’à 100 fichiers"), &options),
+            ["Number mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("Des fichiers"), &options),
+            ["Number mismatch"]
+        );
+    }
+
+    #[test]
+    fn reports_translations_of_date_formats_that_change_their_directives() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"%m/%d/%Y %H:%M\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("%d/%m/%Y %Hh%M"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("%d/%m/%y %Hh%M"), &options),
+            ["Date format mismatch"]
+        );
+        assert_eq!(
+            messages(&entry("%d/%m/%Y %Hh%M %q"), &options),
+            ["Invalid date format directive"]
+        );
+        let printf = "#, c-format\nmsgid \"%d of %H\"\nmsgstr \"%d sur %H\"\n";
+        assert!(!messages(printf, &options).contains(&"Date format mismatch"));
+    }
+
+    #[test]
+    fn reports_invisible_characters_of_translations() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"Save\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Enregistrer\\tmaintenant"), &options).is_empty());
+        assert!(messages(&entry("می\u{200c}خواهم"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Enregis\u{200b}trer"), &options),
+            ["Invisible character"]
+        );
+        assert_eq!(
+            messages(&entry("\u{feff}Enregistrer"), &options),
+            ["Invisible character"]
+        );
+    }
+
+    #[test]
+    fn reports_translations_not_in_nfc() {
+        let mut options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"Cafe\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Caf\u{e9}"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Cafe\u{301}"), &options),
+            ["Unnormalized translation"]
+        );
+        options.nfc = false;
+        assert!(messages(&entry("Cafe\u{301}"), &options).is_empty());
+    }
+
+    #[test]
+    fn normalize_rewrites_translations_in_nfc() {
+        let path =
+            std::env::temp_dir().join(format!("po-parser-normalize-{}.po", std::process::id()));
+        std::fs::write(
+            &path,
+            format!("{}msgid \"Cafe\"\nmsgstr \"Cafe\u{301}\"\n", HEADER),
+        )
+        .unwrap();
+        let options = CheckOptions {
+            normalize: true,
+            ..CheckOptions::default()
+        };
+        let fixed = fix_file(&path, &options, &HashSet::new(), None);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(fixed.unwrap().0.len(), 1);
+        assert!(written.ends_with("msgstr \"Caf\u{e9}\"\n"));
+    }
+
+    #[test]
+    fn reports_mojibake_in_translations() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"Coffee\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Café"), &options).is_empty());
+        assert_eq!(messages(&entry("CafÃ©"), &options), ["Mojibake"]);
+    }
+
+    #[test]
+    fn checks_directional_controls_and_placeholders_of_rtl_catalogs() {
+        let options = CheckOptions::default();
+        let header = HEADER.replace("Language: fr", "Language: he");
+        let diagnostics = |msgstr: &str| {
+            let text = format!(
+                "{}msgid \"Hello {{user}}\"\nmsgstr \"{}\"\n",
+                header, msgstr
+            );
+            check(Path::new("he.po"), Some(text.as_bytes()), &options)
+                .into_iter()
+                .map(|diagnostic| (diagnostic.message, diagnostic.severity))
+                .collect::<Vec<_>>()
+        };
+        assert!(diagnostics("שלום \u{2068}{user}\u{2069}").is_empty());
+        assert_eq!(
+            diagnostics("שלום {user}"),
+            [("Unisolated placeholder", Severity::Warning)]
+        );
+        assert_eq!(
+            diagnostics("שלום \u{2068}{user}"),
+            [("Unbalanced directional control", Severity::Error)]
+        );
+        let entry = "msgid \"Hello {user}\"\nmsgstr \"Bonjour {user}\u{202c}\"\n";
+        assert_eq!(
+            messages(entry, &options),
+            ["Unbalanced directional control"]
+        );
+    }
+
+    #[test]
+    fn checks_the_typography_of_french_catalogs() {
+        let mut options = CheckOptions::default();
+        let entry = "msgid \"Ready?\"\nmsgstr \"Prêt ?\"\n";
+        assert_eq!(messages(entry, &options), ["Typography mismatch"]);
+        assert!(messages("msgid \"Ready?\"\nmsgstr \"Prêt\u{202f}?\"\n", &options).is_empty());
+        let text = format!(
+            "{}{}",
+            HEADER.replace("Language: fr", "Language: de"),
+            entry
+        );
+        assert!(check(Path::new("de.po"), Some(text.as_bytes()), &options).is_empty());
+        options
+            .typography
+            .retain(|&rule| rule != typography::Rule::Spacing);
+        assert!(messages(entry, &options).is_empty());
+    }
+
+    #[test]
+    fn suggests_the_placeholder_a_translated_one_stands_for() {
+        assert_eq!(edit_distance("user", "usr"), 1);
+        assert_eq!(edit_distance("count", "nombre"), 5);
+        assert_eq!(delimiters("{{ name }}"), ("{{ ", " }}"));
+        let text = format!(
+            "{}msgid \"{{user}} sent {{{{count}}}}\"\nmsgstr \"{{usr}} a envoyé {{{{nombre}}}}\"\n",
+            HEADER
+        );
+        let diagnostics = check(
+            Path::new("fr.po"),
+            Some(text.as_bytes()),
+            &CheckOptions::default(),
+        );
+        let extra = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.message == "Extra interpolation")
+            .unwrap();
+        let suggestions: Vec<_> = extra
+            .lines
+            .iter()
+            .filter(|line| line.contains("looks like"))
+            .collect();
+        assert_eq!(
+            suggestions,
+            [
+                "{usr} looks like a translation or misspelling of {user}, names must be kept as they are",
+                "{{nombre}} looks like a translation or misspelling of {{count}}, names must be kept as they are"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_placeholders_only_in_the_translation() {
+        let options = CheckOptions::default();
+        let entry = |msgstr: &str| format!("msgid \"Hello {{user}}\"\nmsgstr \"{}\"\n", msgstr);
+        assert!(messages(&entry("Bonjour {user}"), &options).is_empty());
+        assert_eq!(
+            messages(&entry("Bonjour {user} {date}"), &options),
+            ["Extra interpolation"]
+        );
+    }
+
+    #[test]
+    fn compares_placeholders_as_multisets() {
+        let mut options = CheckOptions::default();
+        let entry = "msgid \"{a} and {a} of {b}\"\nmsgstr \"{b} de {a}\"\n";
+        let text = format!("{}{}", HEADER, entry);
+        let diagnostics = check(Path::new("fr.po"), Some(text.as_bytes()), &options);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Missing interpolation");
+        assert_eq!(diagnostics[0].missing, ["{a}"]);
+        options.ignore_repeats = true;
+        assert!(messages(entry, &options).is_empty());
+    }
+
+    #[test]
+    fn matches_each_placeholder_on_its_own() {
+        let regex = Regex::new(DEFAULT_PATTERN).unwrap();
+        let texts = |value| {
+            placeholders(&regex, value)
+                .iter()
+                .map(|placeholder| placeholder.text)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(texts("{a}{b} {{c}} {d}"), ["{a}", "{b}", "{{c}}", "{d}"]);
+        assert!(texts("{ unclosed").is_empty());
+    }
+
+    #[test]
+    fn compares_placeholders_without_whitespace_and_formatters() {
+        assert_eq!(normalize("{{ count, number }}"), "{{count}}");
+        assert_eq!(normalize("{{name | capitalize}}"), "{{name}}");
+        assert_eq!(normalize("{ user }"), "{user}");
+        assert_eq!(normalize("{{}}"), "{{}}");
+        assert_eq!(normalize("{{a}"), "{{a}");
+        let options = CheckOptions::default();
+        let entry = "msgid \"{{ count, number }} files\"\nmsgstr \"{{count}} fichiers\"\n";
+        assert!(messages(entry, &options).is_empty());
+    }
+
+    #[test]
+    fn does_not_require_the_header_of_templates() {
+        let options = CheckOptions::default();
+        let text = "msgid \"Hello\"\nmsgstr \"\"\n";
+        assert!(check(Path::new("messages.pot"), Some(text.as_bytes()), &options).is_empty());
+        let diagnostics = check(Path::new("fr.po"), Some(text.as_bytes()), &options);
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.message, diagnostic.severity))
+            .collect();
+        assert!(messages.contains(&("Missing header", Severity::Warning)));
+    }
+
+    #[test]
+    fn warns_of_a_missing_revision_date() {
+        let options = CheckOptions::default();
+        let text = "msgid \"\"\nmsgstr \"\"\n\"Language: fr\\n\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n";
+        let diagnostics = check(Path::new("fr.po"), Some(text.as_bytes()), &options);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Missing header field");
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn reports_malformed_header_fields() {
+        let options = CheckOptions::default();
+        assert!(messages("", &options).is_empty());
+        let text = HEADER.replace("Language: fr", "Language: French");
+        let diagnostics = check(Path::new("fr.po"), Some(text.as_bytes()), &options);
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message).collect();
+        assert_eq!(messages, ["Invalid header field"]);
+    }
+
+    #[test]
+    fn does_not_count_the_header_as_an_entry() {
+        let text = format!("{}msgid \"a\"\nmsgstr \"b\"\n", HEADER);
+        let mut entries = 0;
+        check_file(
+            Path::new("fr.po"),
+            Some(text.as_bytes()),
+            &CheckOptions::default(),
+            &mut entries,
+            &mut |_| {},
+        );
+        assert_eq!(entries, 1);
+    }
+
+    #[test]
+    fn does_not_take_msgstr_without_msgid_for_duplicates() {
+        let options = CheckOptions::default();
+        let entries = "msgstr \"a\"\n\nmsgstr \"b\"\n";
+        assert!(!messages(entries, &options).contains(&"Duplicate entry"));
+        let entries = "msgid \"a\"\nmsgstr \"b\"\n\nmsgid \"a\"\nmsgstr \"c\"\n";
+        assert_eq!(messages(entries, &options), ["Duplicate entry"]);
+    }
+
+    #[test]
+    fn leaves_the_placeholders_of_brace_formats_to_their_rules() {
+        let options = CheckOptions::default();
+        let entries = "#, python-brace-format\nmsgid \"Hello {user}\"\nmsgstr \"Bonjour\"\n";
+        let messages = messages(entries, &options);
+        assert!(!messages.contains(&"Missing interpolation"));
+        assert!(!messages.is_empty());
+    }
+
+    static TODO: RuleInfo = RuleInfo {
+        code: "X001",
+        name: "todo",
+        messages: &["TODO in translation"],
+        description: "A translation is marked TODO.",
+        flag: None,
+        needs_value: false,
+        severity: Severity::Warning,
+    };
+
+    /// A rule of a library user.
+    struct Todo;
+
+    impl Rule for Todo {
+        fn info(&self) -> &'static RuleInfo {
+            &TODO
+        }
+
+        fn check(&self, entry: &Entry, ctx: &CheckContext) -> Vec<Diagnostic> {
+            entry
+                .msgstr
+                .iter()
+                .filter(|msgstr| msgstr.value.contains("TODO"))
+                .map(|_| Diagnostic {
+                    message: "TODO in translation",
+                    severity: Severity::Warning,
+                    context: None,
+                    missing: Vec::new(),
+                    unexpected: Vec::new(),
+                    path: ctx.path.to_path_buf(),
+                    line_index: entry.line_index,
+                    column: 1,
+                    lines: Vec::new(),
+                    rule: None,
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn reports_the_rules_of_library_users_with_their_code() {
+        let mut options = CheckOptions::default();
+        options.rules.push(Arc::new(Todo));
+        let text = format!("{}msgid \"Save\"\nmsgstr \"TODO\"\n", HEADER);
+        let diagnostics = check(Path::new("fr.po"), Some(text.as_bytes()), &options);
+        let codes: Vec<_> = diagnostics.iter().map(|d| d.rule().code).collect();
+        assert_eq!(codes, ["X001"]);
+
+        options.settings = Arc::new(rules::Settings {
+            disabled: vec![&TODO],
+            ..rules::Settings::default()
+        });
+        assert!(check(Path::new("fr.po"), Some(text.as_bytes()), &options).is_empty());
+    }
+
+    #[test]
+    fn fix_leaves_malformed_entries_as_they_are() {
+        let path = std::env::temp_dir().join(format!("po-parser-fix-{}.po", std::process::id()));
+        let malformed = "msgid \"Broken {x}\"\nmsgstr \"Cassé\"\nbogus line\nmsgstr[9] \"kept\"\n";
+        let text = format!(
+            "{}msgid \"Hello {{user}}\"\nmsgstr \"Bonjour @@\"\n\n{}",
+            HEADER, malformed
+        );
+        std::fs::write(&path, &text).unwrap();
+        let options = CheckOptions {
+            fix: true,
+            normalize: true,
+            ..CheckOptions::default()
+        };
+        let broken = HashSet::from([(None, String::from("Broken {x}"))]);
+        let fixed = fix_file(&path, &options, &broken, None);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(fixed.unwrap().0.len(), 1);
+        assert!(written.contains("msgstr \"Bonjour {user}\""));
+        assert!(written.ends_with(malformed));
+    }
+}
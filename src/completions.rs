@@ -0,0 +1,431 @@
+use clap::{ArgAction, Command, ValueEnum};
+
+/// The shells completion scripts are generated for.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+/// An argument of a command, as completion scripts need it.
+struct Flag {
+    short: Option<char>,
+    long: Option<String>,
+    /// The first line of its help
+    help: String,
+    /// The name of its value, if it takes one
+    value_name: Option<String>,
+    /// The values it accepts, if they are a list
+    values: Vec<String>,
+    /// Whether it is given several times
+    repeated: bool,
+}
+
+impl Flag {
+    fn is_positional(&self) -> bool {
+        self.short.is_none() && self.long.is_none()
+    }
+
+    /// Whether its values are paths, which the shell completes.
+    fn takes_path(&self) -> bool {
+        self.value_name
+            .as_deref()
+            .is_some_and(|name| matches!(name, "FILE" | "DIR" | "PATH" | "PATHS"))
+    }
+
+    /// Its names, with their dashes.
+    fn names(&self) -> Vec<String> {
+        let short = self.short.map(|short| format!("-{}", short));
+        let long = self.long.as_ref().map(|long| format!("--{}", long));
+        short.into_iter().chain(long).collect()
+    }
+}
+
+/// A command and its arguments: the program itself, without a name, or one of its commands.
+struct Spec {
+    name: Option<String>,
+    about: String,
+    flags: Vec<Flag>,
+}
+
+/// Returns the first line of a help text.
+fn summary(help: Option<&clap::builder::StyledStr>) -> String {
+    let help = help.map(ToString::to_string).unwrap_or_default();
+    help.lines().next().unwrap_or("").trim().to_string()
+}
+
+fn flags(command: &Command) -> Vec<Flag> {
+    command
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set())
+        .map(|arg| {
+            let takes_value = arg.get_action().takes_values();
+            Flag {
+                short: arg.get_short(),
+                long: arg.get_long().map(String::from),
+                help: summary(arg.get_help()),
+                value_name: takes_value.then(|| {
+                    arg.get_value_names()
+                        .and_then(|names| names.first())
+                        .map_or_else(|| arg.get_id().to_string(), ToString::to_string)
+                }),
+                values: arg
+                    .get_possible_values()
+                    .iter()
+                    .filter(|value| !value.is_hide_set())
+                    .map(|value| value.get_name().to_string())
+                    .collect(),
+                repeated: matches!(arg.get_action(), ArgAction::Append | ArgAction::Count),
+            }
+        })
+        .collect()
+}
+
+/// Returns the program and each of its commands.
+fn specs(command: &mut Command) -> Vec<Spec> {
+    command.build();
+    let mut specs = vec![Spec {
+        name: None,
+        about: summary(command.get_about()),
+        flags: flags(command),
+    }];
+    for subcommand in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        specs.push(Spec {
+            name: Some(subcommand.get_name().to_string()),
+            about: summary(subcommand.get_about()),
+            flags: flags(subcommand),
+        });
+    }
+    specs
+}
+
+/// Returns the script completing the arguments of a program in a shell.
+pub fn script(shell: Shell, command: &mut Command) -> String {
+    let name = command.get_name().to_string();
+    let specs = specs(command);
+    match shell {
+        Shell::Bash => bash(&name, &specs),
+        Shell::Zsh => zsh(&name, &specs),
+        Shell::Fish => fish(&name, &specs),
+        Shell::Powershell => powershell(&name, &specs),
+    }
+}
+
+/// Quotes a string for a shell, in single quotes.
+fn single_quoted(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn bash(name: &str, specs: &[Spec]) -> String {
+    let function = format!("_{}", name.replace('-', "_"));
+    let commands: Vec<&str> = specs
+        .iter()
+        .filter_map(|spec| spec.name.as_deref())
+        .collect();
+    let mut text = format!(
+        "{function}() {{\n    local cur prev command i opts\n    COMPREPLY=()\n    \
+         cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n    \
+         command=\"\"\n    for ((i = 1; i < COMP_CWORD; i++)); do\n        \
+         case \"${{COMP_WORDS[i]}}\" in\n            {}) command=\"${{COMP_WORDS[i]}}\"; break ;;\n        \
+         esac\n    done\n\n    case \"$command\" in\n",
+        commands.join("|")
+    );
+    for spec in specs {
+        let names: Vec<String> = spec.flags.iter().flat_map(Flag::names).collect();
+        text.push_str(&format!(
+            "        {}) opts=\"{}\" ;;\n",
+            spec.name.as_deref().unwrap_or("\"\""),
+            names.join(" ")
+        ));
+    }
+    text.push_str("    esac\n\n    case \"$prev\" in\n");
+    let mut seen = Vec::new();
+    for flag in specs.iter().flat_map(|spec| &spec.flags) {
+        let names = flag.names();
+        if flag.value_name.is_none() || flag.is_positional() || seen.contains(&names) {
+            continue;
+        }
+        let completion = if !flag.values.is_empty() {
+            format!(
+                "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                flag.values.join(" ")
+            )
+        } else if flag.takes_path() {
+            String::from("COMPREPLY=($(compgen -f -- \"$cur\"))")
+        } else {
+            String::from("COMPREPLY=()")
+        };
+        text.push_str(&format!(
+            "        {}) {}; return ;;\n",
+            names.join("|"),
+            completion
+        ));
+        seen.push(names);
+    }
+    text.push_str(&format!(
+        "    esac\n\n    if [[ \"$cur\" == -* ]]; then\n        \
+         COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))\n    \
+         elif [[ -z \"$command\" ]]; then\n        \
+         COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n    fi\n}}\n\n\
+         complete -F {function} -o bashdefault -o default {name}\n",
+        commands.join(" ")
+    ));
+    text
+}
+
+/// Escapes the help of an argument for the brackets of a zsh specification.
+fn zsh_help(help: &str) -> String {
+    help.replace('\\', r"\\")
+        .replace('[', r"\[")
+        .replace(']', r"\]")
+        .replace(':', r"\:")
+}
+
+/// Returns the specifications of the arguments of a command for `_arguments`.
+fn zsh_arguments(spec: &Spec) -> Vec<String> {
+    let mut arguments = Vec::new();
+    for flag in &spec.flags {
+        let action = if !flag.values.is_empty() {
+            format!("({})", flag.values.join(" "))
+        } else if flag.takes_path() {
+            String::from("_files")
+        } else {
+            String::from(" ")
+        };
+        if flag.is_positional() {
+            let name = flag.value_name.as_deref().unwrap_or("value");
+            let repeat = if flag.repeated || flag.takes_path() {
+                "*"
+            } else {
+                ""
+            };
+            arguments.push(format!("{}:{}:{}", repeat, name, action));
+            continue;
+        }
+        let repeat = if flag.repeated { "*" } else { "" };
+        for name in flag.names() {
+            let value = match &flag.value_name {
+                Some(value_name) => format!(":{}:{}", value_name, action),
+                None => String::new(),
+            };
+            let separator = if flag.value_name.is_some() && name.starts_with("--") {
+                "="
+            } else {
+                ""
+            };
+            arguments.push(format!(
+                "{}{}{}[{}]{}",
+                repeat,
+                name,
+                separator,
+                zsh_help(&flag.help),
+                value
+            ));
+        }
+    }
+    arguments
+}
+
+fn zsh(name: &str, specs: &[Spec]) -> String {
+    let function = format!("_{}", name.replace('-', "_"));
+    let mut text =
+        format!("#compdef {name}\n\n{function}() {{\n    local -a commands\n    commands=(\n");
+    for spec in specs {
+        if let Some(command) = &spec.name {
+            let description = format!("{}:{}", command, spec.about.replace(':', r"\:"));
+            text.push_str(&format!("        {}\n", single_quoted(&description)));
+        }
+    }
+    text.push_str(
+        "    )\n\n    if (( CURRENT > 2 )) && (( ${commands[(I)${words[2]}:*]} )); then\n        \
+         local command=${words[2]}\n        shift words\n        (( CURRENT-- ))\n        \
+         case $command in\n",
+    );
+    for spec in specs {
+        let Some(command) = &spec.name else {
+            continue;
+        };
+        let arguments: Vec<String> = zsh_arguments(spec)
+            .iter()
+            .map(|argument| single_quoted(argument))
+            .collect();
+        text.push_str(&format!(
+            "            {})\n                _arguments -s \\\n                    {}\n                ;;\n",
+            command,
+            arguments.join(" \\\n                    ")
+        ));
+    }
+    text.push_str(
+        "        esac\n    else\n        if (( CURRENT == 2 )) && [[ $words[2] != -* ]]; then\n            \
+         _describe -t commands command commands\n        fi\n",
+    );
+    let arguments: Vec<String> = zsh_arguments(&specs[0])
+        .iter()
+        .map(|argument| single_quoted(argument))
+        .collect();
+    text.push_str(&format!(
+        "        _arguments -s \\\n            {}\n    fi\n}}\n\n{function} \"$@\"\n",
+        arguments.join(" \\\n            ")
+    ));
+    text
+}
+
+fn fish(name: &str, specs: &[Spec]) -> String {
+    let mut text = String::new();
+    for spec in specs.iter().skip(1) {
+        if let Some(command) = &spec.name {
+            text.push_str(&format!(
+                "complete -c {} -n '__fish_use_subcommand' -f -a {} -d {}\n",
+                name,
+                command,
+                single_quoted(&spec.about)
+            ));
+        }
+    }
+    for spec in specs {
+        let condition = match &spec.name {
+            Some(command) => format!("__fish_seen_subcommand_from {}", command),
+            None => String::from("__fish_use_subcommand"),
+        };
+        for flag in spec.flags.iter().filter(|flag| !flag.is_positional()) {
+            let mut line = format!("complete -c {} -n {}", name, single_quoted(&condition));
+            if let Some(short) = flag.short {
+                line.push_str(&format!(" -s {}", short));
+            }
+            if let Some(long) = &flag.long {
+                line.push_str(&format!(" -l {}", long));
+            }
+            line.push_str(&format!(" -d {}", single_quoted(&flag.help)));
+            if flag.value_name.is_some() {
+                line.push_str(" -r");
+                if !flag.values.is_empty() {
+                    line.push_str(&format!(" -f -a {}", single_quoted(&flag.values.join(" "))));
+                } else if !flag.takes_path() {
+                    line.push_str(" -f");
+                }
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn powershell(name: &str, specs: &[Spec]) -> String {
+    let quoted = |value: &str| format!("'{}'", value.replace('\'', "''"));
+    let mut text = format!(
+        "using namespace System.Management.Automation\n\n\
+         Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{\n    \
+         param($wordToComplete, $commandAst, $cursorPosition)\n\n    $commands = @(",
+        quoted(name)
+    );
+    let commands: Vec<String> = specs
+        .iter()
+        .filter_map(|spec| spec.name.as_deref())
+        .map(quoted)
+        .collect();
+    text.push_str(&commands.join(", "));
+    text.push_str(
+        ")\n    $command = ''\n    \
+         foreach ($element in $commandAst.CommandElements | Select-Object -Skip 1) {\n        \
+         $text = $element.ToString()\n        \
+         if ($text -eq $wordToComplete) { break }\n        \
+         if ($commands -contains $text) { $command = $text; break }\n    }\n\n    \
+         $completions = switch ($command) {\n",
+    );
+    for spec in specs {
+        let mut results = Vec::new();
+        for flag in spec.flags.iter().filter(|flag| !flag.is_positional()) {
+            let help = if flag.help.is_empty() {
+                flag.names().join(", ")
+            } else {
+                flag.help.clone()
+            };
+            for option in flag.names() {
+                results.push(format!(
+                    "[CompletionResult]::new({}, {}, [CompletionResultType]::ParameterName, {})",
+                    quoted(&option),
+                    quoted(option.trim_start_matches('-')),
+                    quoted(&help)
+                ));
+            }
+        }
+        if spec.name.is_none() {
+            for command in specs.iter().skip(1) {
+                let command_name = command.name.as_deref().unwrap_or_default();
+                results.push(format!(
+                    "[CompletionResult]::new({}, {}, [CompletionResultType]::ParameterValue, {})",
+                    quoted(command_name),
+                    quoted(command_name),
+                    quoted(&command.about)
+                ));
+            }
+        }
+        text.push_str(&format!(
+            "        {} {{\n            @(\n                {}\n            )\n        }}\n",
+            quoted(spec.name.as_deref().unwrap_or("")),
+            results.join(",\n                ")
+        ));
+    }
+    text.push_str(
+        "    }\n\n    $completions |\n        \
+         Where-Object { $_.CompletionText -like \"$wordToComplete*\" } |\n        \
+         Sort-Object -Property ListItemText\n}\n",
+    );
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::CommandFactory;
+    use regex::Regex;
+
+    #[test]
+    fn completes_every_long_flag_of_each_command() {
+        let mut command = Cli::command();
+        command.build();
+        let commands: Vec<&Command> = std::iter::once(&command)
+            .chain(command.get_subcommands().filter(|sub| !sub.is_hide_set()))
+            .collect();
+        let mut longs: Vec<&str> = commands
+            .iter()
+            .flat_map(|command| command.get_arguments())
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(|arg| arg.get_long())
+            .collect();
+        longs.sort_unstable();
+        longs.dedup();
+        for shell in Shell::value_variants() {
+            let script = script(*shell, &mut Cli::command());
+            for long in &longs {
+                let name = match shell {
+                    Shell::Fish => format!("-l {}", long),
+                    _ => format!("--{}", long),
+                };
+                let flag = Regex::new(&format!(r"{}(?:[^\w-]|$)", regex::escape(&name))).unwrap();
+                // Each command taking the flag completes it.
+                let taking = commands
+                    .iter()
+                    .filter(|command| {
+                        command
+                            .get_arguments()
+                            .any(|arg| arg.get_long() == Some(long))
+                    })
+                    .count();
+                let completed = flag.find_iter(&script).count();
+                assert!(
+                    completed >= taking,
+                    "{} is completed {} times in {} instead of {}",
+                    name,
+                    completed,
+                    shell.to_possible_value().unwrap().get_name(),
+                    taking
+                );
+            }
+        }
+    }
+}
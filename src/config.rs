@@ -0,0 +1,249 @@
+use crate::json::Value;
+use crate::rules::Settings;
+use crate::toml;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The configuration file of a project, found in the folder checked or one above it.
+pub const FILE: &str = "po-parser.toml";
+
+/// Returns the configuration file of the folder a path is in, or of the closest folder above it
+/// that has one.
+pub fn find(path: &Path) -> Option<PathBuf> {
+    let absolute = fs::canonicalize(path).ok()?;
+    let dir = if absolute.is_dir() {
+        absolute.as_path()
+    } else {
+        absolute.parent()?
+    };
+    dir.ancestors()
+        .map(|dir| dir.join(FILE))
+        .find(|file| file.is_file())
+}
+
+/// The settings of a configuration file.
+pub struct Config {
+    /// The settings of every catalog, as the command line arguments they stand for
+    pub arguments: Vec<OsString>,
+    /// The rules turned on, off or given a severity in the catalogs of a language, by the table of
+    /// the language such as `[language.fr]`
+    pub languages: Vec<(String, Settings)>,
+}
+
+/// Returns the value of a setting as it is given to its flag.
+fn argument(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the strings of a setting that is a string or an array of them.
+fn strings<'a>(value: &'a Value, key: &str) -> Result<Vec<&'a str>, String> {
+    let values = match value {
+        Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::String(value) => Ok(value.as_str()),
+            _ => Err(format!("expected strings for {}", key)),
+        })
+        .collect()
+}
+
+/// Returns the rule settings of a language table, whose keys are `enable`, `disable` and
+/// `severity`.
+fn language(language: &str, table: &Value) -> Result<Settings, String> {
+    let Value::Object(members) = table else {
+        return Err(format!("expected a table for language.{}", language));
+    };
+    let mut settings = Settings::default();
+    for (key, value) in members {
+        if !matches!(key.as_str(), "enable" | "disable" | "severity") {
+            return Err(format!(
+                "unknown setting language.{}.{}, expected enable, disable or severity",
+                language, key
+            ));
+        }
+        let values = strings(value, &format!("language.{}.{}", language, key))?;
+        match key.as_str() {
+            "enable" => {
+                for value in values {
+                    settings.enabled.push(crate::rule(value)?);
+                }
+            }
+            "disable" => {
+                for value in values {
+                    settings.disabled.push(crate::rule(value)?);
+                }
+            }
+            _ => {
+                for value in values {
+                    settings.severities.push(crate::rule_severity(value)?);
+                }
+            }
+        }
+    }
+    Ok(settings)
+}
+
+/// Reads a configuration file.
+///
+/// Each key is the name of a flag without its dashes, such as `fail-on-fuzzy = true`,
+/// `pattern = "%\\w"` or `enable = ["PO033", "PO034"]`. The command line takes precedence: a
+/// setting is left out when its flag, or one it conflicts with, is in the `given` matches, as
+/// `quiet` is with `-v`. Relative paths are relative to the folder of the file.
+///
+/// The `language` table has a table for each language that sets its rules apart, as in
+/// `[language.ja]` with `disable = ["punctuation"]`.
+pub fn read(path: &Path, command: &Command, given: &ArgMatches) -> io::Result<Config> {
+    let invalid = |reason: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), reason),
+        )
+    };
+    let text = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    let Value::Object(settings) = toml::parse(&text).map_err(invalid)? else {
+        unreachable!("a document is a table");
+    };
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut config = Config {
+        arguments: Vec::new(),
+        languages: Vec::new(),
+    };
+    let arguments = &mut config.arguments;
+    let mut command = command.clone();
+    command.build();
+    let on_command_line =
+        |id: &clap::Id| given.value_source(id.as_str()) == Some(ValueSource::CommandLine);
+    for (key, value) in &settings {
+        if key == "language" {
+            let Value::Object(languages) = value else {
+                return Err(invalid(String::from("expected a table for language")));
+            };
+            for (name, table) in languages {
+                let settings = language(name, table).map_err(invalid)?;
+                config.languages.push((name.clone(), settings));
+            }
+            continue;
+        }
+        let flag = command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(key.as_str()) && !is_excluded(key))
+            .ok_or_else(|| invalid(format!("unknown setting {}", key)))?;
+        // Conflicts are declared on either of the flags, as `github` declares that with `format`.
+        let conflicts = |a: &clap::Arg, b: &clap::Arg| {
+            command
+                .get_arg_conflicts_with(a)
+                .iter()
+                .any(|arg| arg.get_id() == b.get_id())
+        };
+        if on_command_line(flag.get_id())
+            || command.get_arguments().any(|arg| {
+                on_command_line(arg.get_id()) && (conflicts(flag, arg) || conflicts(arg, flag))
+            })
+        {
+            continue;
+        }
+        let takes_value = flag.get_action().takes_values();
+        let is_path = flag
+            .get_value_names()
+            .is_some_and(|names| names.iter().any(|name| name == "FILE" || name == "DIR"));
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            match (value, takes_value) {
+                (Value::Bool(true), false) => arguments.push(format!("--{}", key).into()),
+                (Value::Bool(false), false) => {}
+                (value, true) => {
+                    let value = argument(value).ok_or_else(|| {
+                        invalid(format!("expected strings or numbers for {}", key))
+                    })?;
+                    arguments.push(format!("--{}", key).into());
+                    if is_path && Path::new(&value).is_relative() {
+                        arguments.push(dir.join(value).into());
+                    } else {
+                        arguments.push(value.into());
+                    }
+                }
+                (_, false) => return Err(invalid(format!("expected true or false for {}", key))),
+            }
+        }
+    }
+    Ok(config)
+}
+
+/// Whether a flag is only given on the command line, as those choosing the configuration file.
+fn is_excluded(key: &str) -> bool {
+    matches!(key, "config" | "no-config" | "help" | "version")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::CommandFactory;
+
+    /// Returns the arguments of a configuration file with a command line, then those arguments
+    /// spliced before the given ones as `run` does.
+    fn apply(settings: &str, given: &[&str]) -> (Vec<OsString>, Vec<OsString>) {
+        let dir = std::env::temp_dir().join(format!("po-parser-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE);
+        fs::write(&path, settings).unwrap();
+        let given: Vec<OsString> = given.iter().map(OsString::from).collect();
+        let matches = Cli::command().get_matches_from(&given);
+        let config = read(&path, &Cli::command(), &matches).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        let mut all = given;
+        all.splice(1..1, config.arguments.clone());
+        (config.arguments, all)
+    }
+
+    #[test]
+    fn the_command_line_overrides_the_settings() {
+        let settings = "quiet = true\nformat = \"json\"\n";
+        let (arguments, _) = apply(settings, &["po-parser", "."]);
+        assert_eq!(arguments, ["--quiet", "--format", "json"]);
+        // The flags that conflict with the settings replace them, rather than failing the run.
+        let given: [&[&str]; 2] = [
+            &["po-parser", "-v", "--github", "."],
+            &["po-parser", "--verbose", "--format", "csv", "."],
+        ];
+        for given in given {
+            let (arguments, all) = apply(settings, given);
+            assert!(arguments.is_empty(), "{:?}", arguments);
+            assert!(Cli::command().try_get_matches_from(all).is_ok());
+        }
+        let (arguments, all) = apply(settings, &["po-parser", "--github", "."]);
+        assert_eq!(arguments, ["--quiet"]);
+        assert!(Cli::command().try_get_matches_from(all).is_ok());
+    }
+
+    #[test]
+    fn reads_the_settings_of_languages() {
+        let settings = "[language.ja]\ndisable = [\"punctuation\"]\n";
+        let dir = std::env::temp_dir().join(format!("po-parser-languages-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE);
+        fs::write(&path, settings).unwrap();
+        let matches = Cli::command().get_matches_from(["po-parser", "."]);
+        let config = read(&path, &Cli::command(), &matches).unwrap();
+        assert_eq!(config.languages[0].0, "ja");
+        assert_eq!(config.languages[0].1.disabled[0].name, "punctuation");
+        fs::write(&path, "bogus = 1\n").unwrap();
+        assert!(read(&path, &Cli::command(), &matches).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use crate::diagnostic::{located, Context, Diagnostic, Severity};
+use crate::parser::{quoted, Entry};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The translation of a message in a catalog, kept to compare it with those of other catalogs.
+pub struct Translation {
+    pub msgctxt: Option<String>,
+    pub msgid: String,
+    /// The translated forms, in order
+    pub msgstr: Vec<String>,
+    pub path: PathBuf,
+    /// The position of the first translated form
+    pub position: (u32, u32),
+}
+
+impl Translation {
+    /// Returns the translation of an entry, unless it is fuzzy or not fully translated.
+    pub fn of(path: &Path, entry: &Entry) -> Option<Translation> {
+        let first = entry.msgstr.first()?;
+        if entry.is_fuzzy() || entry.msgstr.iter().any(|msgstr| msgstr.value.is_empty()) {
+            return None;
+        }
+        Some(Translation {
+            msgctxt: entry.msgctxt.clone(),
+            msgid: entry.msgid.clone(),
+            msgstr: entry
+                .msgstr
+                .iter()
+                .map(|msgstr| msgstr.value.clone())
+                .collect(),
+            path: path.to_path_buf(),
+            position: entry.translation(first).position(0),
+        })
+    }
+}
+
+/// Reports the messages translated differently by catalogs of the same language, showing each of
+/// their translations where it first appears.
+pub fn inconsistencies(translations: &[Translation]) -> Vec<Diagnostic> {
+    let mut messages: HashMap<(&Option<String>, &str), Vec<&Translation>> = HashMap::new();
+    let mut order = Vec::new();
+    for translation in translations {
+        let key = (&translation.msgctxt, translation.msgid.as_str());
+        let variants = messages.entry(key).or_insert_with(|| {
+            order.push(key);
+            Vec::new()
+        });
+        if variants
+            .iter()
+            .all(|variant| variant.msgstr != translation.msgstr)
+        {
+            variants.push(translation);
+        }
+    }
+    let mut diagnostics = Vec::new();
+    for key in order {
+        let variants = &messages[&key];
+        let [first, second, ..] = variants.as_slice() else {
+            continue;
+        };
+        let mut lines = Vec::new();
+        if let Some(msgctxt) = &first.msgctxt {
+            lines.push(quoted("msgctxt", msgctxt));
+        }
+        lines.push(quoted("msgid", &first.msgid));
+        for variant in variants {
+            lines.push(located(
+                &variant.path,
+                variant.position,
+                &quoted("msgstr", &variant.msgstr[0]),
+            ));
+        }
+        diagnostics.push(Diagnostic {
+            message: "Inconsistent translation",
+            severity: Severity::Error,
+            context: Some(Context {
+                msgctxt: first.msgctxt.clone(),
+                msgid: first.msgid.clone(),
+                msgstr: Some(second.msgstr[0].clone()),
+                references: Vec::new(),
+            }),
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: second.path.clone(),
+            line_index: second.position.0,
+            column: second.position.1,
+            lines,
+            rule: None,
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translation(path: &str, msgctxt: Option<&str>, msgid: &str, msgstr: &str) -> Translation {
+        Translation {
+            msgctxt: msgctxt.map(String::from),
+            msgid: msgid.to_string(),
+            msgstr: vec![msgstr.to_string()],
+            path: PathBuf::from(path),
+            position: (4, 8),
+        }
+    }
+
+    #[test]
+    fn reports_messages_translated_differently() {
+        let translations = [
+            translation("a/fr.po", None, "Save", "Enregistrer"),
+            translation("b/fr.po", None, "Save", "Sauvegarder"),
+            translation("c/fr.po", None, "Save", "Enregistrer"),
+            translation("a/fr.po", None, "Open", "Ouvrir"),
+            translation("b/fr.po", None, "Open", "Ouvrir"),
+        ];
+        let diagnostics = inconsistencies(&translations);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, Path::new("b/fr.po"));
+        let context = diagnostics[0].context.as_ref().unwrap();
+        assert_eq!(context.msgid, "Save");
+        assert_eq!(context.msgstr.as_deref(), Some("Sauvegarder"));
+        assert_eq!(diagnostics[0].lines.len(), 3);
+    }
+
+    #[test]
+    fn tells_messages_apart_by_their_context() {
+        let translations = [
+            translation("a/fr.po", Some("verb"), "Open", "Ouvrir"),
+            translation("b/fr.po", Some("adjective"), "Open", "Ouvert"),
+        ];
+        assert!(inconsistencies(&translations).is_empty());
+    }
+}
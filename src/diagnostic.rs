@@ -0,0 +1,158 @@
+use crate::diff::{self, Change};
+use crate::parser::escape;
+use crate::rules::{self, RuleInfo};
+use console::Style;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// How serious a problem is, warnings not failing the run.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// The message of a catalog a diagnostic is about.
+#[derive(Clone, Default, PartialEq)]
+pub struct Context {
+    pub msgctxt: Option<String>,
+    pub msgid: String,
+    /// The translation the diagnostic is about, if it is about one rather than the whole entry
+    pub msgstr: Option<String>,
+    /// The source code references of the entry (`#:`), such as `src/main.rs:42`
+    pub references: Vec<String>,
+}
+
+/// A problem found in an entry of a catalog.
+pub struct Diagnostic {
+    /// A short description of the problem, e.g. "Missing interpolation"
+    pub message: &'static str,
+    pub severity: Severity,
+    /// The message the problem is in, or none for problems of a whole file
+    pub context: Option<Context>,
+    /// What the translation lacks of its source, such as placeholders, for the checks comparing
+    /// them
+    pub missing: Vec<String>,
+    /// What the translation has that its source lacks
+    pub unexpected: Vec<String>,
+    pub path: PathBuf,
+    pub line_index: u32,
+    pub column: u32,
+    /// The entry lines displayed below the message
+    pub lines: Vec<String>,
+    /// The rule that reported the problem, set for the rules of `CheckOptions`, which may not be
+    /// those of `rules::RULES`; the others are told by their message
+    pub rule: Option<&'static RuleInfo>,
+}
+
+impl Diagnostic {
+    /// Returns the rule of the diagnostic, or `rules::CUSTOM` for a diagnostic of a library user
+    /// that names none.
+    pub fn rule(&self) -> &'static RuleInfo {
+        self.rule
+            .or_else(|| rules::of(self.message))
+            .unwrap_or(&rules::CUSTOM)
+    }
+}
+
+/// Writes tokens of a diff as removed or added, those in `reported` standing out, with colors or
+/// with wdiff's `[-removed-]` and `{+added+}` markers without.
+fn changed(line: &mut String, tokens: &[&str], reported: &[String], removed: bool, colors: bool) {
+    if tokens.is_empty() {
+        return;
+    }
+    if !colors {
+        let (open, close) = if removed { ("[-", "-]") } else { ("{+", "+}") };
+        let text: String = tokens.iter().map(|token| escape(token)).collect();
+        line.push_str(&format!("{}{}{}", open, text, close));
+        return;
+    }
+    let style = if removed {
+        Style::new().red().strikethrough().force_styling(true)
+    } else {
+        Style::new().green().underlined().force_styling(true)
+    };
+    for token in tokens {
+        let style = if reported.iter().any(|reported| reported == token) {
+            style.clone().bold().reverse()
+        } else {
+            style.clone()
+        };
+        line.push_str(&style.apply_to(escape(token)).to_string());
+    }
+}
+
+impl Diagnostic {
+    /// Returns the word-level diff of the source and translation of a diagnostic that found
+    /// something missing or unexpected in the translation, which stands out in it.
+    fn diff(&self, colors: bool) -> Option<String> {
+        if self.missing.is_empty() && self.unexpected.is_empty() {
+            return None;
+        }
+        let context = self.context.as_ref()?;
+        let changes = diff::words(&context.msgid, context.msgstr.as_ref()?)?;
+        let mut line = String::new();
+        for change in changes {
+            match change {
+                Change::Same(token) => line.push_str(&escape(token)),
+                Change::Replaced(removed, added) => {
+                    changed(&mut line, &removed, &self.missing, true, colors);
+                    changed(&mut line, &added, &self.unexpected, false, colors);
+                }
+            }
+        }
+        Some(line)
+    }
+
+    /// Returns the diagnostic as it is printed for the user, with or without colors.
+    pub fn text(&self, colors: bool) -> String {
+        let (style, label) = match self.severity {
+            Severity::Error => (Style::new().red(), "[ERROR]"),
+            Severity::Warning => (Style::new().yellow(), "[WARNING]"),
+        };
+        let mut text = format!(
+            "{} {} {} in {}:{}:{}",
+            label,
+            self.rule().code,
+            self.message,
+            self.path.display(),
+            self.line_index,
+            self.column
+        );
+        for line in &self.lines {
+            text.push_str("\n\t");
+            text.push_str(line);
+        }
+        if let Some(context) = self.context.as_ref().filter(|c| !c.references.is_empty()) {
+            text.push_str("\n\treferences: ");
+            text.push_str(&context.references.join(" "));
+        }
+        let mut text = style.force_styling(colors).apply_to(text).to_string();
+        if let Some(diff) = self.diff(colors) {
+            text.push_str("\n\tdiff: ");
+            text.push_str(&diff);
+        }
+        text
+    }
+}
+
+/// Writes the diagnostic colored if stdout is.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text(console::colors_enabled()))
+    }
+}
+
+/// Prefixes a line of a diagnostic with the `file:line:col` position it refers to.
+pub fn located(path: &Path, (line_index, column): (u32, u32), line: &str) -> String {
+    format!("{}:{}:{}: {}", path.display(), line_index, column, line)
+}
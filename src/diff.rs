@@ -0,0 +1,283 @@
+use regex::Regex;
+
+/// Matches the placeholders of the common syntaxes, `{name}`, `{{name}}` and `%s` or `%(name)s`,
+/// to show them apart from words.
+pub const PLACEHOLDER: &str =
+    r"\{\{[^{}]*\}\}|\{[^{}]*\}|%(?:\([^)]*\))?[-+ #0]*\d*(?:\.\d+)?[a-zA-Z@]";
+
+/// The most tokens of a string that are compared, the diff taking quadratic time.
+const MAX_TOKENS: usize = 500;
+
+/// A part of the word-level diff of two strings.
+#[derive(PartialEq, Debug)]
+pub enum Change<'a> {
+    /// Tokens both strings have
+    Same(&'a str),
+    /// Tokens of the first string replaced with tokens of the second one, either of which may be
+    /// empty
+    Replaced(Vec<&'a str>, Vec<&'a str>),
+}
+
+/// Splits a string into placeholders, words, runs of whitespace and other characters.
+fn tokens(value: &str) -> Vec<&str> {
+    let token = Regex::new(&format!(r"{}|\w+|\s+|.", PLACEHOLDER)).unwrap();
+    token.find_iter(value).map(|found| found.as_str()).collect()
+}
+
+/// Returns the word-level diff of two strings, or none when they are too long to compare.
+///
+/// Whitespace between two changes is counted as changed too, so that the diff reads as whole
+/// phrases replaced with others.
+pub fn words<'a>(old: &'a str, new: &'a str) -> Option<Vec<Change<'a>>> {
+    let (old, new) = (tokens(old), tokens(new));
+    if old.len() > MAX_TOKENS || new.len() > MAX_TOKENS {
+        return None;
+    }
+    // The lengths of the longest common subsequences of the ends of both token lists.
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    // Each token is kept, removed or added.
+    let mut operations = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            operations.push((Some(old[i]), Some(new[j])));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || i < old.len() && lengths[i + 1][j] >= lengths[i][j + 1] {
+            operations.push((Some(old[i]), None));
+            i += 1;
+        } else {
+            operations.push((None, Some(new[j])));
+            j += 1;
+        }
+    }
+    let kept = |index: usize| matches!(operations.get(index), Some((Some(_), Some(_))));
+    let mut changes = Vec::new();
+    let mut index = 0;
+    while index < operations.len() {
+        if let (Some(token), Some(_)) = operations[index] {
+            changes.push(Change::Same(token));
+            index += 1;
+            continue;
+        }
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        while index < operations.len() {
+            match operations[index] {
+                (Some(token), Some(_)) => {
+                    let between =
+                        token.trim().is_empty() && index + 1 < operations.len() && !kept(index + 1);
+                    if !between {
+                        break;
+                    }
+                    removed.push(token);
+                    added.push(token);
+                }
+                (removed_token, added_token) => {
+                    removed.extend(removed_token);
+                    added.extend(added_token);
+                }
+            }
+            index += 1;
+        }
+        changes.push(Change::Replaced(removed, added));
+    }
+    Some(changes)
+}
+
+/// The unchanged lines shown around the changes of a unified diff.
+const CONTEXT: usize = 3;
+
+/// A line of the line-level diff of two texts, by its index in either.
+enum Line {
+    Same(usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Returns the unified diff of two versions of a file, as `diff -u` prints it and `git apply`
+/// reads it, or an empty string when they are the same.
+///
+/// The lines both versions start and end with are set aside before they are compared, so that
+/// the few lines a correction changes in a long catalog are found quickly.
+pub fn unified(path: &str, old: &str, new: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (removed, added) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    // The lengths of the longest common subsequences of the ends of both line lists.
+    let mut lengths = vec![vec![0usize; added.len() + 1]; removed.len() + 1];
+    for i in (0..removed.len()).rev() {
+        for j in (0..added.len()).rev() {
+            lengths[i][j] = if removed[i] == added[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut lines: Vec<Line> = (0..prefix).map(Line::Same).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < removed.len() || j < added.len() {
+        if i < removed.len() && j < added.len() && removed[i] == added[j] {
+            lines.push(Line::Same(prefix + i));
+            i += 1;
+            j += 1;
+        } else if j == added.len() || i < removed.len() && lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(Line::Removed(prefix + i));
+            i += 1;
+        } else {
+            lines.push(Line::Added(prefix + j));
+            j += 1;
+        }
+    }
+    lines.extend((old.len() - suffix..old.len()).map(Line::Same));
+
+    // The line of either version each line of the diff is at.
+    let mut positions = Vec::with_capacity(lines.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in &lines {
+        positions.push((old_line, new_line));
+        match line {
+            Line::Same(_) => {
+                old_line += 1;
+                new_line += 1;
+            }
+            Line::Removed(_) => old_line += 1,
+            Line::Added(_) => new_line += 1,
+        }
+    }
+    let changed: Vec<usize> = (0..lines.len())
+        .filter(|&index| !matches!(lines[index], Line::Same(_)))
+        .collect();
+    let mut text = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut index = 0;
+    while index < changed.len() {
+        // Changes close enough for their context to overlap are in the same hunk.
+        let mut last = index;
+        while last + 1 < changed.len() && changed[last + 1] - changed[last] <= 2 * CONTEXT + 1 {
+            last += 1;
+        }
+        let start = changed[index].saturating_sub(CONTEXT);
+        let end = (changed[last] + CONTEXT + 1).min(lines.len());
+        let hunk = &lines[start..end];
+        let old_count = hunk
+            .iter()
+            .filter(|line| !matches!(line, Line::Added(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|line| !matches!(line, Line::Removed(_)))
+            .count();
+        // A range without lines starts at the line before it.
+        let (old_start, new_start) = positions[start];
+        text.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_count > 0),
+            old_count,
+            new_start + usize::from(new_count > 0),
+            new_count
+        ));
+        for line in hunk {
+            let (sign, value) = match line {
+                Line::Same(i) => (' ', old[*i]),
+                Line::Removed(i) => ('-', old[*i]),
+                Line::Added(j) => ('+', new[*j]),
+            };
+            text.push(sign);
+            text.push_str(value);
+            if !value.ends_with('\n') {
+                text.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        index = last + 1;
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_words_keeping_placeholders_whole() {
+        assert_eq!(
+            words("Delete {count} files", "Supprimer {count} fichiers"),
+            Some(vec![
+                Change::Replaced(vec!["Delete"], vec!["Supprimer"]),
+                Change::Same(" "),
+                Change::Same("{count}"),
+                Change::Same(" "),
+                Change::Replaced(vec!["files"], vec!["fichiers"]),
+            ])
+        );
+        assert_eq!(
+            words("Open the file", "Open a new file"),
+            Some(vec![
+                Change::Same("Open"),
+                Change::Same(" "),
+                Change::Replaced(vec!["the", " "], vec!["a", " ", "new", " "]),
+                Change::Same("file"),
+            ])
+        );
+    }
+
+    #[test]
+    fn does_not_diff_strings_too_long_to_compare() {
+        let long = "word ".repeat(MAX_TOKENS);
+        assert_eq!(words(&long, "word"), None);
+        assert!(words("word ".repeat(10).as_str(), "word").is_some());
+    }
+
+    #[test]
+    fn diffs_files_in_hunks_with_their_context() {
+        assert_eq!(unified("fr.po", "a\nb\n", "a\nb\n"), "");
+        let old: String = (1..=12).map(|line| format!("{}\n", line)).collect();
+        let new: String = (1..=12)
+            .filter(|&line| line != 11)
+            .map(|line| match line {
+                2 => String::from("two\n"),
+                _ => format!("{}\n", line),
+            })
+            .collect();
+        assert_eq!(
+            unified("fr.po", &old, &new),
+            "--- a/fr.po\n+++ b/fr.po\n\
+             @@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+             @@ -8,5 +8,4 @@\n 8\n 9\n 10\n-11\n 12\n"
+        );
+    }
+
+    #[test]
+    fn marks_lines_without_a_newline() {
+        assert_eq!(
+            unified("fr.po", "a\nb", "a\nc"),
+            "--- a/fr.po\n+++ b/fr.po\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n\
+             +c\n\\ No newline at end of file\n"
+        );
+        assert_eq!(
+            unified("fr.po", "", "a\n"),
+            "--- a/fr.po\n+++ b/fr.po\n@@ -0,0 +1,1 @@\n+a\n"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Returns the arguments opening a file at a line and column in an editor, by the name of its
+/// program: GUI editors take `path:line:column`, and terminal ones `+line path` as vi does.
+fn arguments(program: &str, path: &str, line: u32, column: u32) -> Vec<String> {
+    let name = Path::new(program)
+        .file_stem()
+        .map_or(String::new(), |name| name.to_string_lossy().to_lowercase());
+    let located = format!("{}:{}:{}", path, line, column);
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" => vec![String::from("-g"), located],
+        "subl" | "zed" | "hx" | "helix" => vec![located],
+        _ => vec![format!("+{}", line), path.to_string()],
+    }
+}
+
+/// Opens a file at a line and column in an editor, waiting for it to be closed.
+///
+/// The editor is the command of `template`, whose `{path}`, `{line}` and `{column}` are replaced,
+/// or else that of `$VISUAL` or `$EDITOR`, or vi.
+pub fn open(template: Option<&str>, path: &Path, line: u32, column: u32) -> io::Result<()> {
+    let path = path.to_string_lossy();
+    let words: Vec<String> = match template {
+        Some(template) => template
+            .split_whitespace()
+            .map(|word| {
+                word.replace("{path}", &path)
+                    .replace("{line}", &line.to_string())
+                    .replace("{column}", &column.to_string())
+            })
+            .collect(),
+        None => {
+            let editor = ["VISUAL", "EDITOR"]
+                .into_iter()
+                .filter_map(|name| env::var(name).ok())
+                .find(|editor| !editor.trim().is_empty())
+                .unwrap_or_else(|| String::from("vi"));
+            let mut words: Vec<String> = editor.split_whitespace().map(String::from).collect();
+            let program = words[0].clone();
+            words.extend(arguments(&program, &path, line, column));
+            words
+        }
+    };
+    let Some((program, args)) = words.split_first() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the editor command is empty",
+        ));
+    };
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", program, e)))?;
+    if !status.success() {
+        return Err(io::Error::other(format!("{} failed: {}", program, status)));
+    }
+    Ok(())
+}
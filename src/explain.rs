@@ -0,0 +1,390 @@
+use crate::rules::{RuleInfo, RULES};
+use console::Style;
+use std::fmt::Write;
+
+/// What `po-parser explain` tells about a rule beyond its one-sentence description.
+struct Explanation {
+    code: &'static str,
+    /// Why the rule matters and what it accepts, in a few sentences
+    details: &'static str,
+    /// A catalog excerpt the rule reports
+    failing: &'static str,
+    /// The same excerpt, fixed
+    passing: &'static str,
+    /// Whether the diagnostics of the rule are about an entry, and so can be suppressed by a
+    /// translator comment on it
+    entry: bool,
+}
+
+const fn explanation(
+    code: &'static str,
+    details: &'static str,
+    failing: &'static str,
+    passing: &'static str,
+) -> Explanation {
+    Explanation {
+        code,
+        details,
+        failing,
+        passing,
+        entry: true,
+    }
+}
+
+impl Explanation {
+    /// Marks the diagnostics of the rule as about a whole catalog, or several ones.
+    const fn of_files(self) -> Explanation {
+        Explanation {
+            entry: false,
+            ..self
+        }
+    }
+}
+
+/// The explanations of every rule, in the order of `RULES`.
+static EXPLANATIONS: [Explanation; 35] = [
+    explanation(
+        "PO001",
+        "The program fills the placeholders of a string with values at runtime. When one of \
+         them is dropped by the translation, users of the language never see that value, and \
+         some formatting libraries fail when they are given an argument they do not use.",
+        "msgid \"Hello {name}\"\nmsgstr \"Bonjour\"",
+        "msgid \"Hello {name}\"\nmsgstr \"Bonjour {name}\"",
+    ),
+    explanation(
+        "PO002",
+        "A placeholder of the translation that its source lacks is either shown as it is, or \
+         makes the program fail because no value is given for it. Placeholder names must not \
+         be translated.",
+        "msgid \"Hello {name}\"\nmsgstr \"Bonjour {nom}\"",
+        "msgid \"Hello {name}\"\nmsgstr \"Bonjour {name}\"",
+    ),
+    explanation(
+        "PO003",
+        "Entries flagged with a format language, such as `c-format` or `python-format`, have \
+         their directives compared by position and type, the way gettext's msgfmt --check \
+         does. A translation that swaps `%s` and `%d`, or drops one, crashes or prints garbage.",
+        "#, c-format\nmsgid \"%d files in %s\"\nmsgstr \"%s fichiers dans %d\"",
+        "#, c-format\nmsgid \"%d files in %s\"\nmsgstr \"%d fichiers dans %s\"",
+    ),
+    explanation(
+        "PO004",
+        "A directive the format language does not accept is an error of the string itself, \
+         which the formatting library rejects or prints as it is.",
+        "#, c-format\nmsgid \"%d files\"\nmsgstr \"%y fichiers\"",
+        "#, c-format\nmsgid \"%d files\"\nmsgstr \"%d fichiers\"",
+    ),
+    explanation(
+        "PO005",
+        "Strings with HTML markup are inserted into pages as they are. A tag that is dropped, \
+         renamed or left unclosed by the translation breaks the layout of the page.",
+        "msgid \"<b>Save</b> your work\"\nmsgstr \"<b>Enregistrez votre travail\"",
+        "msgid \"<b>Save</b> your work\"\nmsgstr \"<b>Enregistrez</b> votre travail\"",
+    ),
+    explanation(
+        "PO006",
+        "Date formats are filled by strftime. A translation may reorder their conversions, to \
+         follow the conventions of its language, but not drop or add any.",
+        "msgid \"%B %d, %Y\"\nmsgstr \"%d %B\"",
+        "msgid \"%B %d, %Y\"\nmsgstr \"%d %B %Y\"",
+    ),
+    explanation(
+        "PO007",
+        "A conversion strftime does not know is printed as it is, or makes it fail on some \
+         platforms.",
+        "msgid \"%B %d, %Y\"\nmsgstr \"%d %Q %Y\"",
+        "msgid \"%B %d, %Y\"\nmsgstr \"%d %B %Y\"",
+    ),
+    explanation(
+        "PO008",
+        "Menu items and buttons mark the letter of their keyboard shortcut with a character, \
+         such as `&` or `_`. A translation without one cannot be reached from the keyboard, \
+         and one with two is ambiguous. The rule is checked when --accelerator gives the \
+         character.",
+        "msgid \"&Open\"\nmsgstr \"Ouvrir\"",
+        "msgid \"&Open\"\nmsgstr \"&Ouvrir\"",
+    ),
+    explanation(
+        "PO009",
+        "Leading and trailing newlines and spaces are often part of the layout, such as the \
+         line ending of a message printed on a terminal, or the space before a value that is \
+         appended to the string.",
+        "msgid \"Done\\n\"\nmsgstr \"Terminé\"",
+        "msgid \"Done\\n\"\nmsgstr \"Terminé\\n\"",
+    ),
+    explanation(
+        "PO010",
+        "A source ending with a period, colon, question mark or ellipsis is usually followed \
+         by something that expects it, such as a value after a colon. Languages with \
+         punctuation of their own, such as `。` or `؟`, are compared by the meaning of their \
+         marks. --no-punctuation turns the rule off.",
+        "msgid \"Name:\"\nmsgstr \"Nom\"",
+        "msgid \"Name:\"\nmsgstr \"Nom :\"",
+    ),
+    explanation(
+        "PO011",
+        "The Plural-Forms field of the header declares how many forms the plurals of the \
+         language have. An entry with fewer translations shows nothing for some counts.",
+        "\"Plural-Forms: nplurals=2; plural=(n > 1);\\n\"\n\nmsgid \"One file\"\n\
+         msgid_plural \"{n} files\"\nmsgstr[0] \"Un fichier\"",
+        "\"Plural-Forms: nplurals=2; plural=(n > 1);\\n\"\n\nmsgid \"One file\"\n\
+         msgid_plural \"{n} files\"\nmsgstr[0] \"Un fichier\"\nmsgstr[1] \"{n} fichiers\"",
+    ),
+    explanation(
+        "PO012",
+        "Fuzzy entries were guessed by msgmerge, or marked for review by a translator, and \
+         are not used by gettext until the flag is removed. The rule is checked with \
+         --fail-on-fuzzy, so that a release does not ship them.",
+        "#, fuzzy\nmsgid \"Open file\"\nmsgstr \"Ouvrir\"",
+        "msgid \"Open file\"\nmsgstr \"Ouvrir le fichier\"",
+    ),
+    explanation(
+        "PO013",
+        "Untranslated entries are shown in the source language. The rule is checked with \
+         --fail-on-untranslated, for languages that must be complete.",
+        "msgid \"Open file\"\nmsgstr \"\"",
+        "msgid \"Open file\"\nmsgstr \"Ouvrir le fichier\"",
+    ),
+    explanation(
+        "PO014",
+        "msgfmt rejects a catalog with two entries of the same context and source, and other \
+         tools silently keep either of their translations.",
+        "msgid \"Open\"\nmsgstr \"Ouvrir\"\n\nmsgid \"Open\"\nmsgstr \"Ouvert\"",
+        "msgid \"Open\"\nmsgstr \"Ouvrir\"\n\nmsgctxt \"state\"\nmsgid \"Open\"\n\
+         msgstr \"Ouvert\"",
+    ),
+    explanation(
+        "PO015",
+        "The catalog is parsed on a best-effort basis, so that the rest of it is checked, but \
+         msgfmt and other tools may reject it or read it differently. Some constructs are only \
+         reported with --strict-syntax.",
+        "msgid \"Open\nmsgstr \"Ouvrir\"",
+        "msgid \"Open\"\nmsgstr \"Ouvrir\"",
+    ),
+    explanation(
+        "PO016",
+        "The Content-Type field of the header names the charset of the catalog. A catalog \
+         that is not valid in it, or names one that is unknown, shows garbled text.",
+        "\"Content-Type: text/plain; charset=UTF-8\\n\"\n(with the file saved as Windows-1252)",
+        "\"Content-Type: text/plain; charset=UTF-8\\n\"\n(with the file saved as UTF-8)",
+    )
+    .of_files(),
+    explanation(
+        "PO017",
+        "The header tells gettext the charset and plural forms of the catalog, and tools its \
+         language. A field that is missing or does not match the others, such as a Language \
+         that is not that of the file name, is reported.",
+        "msgid \"\"\nmsgstr \"Content-Type: text/plain; charset=UTF-8\\n\"",
+        "msgid \"\"\nmsgstr \"\"\n\"Language: fr\\n\"\n\
+         \"Content-Type: text/plain; charset=UTF-8\\n\"\n\
+         \"Plural-Forms: nplurals=2; plural=(n > 1);\\n\"",
+    )
+    .of_files(),
+    explanation(
+        "PO018",
+        "A catalog that cannot be read is not checked at all, so the run fails rather than \
+         passing without it.",
+        "(a catalog without read permission)",
+        "(a catalog the user running po-parser can read)",
+    )
+    .of_files(),
+    explanation(
+        "PO019",
+        "Control, zero-width and byte order mark characters are invisible in most editors, \
+         and are usually pasted with the text by accident. They break searches, comparisons \
+         and layouts.",
+        "msgid \"Save\"\nmsgstr \"Enregistrer\\u200B\"  (with a zero-width space)",
+        "msgid \"Save\"\nmsgstr \"Enregistrer\"",
+    ),
+    explanation(
+        "PO020",
+        "The same accented letter can be written as a single character or as a letter and a \
+         combining mark. Translations in Normalization Form C compare equal to the strings \
+         the program has. --normalize rewrites them, and --no-nfc turns the rule off.",
+        "msgid \"Cafe\"\nmsgstr \"Cafe\\u0301\"  (e and a combining acute accent)",
+        "msgid \"Cafe\"\nmsgstr \"Café\"",
+    ),
+    explanation(
+        "PO021",
+        "UTF-8 text that was read as Windows-1252 then saved again has its accented letters \
+         replaced by sequences such as `Ã©`. The translation must be restored from the \
+         original text.",
+        "msgid \"Coffee\"\nmsgstr \"CafÃ©\"",
+        "msgid \"Coffee\"\nmsgstr \"Café\"",
+    ),
+    explanation(
+        "PO022",
+        "An embedding, override or isolate that is not closed changes the direction of \
+         everything displayed after the translation.",
+        "msgid \"{name} wrote\"\nmsgstr \"\\u2067{name} كتب\"",
+        "msgid \"{name} wrote\"\nmsgstr \"\\u2067{name}\\u2069 كتب\"",
+    ),
+    explanation(
+        "PO023",
+        "A placeholder of a right-to-left translation that is filled with left-to-right text, \
+         such as a file name, can be reordered with the words around it. Wrapping it in FSI \
+         (U+2068) and PDI (U+2069) keeps it in place.",
+        "msgid \"{name} wrote\"\nmsgstr \"{name} كتب\"",
+        "msgid \"{name} wrote\"\nmsgstr \"\\u2068{name}\\u2069 كتب\"",
+    ),
+    explanation(
+        "PO024",
+        "French puts a no-break space before `?`, `!`, `;` and `:` and inside guillemets, \
+         which replace straight double quotes, and uses a single apostrophe style in a \
+         translation. --no-typography turns off each of these.",
+        "msgid \"Continue?\"\nmsgstr \"Continuer?\"",
+        "msgid \"Continue?\"\nmsgstr \"Continuer\\u00A0?\"",
+    ),
+    explanation(
+        "PO025",
+        "URLs and email addresses are not translated: one the translation changes leads users \
+         astray. --no-links turns the rule off.",
+        "msgid \"See https://example.com/help\"\nmsgstr \"Voir https://example.com/aide\"",
+        "msgid \"See https://example.com/help\"\nmsgstr \"Voir https://example.com/help\"",
+    ),
+    explanation(
+        "PO026",
+        "Numbers such as versions, limits and prices are usually facts the translation must \
+         keep, possibly written with the separators of its language. The rule is checked \
+         with --numbers.",
+        "msgid \"Up to 10 files\"\nmsgstr \"Jusqu'à 100 fichiers\"",
+        "msgid \"Up to 10 files\"\nmsgstr \"Jusqu'à 10 fichiers\"",
+    ),
+    explanation(
+        "PO027",
+        "Strings rendered as Markdown lose their formatting, or show stray markers, when the \
+         translation drops code spans, links, emphasis or list markers. The rule is checked \
+         with --markdown.",
+        "msgid \"Run `make`\"\nmsgstr \"Lancez make\"",
+        "msgid \"Run `make`\"\nmsgstr \"Lancez `make`\"",
+    ),
+    explanation(
+        "PO028",
+        "A translation much shorter than its source was often cut, and one much longer does \
+         not fit the layout. The rule is checked when --length-ratio gives the accepted range \
+         of percentages, such as `30,300`.",
+        "msgid \"Delete all selected files\"\nmsgstr \"Sup\"",
+        "msgid \"Delete all selected files\"\nmsgstr \"Supprimer les fichiers sélectionnés\"",
+    ),
+    explanation(
+        "PO029",
+        "A glossary keeps the terms of a product translated the same way everywhere. The rule \
+         is checked when --glossary gives a CSV file of terms and their translations by \
+         language, such as `source,fr` then `folder,dossier`.",
+        "msgid \"Open the folder\"\nmsgstr \"Ouvrir le répertoire\"",
+        "msgid \"Open the folder\"\nmsgstr \"Ouvrir le dossier\"",
+    ),
+    explanation(
+        "PO030",
+        "Product names, trademarks and legal phrases must be kept as they are. The rule is \
+         checked for the terms given with --protect or --protected-terms-file.",
+        "msgid \"Sync with Dropbox\"\nmsgstr \"Synchroniser avec Boîte de dépôt\"",
+        "msgid \"Sync with Dropbox\"\nmsgstr \"Synchroniser avec Dropbox\"",
+    ),
+    explanation(
+        "PO031",
+        "Words missing from the Hunspell dictionary of the language are reported as warnings, \
+         since dictionaries lack many names and technical terms; --wordlist accepts \
+         more. The rule is checked with --spellcheck.",
+        "msgid \"Open file\"\nmsgstr \"Ouvrir le fihcier\"",
+        "msgid \"Open file\"\nmsgstr \"Ouvrir le fichier\"",
+    ),
+    explanation(
+        "PO032",
+        "With --spellcheck, a catalog whose language has no dictionary in --dictionaries is \
+         not spellchecked, which is reported as a warning once.",
+        "fr.po, with no fr.dic or fr_FR.dic in the dictionaries folder",
+        "fr.po, with fr_FR.dic and fr_FR.aff in the dictionaries folder",
+    )
+    .of_files(),
+    explanation(
+        "PO033",
+        "A translation identical to its source was usually copied to be translated later. \
+         Sources with nothing to translate, such as placeholders, numbers and the names given \
+         with --brand, are not reported. The rule is checked with --same-as-source.",
+        "msgid \"Open file\"\nmsgstr \"Open file\"",
+        "msgid \"Open file\"\nmsgstr \"Ouvrir le fichier\"",
+    ),
+    explanation(
+        "PO034",
+        "The same message translated differently in several catalogs of a language confuses \
+         users moving between the parts of a product. The rule is checked with --cross-file.",
+        "app.po: msgid \"Save\" msgstr \"Enregistrer\"\n\
+         web.po: msgid \"Save\" msgstr \"Sauvegarder\"",
+        "app.po: msgid \"Save\" msgstr \"Enregistrer\"\n\
+         web.po: msgid \"Save\" msgstr \"Enregistrer\"",
+    )
+    .of_files(),
+    explanation(
+        "PO035",
+        "A suppression comment that no longer suppresses anything, because the entry was \
+         fixed or the rule name is misspelled, would hide a new problem of the entry later.",
+        "# po-parser:ignore punctuation\nmsgid \"Name:\"\nmsgstr \"Nom :\"",
+        "msgid \"Name:\"\nmsgstr \"Nom :\"",
+    ),
+];
+
+/// Returns an excerpt of a catalog indented as a block.
+fn indented(excerpt: &str) -> String {
+    excerpt
+        .lines()
+        .map(|line| format!("    {}\n", line))
+        .collect()
+}
+
+/// Returns the explanation of a rule printed by `po-parser explain`: what it reports, why, an
+/// entry it reports and the same entry fixed, and how to turn it on and off.
+pub fn explain(rule: &RuleInfo) -> String {
+    let explanation = EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code == rule.code)
+        .unwrap_or_else(|| panic!("{} has no explanation", rule.code));
+    let heading = Style::new().bold();
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "{}",
+        heading.apply_to(format!("{} {}", rule.code, rule.name))
+    );
+    let _ = writeln!(text, "\n{}\n\n{}", rule.description, explanation.details);
+    let default = match rule.flag {
+        Some(flag) if rule.needs_value => format!("off unless {} is given a value", flag),
+        Some(flag) => format!("off unless {} or --enable {} is given", flag, rule.name),
+        None => String::from("on"),
+    };
+    let _ = writeln!(
+        text,
+        "\nDefault: {}, reported as {}s.",
+        default,
+        rule.severity.name()
+    );
+    let _ = writeln!(text, "\n{}", heading.apply_to("Failing"));
+    text.push_str(&indented(explanation.failing));
+    let _ = writeln!(text, "\n{}", heading.apply_to("Passing"));
+    text.push_str(&indented(explanation.passing));
+    let _ = writeln!(text, "\n{}", heading.apply_to("Suppressing"));
+    if explanation.entry {
+        let _ = writeln!(
+            text,
+            "For a single entry, add a translator comment above it:\n\n    # po-parser:ignore {}\n",
+            rule.name
+        );
+    }
+    let _ = writeln!(
+        text,
+        "For every catalog, pass --disable {}, or --severity {}=warning to report it without \
+         failing the run. Existing diagnostics can be recorded with --write-baseline and \
+         accepted with --baseline.",
+        rule.code, rule.code
+    );
+    text
+}
+
+/// Returns the list of rules printed by `po-parser explain` without a rule.
+pub fn list() -> String {
+    let mut text = String::new();
+    for rule in &RULES {
+        let _ = writeln!(text, "{} {:<26} {}", rule.code, rule.name, rule.description);
+    }
+    text
+}
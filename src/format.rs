@@ -0,0 +1,831 @@
+use crate::icu;
+use crate::parser::Entry;
+use regex::Regex;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// A format string language, checked on the entries flagged with its `<name>-format` flag.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    /// printf directives, such as `%s`, `%1$d` or `%-*.2f`
+    C,
+    /// `%` operator directives, such as `%s` or `%(name)s`
+    Python,
+    /// `str.format` replacement fields, such as `{}`, `{0}` or `{name!r:>10}`
+    PythonBrace,
+    /// ICU MessageFormat arguments, such as `{name}` or `{count, plural, one {#} other {#}}`
+    Icu,
+    /// `QString::arg` markers, such as `%1` or `%L2`
+    Qt,
+    /// `format` and Rails interpolations, such as `%{name}`, `%<name>d` or `%s`
+    Ruby,
+    /// `MessageFormat` arguments, such as `{0}` or `{1,number}`
+    Java,
+    /// `String.Format` items, such as `{0}`, `{0:d}` or `{1,-10}`
+    CSharp,
+}
+
+impl Format {
+    pub const ALL: [Format; 8] = [
+        Format::C,
+        Format::Python,
+        Format::PythonBrace,
+        Format::Icu,
+        Format::Qt,
+        Format::Ruby,
+        Format::Java,
+        Format::CSharp,
+    ];
+
+    /// Returns the flag gettext tools mark the entries in this language with.
+    pub fn flag(self) -> &'static str {
+        match self {
+            Format::C => "c-format",
+            Format::Python => "python-format",
+            Format::PythonBrace => "python-brace-format",
+            Format::Icu => "icu-format",
+            Format::Qt => "qt-format",
+            Format::Ruby => "ruby-format",
+            Format::Java => "java-format",
+            Format::CSharp => "csharp-format",
+        }
+    }
+
+    /// Whether the entry is in this language: flagged with it, in the language assumed for all
+    /// entries, or for ICU, when its msgid has a plural or select argument.
+    pub fn applies(self, entry: &Entry, default: Option<Format>) -> bool {
+        if entry.has_flag(&format!("no-{}", self.flag())) {
+            return false;
+        }
+        static ICU: OnceLock<Regex> = OnceLock::new();
+        let icu = ICU.get_or_init(|| {
+            Regex::new(r"\{\s*[^{}\s,]+\s*,\s*(plural|selectordinal|select)\s*,").unwrap()
+        });
+        entry.has_flag(self.flag())
+            || default == Some(self)
+            || (self == Format::Icu && icu.is_match(&entry.msgid))
+    }
+
+    /// Whether the directives of the language are in braces, as the placeholders of the default
+    /// pattern are, so that a pattern would take them for placeholders.
+    pub fn uses_braces(self) -> bool {
+        matches!(
+            self,
+            Format::PythonBrace | Format::Icu | Format::Java | Format::CSharp
+        )
+    }
+
+    /// Returns the arguments a string consumes, or the first directive that is not valid.
+    pub fn parse(self, value: &str) -> Result<Vec<Argument>, Invalid> {
+        match self {
+            Format::C => parse_c(value),
+            Format::Python => parse_python(value),
+            Format::PythonBrace => parse_python_brace(value),
+            Format::Icu => icu::parse(value),
+            Format::Qt => Ok(parse_qt(value)),
+            Format::Ruby => parse_ruby(value),
+            Format::Java => parse_java(value),
+            Format::CSharp => parse_csharp(value),
+        }
+    }
+}
+
+/// Identifies an argument of a format string.
+#[derive(Clone, PartialEq)]
+pub enum Key {
+    /// A positional argument, numbered from 1
+    Position(usize),
+    /// A named argument, taken from a mapping
+    Name(String),
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Key::Position(position) => write!(f, "argument {}", position),
+            Key::Name(name) => write!(f, "argument '{}'", name),
+        }
+    }
+}
+
+/// An argument consumed by a directive of a format string.
+pub struct Argument {
+    pub key: Key,
+    /// The type the argument is converted from, e.g. `s` or `ld`
+    pub kind: String,
+    /// The directive the argument is consumed by, e.g. `%2$s`
+    pub directive: String,
+    pub offset: usize,
+    /// The keys of the branches a select-like argument chooses between, in a sorted order
+    pub selectors: Vec<String>,
+    /// The arguments used in the branches of a select-like argument, in a sorted order
+    pub nested: Vec<String>,
+}
+
+/// A directive that does not follow the syntax of its format string language.
+pub struct Invalid {
+    pub offset: usize,
+    pub directive: String,
+    pub reason: &'static str,
+}
+
+/// A difference between the arguments of a source string and its translation.
+pub struct Mismatch {
+    /// Where the difference is in the translation, if it is there
+    pub offset: Option<usize>,
+    pub description: String,
+}
+
+/// Returns how the arguments of a translation differ from those of its source.
+///
+/// Translations of plural forms may leave out arguments, such as the count of a singular form.
+pub fn compare(
+    source: &[Argument],
+    translation: &[Argument],
+    allow_missing: bool,
+) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    for argument in translation {
+        match source.iter().find(|source| source.key == argument.key) {
+            None => mismatches.push(Mismatch {
+                offset: Some(argument.offset),
+                description: format!(
+                    "{} ({}) is not in the source",
+                    argument.key, argument.directive
+                ),
+            }),
+            Some(source) if source.kind != argument.kind => mismatches.push(Mismatch {
+                offset: Some(argument.offset),
+                description: format!(
+                    "{} is {}, but {} in the source",
+                    argument.key, argument.directive, source.directive
+                ),
+            }),
+            Some(source) if source.selectors != argument.selectors => mismatches.push(Mismatch {
+                offset: Some(argument.offset),
+                description: format!(
+                    "{} selects {{{}}}, but {{{}}} in the source",
+                    argument.key,
+                    argument.selectors.join(", "),
+                    source.selectors.join(", ")
+                ),
+            }),
+            Some(source) if source.nested != argument.nested => mismatches.push(Mismatch {
+                offset: Some(argument.offset),
+                description: format!(
+                    "{} holds {{{}}}, but {{{}}} in the source",
+                    argument.key,
+                    argument.nested.join(", "),
+                    source.nested.join(", ")
+                ),
+            }),
+            Some(_) => {}
+        }
+    }
+    // A name missing from the translation while another one is not in the source was most likely
+    // translated, which breaks the lookup of the argument.
+    let missing_names = source.iter().filter(|argument| {
+        matches!(argument.key, Key::Name(_))
+            && !translation.iter().any(|other| other.key == argument.key)
+    });
+    let unknown_names = translation.iter().filter(|argument| {
+        matches!(argument.key, Key::Name(_))
+            && !source.iter().any(|other| other.key == argument.key)
+    });
+    for (missing, unknown) in missing_names.zip(unknown_names) {
+        mismatches.push(Mismatch {
+            offset: Some(unknown.offset),
+            description: format!(
+                "{} looks like a translation of {}, names must be kept as they are",
+                unknown.directive, missing.directive
+            ),
+        });
+    }
+    if !allow_missing {
+        for (index, argument) in source.iter().enumerate() {
+            let repeated = source[..index]
+                .iter()
+                .any(|other| other.key == argument.key);
+            if !repeated && !translation.iter().any(|other| other.key == argument.key) {
+                mismatches.push(Mismatch {
+                    offset: None,
+                    description: format!("{} ({}) is missing", argument.key, argument.directive),
+                });
+            }
+        }
+    }
+    mismatches
+}
+
+fn parse_c(value: &str) -> Result<Vec<Argument>, Invalid> {
+    static DIRECTIVE: OnceLock<Regex> = OnceLock::new();
+    let directive = DIRECTIVE.get_or_init(|| {
+        Regex::new(concat!(
+            r"%(?:(?P<position>[1-9][0-9]*)\$)?[-+ #0']*",
+            r"(?P<width>\*(?:(?P<width_position>[1-9][0-9]*)\$)?|[0-9]+)?",
+            r"(?:\.(?P<precision>\*(?:(?P<precision_position>[1-9][0-9]*)\$)?|[0-9]*))?",
+            r"(?P<length>hh|h|ll|l|L|q|j|z|Z|t)?(?P<conversion>[diouxXeEfFgGaAcCsSpnm%])?",
+        ))
+        .unwrap()
+    });
+    let mut arguments = Vec::new();
+    let mut next = 1;
+    // Unnumbered arguments are consumed in order, numbered ones are taken by their number.
+    let mut argument = |position: Option<&str>, kind: String, directive: &str, offset| {
+        let position = match position {
+            Some(position) => position.parse().unwrap_or(0),
+            None => {
+                next += 1;
+                next - 1
+            }
+        };
+        arguments.push(Argument {
+            key: Key::Position(position),
+            kind,
+            directive: directive.to_string(),
+            offset,
+            selectors: Vec::new(),
+            nested: Vec::new(),
+        });
+    };
+    for captures in directive.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let conversion = match captures.name("conversion") {
+            Some(conversion) => conversion.as_str(),
+            None => {
+                return Err(Invalid {
+                    offset: whole.start(),
+                    directive: whole.as_str().to_string(),
+                    reason: "incomplete directive",
+                })
+            }
+        };
+        if conversion == "%" || conversion == "m" {
+            continue;
+        }
+        for (star, position) in [
+            ("width", "width_position"),
+            ("precision", "precision_position"),
+        ] {
+            if captures
+                .name(star)
+                .is_some_and(|star| star.as_str().starts_with('*'))
+            {
+                let position = captures.name(position).map(|position| position.as_str());
+                argument(position, String::from("d"), whole.as_str(), whole.start());
+            }
+        }
+        // Conversions that read the same type of argument are interchangeable.
+        let class = match conversion {
+            "i" => "d",
+            "o" | "x" | "X" => "u",
+            "e" | "E" | "F" | "g" | "G" | "a" | "A" => "f",
+            "C" => "lc",
+            "S" => "ls",
+            other => other,
+        };
+        let length = captures.name("length").map_or("", |length| length.as_str());
+        let position = captures.name("position").map(|position| position.as_str());
+        argument(
+            position,
+            format!("{}{}", length, class),
+            whole.as_str(),
+            whole.start(),
+        );
+    }
+    Ok(arguments)
+}
+
+fn parse_python(value: &str) -> Result<Vec<Argument>, Invalid> {
+    static DIRECTIVE: OnceLock<Regex> = OnceLock::new();
+    let directive = DIRECTIVE.get_or_init(|| {
+        Regex::new(concat!(
+            r"%(?:\((?P<name>[^)]*)\))?[-+ #0]*(?P<width>\*|[0-9]+)?(?:\.(?P<precision>\*|[0-9]*))?",
+            r"[hlL]?(?P<conversion>[diouxXeEfFgGcrsa%])?",
+        ))
+        .unwrap()
+    });
+    let mut arguments = Vec::new();
+    let mut next = 1;
+    let mut named = false;
+    let mut unnamed = false;
+    for captures in directive.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let invalid = |reason| Invalid {
+            offset: whole.start(),
+            directive: whole.as_str().to_string(),
+            reason,
+        };
+        let conversion = match captures.name("conversion") {
+            Some(conversion) => conversion.as_str(),
+            None => return Err(invalid("incomplete directive")),
+        };
+        if conversion == "%" {
+            continue;
+        }
+        let mut argument = |key, kind: &str| {
+            arguments.push(Argument {
+                key,
+                kind: kind.to_string(),
+                directive: whole.as_str().to_string(),
+                offset: whole.start(),
+                selectors: Vec::new(),
+                nested: Vec::new(),
+            });
+        };
+        let class = match conversion {
+            "i" | "u" | "o" | "x" | "X" => "d",
+            "e" | "E" | "F" | "g" | "G" => "f",
+            "r" | "a" => "s",
+            other => other,
+        };
+        match captures.name("name") {
+            Some(name) => {
+                named = true;
+                argument(Key::Name(name.as_str().to_string()), class);
+            }
+            None => {
+                unnamed = true;
+                for star in ["width", "precision"] {
+                    if captures.name(star).is_some_and(|star| star.as_str() == "*") {
+                        argument(Key::Position(next), "d");
+                        next += 1;
+                    }
+                }
+                argument(Key::Position(next), class);
+                next += 1;
+            }
+        }
+        // Arguments come either from a tuple or from a mapping, not both.
+        if named && unnamed {
+            return Err(invalid("mixes named and unnamed arguments"));
+        }
+    }
+    Ok(arguments)
+}
+
+fn parse_python_brace(value: &str) -> Result<Vec<Argument>, Invalid> {
+    let mut arguments = Vec::new();
+    let mut next = 0;
+    let mut chars = value.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let invalid = |end: usize, reason| Invalid {
+            offset: start,
+            directive: value[start..end].to_string(),
+            reason,
+        };
+        match c {
+            '{' if chars.next_if(|&(_, c)| c == '{').is_some() => {}
+            '}' if chars.next_if(|&(_, c)| c == '}').is_some() => {}
+            '}' => {
+                return Err(invalid(
+                    start + 1,
+                    "single '}' outside of a replacement field",
+                ))
+            }
+            '{' => {
+                // The format spec may itself hold replacement fields, one level deep.
+                let mut depth = 1;
+                let mut fields = vec![(start, None)];
+                let mut end = None;
+                for (index, c) in chars.by_ref() {
+                    match c {
+                        '{' if depth == 1 => {
+                            depth += 1;
+                            fields.push((index, None));
+                        }
+                        '}' => {
+                            depth -= 1;
+                            let field = fields.iter_mut().rev().find(|(_, end)| end.is_none());
+                            if let Some(field) = field {
+                                field.1 = Some(index + 1);
+                            }
+                            if depth == 0 {
+                                end = Some(index + 1);
+                                break;
+                            }
+                        }
+                        '{' => return Err(invalid(index + 1, "replacement fields nest too deep")),
+                        _ => {}
+                    }
+                }
+                let end = end.ok_or_else(|| invalid(value.len(), "unclosed replacement field"))?;
+                for (field_start, field_end) in fields {
+                    let field = &value[field_start..field_end.unwrap_or(end)];
+                    let inner = &field[1..field.len() - 1];
+                    let name_end = inner.find(['!', ':', '.', '[']).unwrap_or(inner.len());
+                    let name = &inner[..name_end];
+                    let key = if name.is_empty() {
+                        next += 1;
+                        Key::Position(next)
+                    } else if let Ok(index) = name.parse::<usize>() {
+                        Key::Position(index + 1)
+                    } else {
+                        Key::Name(name.to_string())
+                    };
+                    arguments.push(Argument {
+                        key,
+                        kind: String::new(),
+                        directive: value[field_start..field_end.unwrap_or(end)].to_string(),
+                        offset: field_start,
+                        selectors: Vec::new(),
+                        nested: Vec::new(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(arguments)
+}
+
+/// Qt markers have no invalid syntax, a `%` that starts none is kept as it is.
+fn parse_qt(value: &str) -> Vec<Argument> {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    let marker = MARKER.get_or_init(|| Regex::new(r"%(L?)([1-9][0-9]?)").unwrap());
+    marker
+        .captures_iter(value)
+        .map(|captures| {
+            let whole = captures.get(0).unwrap();
+            Argument {
+                key: Key::Position(captures[2].parse().unwrap()),
+                // Localized markers take the same arguments as the others.
+                kind: String::new(),
+                directive: whole.as_str().to_string(),
+                offset: whole.start(),
+                selectors: Vec::new(),
+                nested: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+fn parse_ruby(value: &str) -> Result<Vec<Argument>, Invalid> {
+    static DIRECTIVE: OnceLock<Regex> = OnceLock::new();
+    let directive = DIRECTIVE.get_or_init(|| {
+        Regex::new(concat!(
+            r"%(?:\{(?P<interpolation>[^}]*)\}|(?:<(?P<name>[^>]*)>)?[-+ #0]*(?:\*|[0-9]+)?",
+            r"(?:\.[0-9]*)?(?P<conversion>[bBdiouxXeEfgGaAcps%])?)",
+        ))
+        .unwrap()
+    });
+    let mut arguments = Vec::new();
+    let mut next = 1;
+    let mut named = false;
+    let mut unnamed = false;
+    for captures in directive.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let invalid = |reason| Invalid {
+            offset: whole.start(),
+            directive: whole.as_str().to_string(),
+            reason,
+        };
+        let (key, kind) = if let Some(name) = captures.name("interpolation") {
+            // Interpolations are converted with `to_s`, whatever their type.
+            (Key::Name(name.as_str().to_string()), "")
+        } else {
+            let conversion = match captures.name("conversion") {
+                Some(conversion) => conversion.as_str(),
+                None => return Err(invalid("incomplete directive")),
+            };
+            let class = match conversion {
+                "%" => continue,
+                "i" | "u" | "b" | "B" | "o" | "x" | "X" => "d",
+                "e" | "E" | "g" | "G" | "a" | "A" => "f",
+                "p" => "s",
+                other => other,
+            };
+            match captures.name("name") {
+                Some(name) => (Key::Name(name.as_str().to_string()), class),
+                None => {
+                    next += 1;
+                    (Key::Position(next - 1), class)
+                }
+            }
+        };
+        match key {
+            Key::Name(_) => named = true,
+            Key::Position(_) => unnamed = true,
+        }
+        // Arguments come either from a list or from a hash, not both.
+        if named && unnamed {
+            return Err(invalid("mixes named and unnamed arguments"));
+        }
+        arguments.push(Argument {
+            key,
+            kind: kind.to_string(),
+            directive: whole.as_str().to_string(),
+            offset: whole.start(),
+            selectors: Vec::new(),
+            nested: Vec::new(),
+        });
+    }
+    Ok(arguments)
+}
+
+fn parse_java(value: &str) -> Result<Vec<Argument>, Invalid> {
+    let mut arguments = Vec::new();
+    let mut chars = value.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\'' if chars.next_if(|&(_, c)| c == '\'').is_some() => {}
+            // A single quote starts a quoted text, which is meant to start with a brace. One that does
+            // not is an apostrophe that silently swallows the text and arguments that follow it.
+            '\'' => {
+                let brace = chars.peek().is_some_and(|&(_, c)| matches!(c, '{' | '}'));
+                let mut end = value.len();
+                while let Some((index, c)) = chars.next() {
+                    if c == '\'' && chars.next_if(|&(_, c)| c == '\'').is_none() {
+                        end = index + 1;
+                        break;
+                    }
+                }
+                if !brace {
+                    return Err(Invalid {
+                        offset: start,
+                        directive: value[start..end].to_string(),
+                        reason: "single quote that must be doubled",
+                    });
+                }
+            }
+            '{' => {
+                // The style of choice arguments holds nested patterns.
+                let mut depth = 1;
+                let end = chars.find_map(|(index, c)| {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    (depth == 0).then_some(index + 1)
+                });
+                let directive = &value[start..end.unwrap_or(value.len())];
+                let invalid = |reason| Invalid {
+                    offset: start,
+                    directive: directive.to_string(),
+                    reason,
+                };
+                if end.is_none() {
+                    return Err(invalid("unclosed argument"));
+                }
+                let mut parts = directive[1..directive.len() - 1].splitn(3, ',');
+                let index = parts.next().unwrap_or("").trim();
+                let kind = parts.next().unwrap_or("").trim().to_lowercase();
+                let index: usize = index
+                    .parse()
+                    .map_err(|_| invalid("argument without an index"))?;
+                arguments.push(Argument {
+                    key: Key::Position(index + 1),
+                    kind,
+                    directive: directive.to_string(),
+                    offset: start,
+                    selectors: Vec::new(),
+                    nested: Vec::new(),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(arguments)
+}
+
+fn parse_csharp(value: &str) -> Result<Vec<Argument>, Invalid> {
+    let mut arguments = Vec::new();
+    let mut chars = value.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let invalid = |end: usize, reason| Invalid {
+            offset: start,
+            directive: value[start..end].to_string(),
+            reason,
+        };
+        match c {
+            '{' if chars.next_if(|&(_, c)| c == '{').is_some() => {}
+            '}' if chars.next_if(|&(_, c)| c == '}').is_some() => {}
+            '}' => return Err(invalid(start + 1, "single '}' outside of a format item")),
+            '{' => {
+                let end = chars
+                    .find(|&(_, c)| c == '}')
+                    .map(|(index, _)| index + 1)
+                    .ok_or_else(|| invalid(value.len(), "unclosed format item"))?;
+                let item = &value[start + 1..end - 1];
+                let index = item.split([',', ':']).next().unwrap_or("").trim();
+                let index: usize = index
+                    .parse()
+                    .map_err(|_| invalid(end, "format item without an index"))?;
+                arguments.push(Argument {
+                    key: Key::Position(index + 1),
+                    // The format string of an item may differ with the conventions of a locale.
+                    kind: String::new(),
+                    directive: value[start..end].to_string(),
+                    offset: start,
+                    selectors: Vec::new(),
+                    nested: Vec::new(),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(format: Format, value: &str) -> Vec<String> {
+        match format.parse(value) {
+            Ok(arguments) => arguments
+                .iter()
+                .map(|argument| format!("{} {}", argument.key, argument.kind))
+                .collect(),
+            Err(invalid) => vec![format!("invalid {}", invalid.directive)],
+        }
+    }
+
+    #[test]
+    fn parses_printf_directives() {
+        assert_eq!(
+            keys(Format::C, "%s has %2$d of %1$s, 100%%"),
+            ["argument 1 s", "argument 2 d", "argument 1 s"]
+        );
+        assert_eq!(keys(Format::C, "%-*.2f"), ["argument 1 d", "argument 2 f"]);
+        assert_eq!(
+            keys(Format::C, "%i %lx %G %m"),
+            ["argument 1 d", "argument 2 lu", "argument 3 f"]
+        );
+        assert_eq!(
+            keys(Format::C, "%*2$.*3$s"),
+            ["argument 2 d", "argument 3 d", "argument 1 s"]
+        );
+        assert_eq!(keys(Format::C, "100% sure"), ["argument 1 s"]);
+        assert_eq!(keys(Format::C, "100%"), ["invalid %"]);
+    }
+
+    #[test]
+    fn compares_the_arguments_of_printf_directives() {
+        let descriptions = |source: &str, translation: &str, allow_missing| {
+            let source = Format::C.parse(source).ok().unwrap();
+            let translation = Format::C.parse(translation).ok().unwrap();
+            compare(&source, &translation, allow_missing)
+                .into_iter()
+                .map(|mismatch| mismatch.description)
+                .collect::<Vec<_>>()
+        };
+        assert!(descriptions("%s of %d", "%2$d de %1$s", false).is_empty());
+        assert!(descriptions("%d", "%i", false).is_empty());
+        assert_eq!(
+            descriptions("%s of %d", "%d de %s", false),
+            [
+                "argument 1 is %d, but %s in the source",
+                "argument 2 is %s, but %d in the source"
+            ]
+        );
+        assert_eq!(
+            descriptions("%d file", "un fichier %s", false),
+            ["argument 1 is %s, but %d in the source"]
+        );
+        assert_eq!(
+            descriptions("%d files", "fichiers", false),
+            ["argument 1 (%d) is missing"]
+        );
+        assert!(descriptions("%d file", "un fichier", true).is_empty());
+        assert_eq!(
+            descriptions("%s", "%s %s", false),
+            ["argument 2 (%s) is not in the source"]
+        );
+        assert_eq!(
+            descriptions("%s", "%1$s %2$s", false),
+            ["argument 2 (%2$s) is not in the source"]
+        );
+    }
+
+    #[test]
+    fn parses_python_directives() {
+        assert_eq!(keys(Format::Python, "%(name)s"), ["argument 'name' s"]);
+        assert!(keys(Format::Python, "%(name)s %s")[0].starts_with("invalid"));
+        assert_eq!(
+            keys(Format::Python, "%*.*f %r %x 100%%"),
+            [
+                "argument 1 d",
+                "argument 2 d",
+                "argument 3 f",
+                "argument 4 s",
+                "argument 5 d"
+            ]
+        );
+        assert_eq!(keys(Format::Python, "%(count)"), ["invalid %(count)"]);
+    }
+
+    #[test]
+    fn parses_python_brace_fields() {
+        assert_eq!(
+            keys(Format::PythonBrace, "{} {} {0} {name!r:>10} {{literal}}"),
+            [
+                "argument 1 ",
+                "argument 2 ",
+                "argument 1 ",
+                "argument 'name' "
+            ]
+        );
+        assert_eq!(
+            keys(Format::PythonBrace, "{user.name} {items[0]}"),
+            ["argument 'user' ", "argument 'items' "]
+        );
+        assert_eq!(
+            keys(Format::PythonBrace, "{value:{width}.{precision}}"),
+            [
+                "argument 'value' ",
+                "argument 'width' ",
+                "argument 'precision' "
+            ]
+        );
+        assert_eq!(keys(Format::PythonBrace, "{name"), ["invalid {name"]);
+        assert_eq!(keys(Format::PythonBrace, "a } b"), ["invalid }"]);
+        assert_eq!(
+            keys(Format::PythonBrace, "{a:{b:{c}}}"),
+            ["invalid {a:{b:{"]
+        );
+    }
+
+    #[test]
+    fn tells_translated_names_from_missing_ones() {
+        let descriptions = |source: &str, translation: &str| {
+            let source = Format::Python.parse(source).ok().unwrap();
+            let translation = Format::Python.parse(translation).ok().unwrap();
+            compare(&source, &translation, false)
+                .into_iter()
+                .map(|mismatch| mismatch.description)
+                .collect::<Vec<_>>()
+        };
+        assert!(descriptions("%(name)s", "%(name)s").is_empty());
+        assert_eq!(
+            descriptions("%(name)s", "%(nom)s"),
+            [
+                "argument 'nom' (%(nom)s) is not in the source",
+                "%(nom)s looks like a translation of %(name)s, names must be kept as they are",
+                "argument 'name' (%(name)s) is missing"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_qt_markers() {
+        assert_eq!(
+            keys(Format::Qt, "%2 of %L1, 100%"),
+            ["argument 2 ", "argument 1 "]
+        );
+        assert!(keys(Format::Qt, "%0 and %%").is_empty());
+    }
+
+    #[test]
+    fn parses_ruby_interpolations_and_directives() {
+        assert_eq!(
+            keys(Format::Ruby, "%{name} has %<count>05d items, 100%%"),
+            ["argument 'name' ", "argument 'count' d"]
+        );
+        assert_eq!(
+            keys(Format::Ruby, "%s %x"),
+            ["argument 1 s", "argument 2 d"]
+        );
+        assert_eq!(keys(Format::Ruby, "%{name} %s"), ["invalid %s"]);
+        assert_eq!(keys(Format::Ruby, "100%"), ["invalid %"]);
+    }
+
+    #[test]
+    fn parses_java_message_format_arguments() {
+        assert_eq!(
+            keys(Format::Java, "{1,number} of {0}, '{literal}' it''s"),
+            ["argument 2 number", "argument 1 "]
+        );
+        assert_eq!(
+            keys(Format::Java, "{0,choice,0#none|1#{0} file}"),
+            ["argument 1 choice"]
+        );
+        assert_eq!(keys(Format::Java, "it's {0}"), ["invalid 's {0}"]);
+        assert_eq!(keys(Format::Java, "{name}"), ["invalid {name}"]);
+        assert_eq!(keys(Format::Java, "{0"), ["invalid {0"]);
+    }
+
+    #[test]
+    fn parses_csharp_format_items() {
+        assert_eq!(
+            keys(Format::CSharp, "{1,-10} {0:d} {{literal}}"),
+            ["argument 2 ", "argument 1 "]
+        );
+        assert_eq!(keys(Format::CSharp, "{name}"), ["invalid {name}"]);
+        assert_eq!(keys(Format::CSharp, "{0"), ["invalid {0"]);
+        assert_eq!(keys(Format::CSharp, "a } b"), ["invalid }"]);
+    }
+
+    #[test]
+    fn tells_icu_messages_by_their_arguments() {
+        let entry = |msgid: &str| Entry {
+            msgid: msgid.to_string(),
+            ..Entry::default()
+        };
+        let plural = entry("{count, plural, one {# file} other {# files}}");
+        assert!(Format::Icu.applies(&plural, None));
+        assert!(!Format::Icu.applies(&entry("{name}"), None));
+        assert!(Format::Icu.applies(&entry("{name}"), Some(Format::Icu)));
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Runs git in a folder, returning what it prints.
+fn git(dir: &Path, arguments: &[&str]) -> io::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(arguments)
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("git: {}", e)))?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("git: {}", message.trim())));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the files of the repository of a folder added or modified since a commit, such as
+/// `HEAD` or `origin/main`, in the working tree and the index, and those git does not track yet,
+/// as absolute paths.
+///
+/// The paths are listed with `-z`, as git otherwise quotes those with characters other than ASCII
+/// ones.
+pub fn changed(dir: &Path, base: &str) -> io::Result<HashSet<PathBuf>> {
+    let root = git(dir, &["rev-parse", "--show-toplevel"])?;
+    let root = Path::new(root.trim_end_matches('\n'));
+    let mut files = git(
+        root,
+        &["diff", "--name-only", "-z", "--diff-filter=AMR", base, "--"],
+    )?;
+    files.push_str(&git(
+        root,
+        &["ls-files", "-z", "--others", "--exclude-standard"],
+    )?);
+    Ok(files
+        .split('\0')
+        .filter(|file| !file.is_empty())
+        .filter_map(|file| fs::canonicalize(root.join(file)).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_changed_files_with_names_git_would_quote() {
+        let dir = std::env::temp_dir().join(format!("po-parser-git-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let commit = ["-c", "user.name=test", "-c", "user.email=test@example.com"];
+        git(&dir, &["init", "-q"]).unwrap();
+        fs::write(dir.join("señal.po"), "").unwrap();
+        fs::write(dir.join("same.po"), "").unwrap();
+        git(&dir, &["add", "."]).unwrap();
+        git(
+            &dir,
+            &[&commit[..], &["commit", "-q", "-m", "catalogs"]].concat(),
+        )
+        .unwrap();
+        fs::write(dir.join("señal.po"), "# changed\n").unwrap();
+        fs::write(dir.join("日本語.po"), "").unwrap();
+
+        let mut files: Vec<PathBuf> = changed(&dir, "HEAD").unwrap().into_iter().collect();
+        files.sort();
+        let dir = fs::canonicalize(&dir).unwrap();
+        assert_eq!(files, [dir.join("señal.po"), dir.join("日本語.po")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
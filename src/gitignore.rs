@@ -0,0 +1,218 @@
+use crate::glob::Pattern;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// A line of a `.gitignore` file.
+#[derive(Clone)]
+struct Rule {
+    /// The folder of the file, relative to the root of the repository
+    base: PathBuf,
+    pattern: Pattern,
+    /// Whether the path is matched as a whole, rather than by its name
+    anchored: bool,
+    /// Whether the rule re-includes what an earlier one ignores (`!`)
+    negated: bool,
+    /// Whether the rule only matches folders (a trailing `/`)
+    dir_only: bool,
+}
+
+/// The rules of git ignoring the files of a folder: those of `.git/info/exclude` and of the
+/// `.gitignore` files of the folder and the ones it is in, up to the root of its repository.
+///
+/// The rules of a file deeper in the tree, and the later rules of a file, take precedence.
+#[derive(Clone)]
+pub struct Ignore {
+    /// The folder that is walked, as it is given
+    dir: PathBuf,
+    /// The same folder, relative to the root of the repository
+    prefix: PathBuf,
+    rules: Vec<Rule>,
+}
+
+/// Returns the rules of a `.gitignore` file, relative to the root of the repository, or none if
+/// it does not exist.
+fn read(path: &Path, base: &Path) -> io::Result<Vec<Rule>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(io::Error::new(
+                e.kind(),
+                format!("{}: {}", path.display(), e),
+            ))
+        }
+    };
+    let mut rules = Vec::new();
+    for line in text.lines() {
+        // Trailing spaces are ignored unless they are escaped.
+        let line = if line.ends_with("\\ ") {
+            line
+        } else {
+            line.trim_end()
+        };
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        // A malformed line is ignored, as git does.
+        let Ok(pattern) = Pattern::new(line) else {
+            continue;
+        };
+        rules.push(Rule {
+            base: base.to_path_buf(),
+            pattern,
+            anchored,
+            negated,
+            dir_only,
+        });
+    }
+    Ok(rules)
+}
+
+impl Ignore {
+    /// Returns the rules of a folder that is walked, from the root of its repository, or from the
+    /// folder itself when it is not in one.
+    pub fn new(dir: &Path) -> io::Result<Ignore> {
+        let absolute = fs::canonicalize(dir)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", dir.display(), e)))?;
+        let root = absolute
+            .ancestors()
+            .find(|ancestor| ancestor.join(".git").exists())
+            .unwrap_or(&absolute);
+        let prefix = absolute.strip_prefix(root).unwrap_or(Path::new(""));
+        let mut rules = read(
+            &root.join(".git").join("info").join("exclude"),
+            Path::new(""),
+        )?;
+        let mut base = PathBuf::new();
+        rules.extend(read(&root.join(".gitignore"), &base)?);
+        for component in prefix.components() {
+            if let Component::Normal(name) = component {
+                base.push(name);
+                rules.extend(read(&root.join(&base).join(".gitignore"), &base)?);
+            }
+        }
+        Ok(Ignore {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_path_buf(),
+            rules,
+        })
+    }
+
+    /// Returns the rules of a subfolder of the walked folder, with those of its `.gitignore`.
+    pub fn enter(&self, dir: &Path) -> io::Result<Ignore> {
+        let mut ignore = self.clone();
+        if let Some(base) = self.relative(dir) {
+            ignore.rules.extend(read(&dir.join(".gitignore"), &base)?);
+        }
+        Ok(ignore)
+    }
+
+    /// Returns a path of the walked folder relative to the root of the repository.
+    fn relative(&self, path: &Path) -> Option<PathBuf> {
+        Some(self.prefix.join(path.strip_prefix(&self.dir).ok()?))
+    }
+
+    /// Whether git ignores a file or folder of the walked folder.
+    pub fn ignores(&self, path: &Path, is_dir: bool) -> bool {
+        let Some(path) = self.relative(path) else {
+            return false;
+        };
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir || ignored != rule.negated {
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(&rule.base) else {
+                continue;
+            };
+            let matched = if rule.anchored {
+                rule.pattern.matches(relative)
+            } else {
+                relative
+                    .file_name()
+                    .is_some_and(|name| rule.pattern.matches(Path::new(name)))
+            };
+            if matched {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a repository in the temporary folder with the given files, returning its root.
+    fn repository(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "po-parser-gitignore-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".git").join("info")).unwrap();
+        for (file, text) in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn ignores_what_the_files_of_the_repository_ignore() {
+        let root = repository(
+            "rules",
+            &[
+                (".git/info/exclude", "*.bak\n"),
+                (".gitignore", "# comment\n*.mo\nbuild/\n/top.po\n!keep.mo\n"),
+                ("locales/.gitignore", "draft.po\n!top.po\n"),
+            ],
+        );
+        let ignore = Ignore::new(&root).unwrap();
+        let ignores = |path: &str, is_dir| ignore.ignores(&root.join(path), is_dir);
+        assert!(ignores("fr.bak", false));
+        assert!(ignores("fr.mo", false));
+        assert!(!ignores("keep.mo", false));
+        assert!(ignores("build", true));
+        assert!(!ignores("build", false));
+        assert!(ignores("top.po", false));
+        assert!(!ignores("locales/top.po", false));
+        assert!(!ignores("locales/draft.po", false));
+
+        let locales = ignore.enter(&root.join("locales")).unwrap();
+        assert!(locales.ignores(&root.join("locales/draft.po"), false));
+        assert!(locales.ignores(&root.join("locales/fr.mo"), false));
+        assert!(!locales.ignores(&root.join("draft.po"), false));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn reads_the_files_of_the_folders_above_the_walked_one() {
+        let root = repository(
+            "above",
+            &[
+                (".gitignore", "*.mo\n"),
+                ("locales/.gitignore", "draft.po\n"),
+                ("locales/fr/draft.po", ""),
+            ],
+        );
+        let dir = root.join("locales").join("fr");
+        let ignore = Ignore::new(&dir).unwrap();
+        assert!(ignore.ignores(&dir.join("draft.po"), false));
+        assert!(ignore.ignores(&dir.join("fr.mo"), false));
+        assert!(!ignore.ignores(&dir.join("fr.po"), false));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// A shell pattern of paths, such as `locales/**/django.po`, matched the same way on every
+/// platform.
+///
+/// `*` matches any characters but `/`, `?` a single one, `[abc]` and `[!abc]` one of a set or not,
+/// and `**` any number of folders.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    pattern: String,
+    regex: Regex,
+}
+
+/// Whether a pattern is matched against whole paths: when it has a `/` other than a trailing one.
+/// Others are matched against the name of each file and folder, as in `*.pot` or `vendor`.
+fn anchored(pattern: &str) -> bool {
+    pattern.trim_end_matches('/').contains('/')
+}
+
+/// Whether a path has characters that make it a pattern rather than the path of a file.
+pub fn is_pattern(value: &str) -> bool {
+    value.contains(['*', '?', '['])
+}
+
+/// Returns a path with `/` separators and without a leading `./`, as patterns are matched against.
+pub fn normalized(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    match path.strip_prefix("./") {
+        Some(rest) => rest.to_string(),
+        None => path,
+    }
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Pattern, String> {
+        let pattern = normalized(Path::new(pattern));
+        // A trailing `/` only tells a folder, which the pattern excludes with its files.
+        let pattern = match pattern.trim_end_matches('/') {
+            "" => pattern,
+            trimmed => trimmed.to_string(),
+        };
+        let mut regex = String::from("^");
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut index = 0;
+        while index < chars.len() {
+            let c = chars[index];
+            index += 1;
+            match c {
+                '*' if chars.get(index) == Some(&'*') => {
+                    index += 1;
+                    if chars.get(index) == Some(&'/') {
+                        index += 1;
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                '[' => {
+                    let end = chars[index..]
+                        .iter()
+                        .skip(1)
+                        .position(|&c| c == ']')
+                        .map(|position| index + position + 1)
+                        .ok_or_else(|| format!("unclosed [ in {}", pattern))?;
+                    let mut set: String = chars[index..end].iter().collect();
+                    if let Some(rest) = set.strip_prefix('!') {
+                        set = format!("^{}", rest);
+                    }
+                    regex.push('[');
+                    regex.push_str(&set.replace('\\', "\\\\").replace('[', "\\["));
+                    regex.push(']');
+                    index = end + 1;
+                }
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        let regex = Regex::new(&regex).map_err(|e| format!("{}: {}", pattern, e))?;
+        Ok(Pattern { pattern, regex })
+    }
+
+    /// Returns the folder the pattern matches files in, that of its components before the first
+    /// one with a wildcard, and whether they can be in its subfolders.
+    pub fn base(&self) -> (PathBuf, bool) {
+        let wildcard = self
+            .pattern
+            .find(['*', '?', '['])
+            .unwrap_or(self.pattern.len());
+        let (base, rest) = match self.pattern[..wildcard].rfind('/') {
+            Some(0) => (PathBuf::from("/"), &self.pattern[1..]),
+            Some(end) => (
+                PathBuf::from(&self.pattern[..end]),
+                &self.pattern[end + 1..],
+            ),
+            None => (PathBuf::from("."), self.pattern.as_str()),
+        };
+        (base, rest.contains('/') || rest.contains("**"))
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.regex.is_match(&normalized(path))
+    }
+
+    /// Whether a pattern excludes a path: matches it or one of the folders it is in, or the name
+    /// of either for a pattern without a `/`.
+    pub fn excludes(&self, path: &Path) -> bool {
+        if anchored(&self.pattern) {
+            path.ancestors().any(|path| self.matches(path))
+        } else {
+            path.iter()
+                .any(|name| self.regex.is_match(&name.to_string_lossy()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wildcards_and_sets() {
+        let pattern = Pattern::new("locales/**/[!x]?.po").unwrap();
+        assert!(pattern.matches(Path::new("locales/fr.po")));
+        assert!(pattern.matches(Path::new("./locales/fr/LC_MESSAGES/de.po")));
+        assert!(!pattern.matches(Path::new("locales/xx.po")));
+        assert!(!pattern.matches(Path::new("locales/fr.pot")));
+        assert!(Pattern::new("*.po").unwrap().matches(Path::new("fr.po")));
+        assert!(!Pattern::new("*.po").unwrap().matches(Path::new("fr/de.po")));
+        assert!(Pattern::new("[abc").is_err());
+    }
+
+    #[test]
+    fn excludes_the_files_of_a_folder() {
+        let pattern = Pattern::new("vendor/**").unwrap();
+        assert!(pattern.excludes(Path::new("vendor/x.po")));
+        assert!(pattern.excludes(Path::new("vendor/fr/x.po")));
+        assert!(!pattern.excludes(Path::new("locales/vendor/x.po")));
+        let pattern = Pattern::new("vendor/").unwrap();
+        assert!(pattern.excludes(Path::new("vendor/fr/x.po")));
+    }
+
+    #[test]
+    fn excludes_by_name_without_a_slash() {
+        let pattern = Pattern::new("*.pot").unwrap();
+        assert!(pattern.excludes(Path::new("locales/messages.pot")));
+        assert!(!pattern.excludes(Path::new("locales/fr.po")));
+        let pattern = Pattern::new("vendor").unwrap();
+        assert!(pattern.excludes(Path::new("locales/vendor/x.po")));
+    }
+
+    #[test]
+    fn tells_the_folder_of_the_files_matched() {
+        let (base, nested) = Pattern::new("locales/*.po").unwrap().base();
+        assert_eq!((base, nested), (PathBuf::from("locales"), false));
+        let (base, nested) = Pattern::new("locales/**/django.po").unwrap().base();
+        assert_eq!((base, nested), (PathBuf::from("locales"), true));
+        let (base, nested) = Pattern::new("*.po").unwrap().base();
+        assert_eq!((base, nested), (PathBuf::from("."), false));
+    }
+}
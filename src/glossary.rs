@@ -0,0 +1,194 @@
+use crate::punctuation::base_language;
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A source term and the translations it must have, by language.
+struct Term {
+    source: String,
+    /// Matches the term as a whole word, whatever its case
+    regex: Regex,
+    translations: Vec<(String, String)>,
+}
+
+/// The terms translations must use, read from a CSV file whose first row names the languages of
+/// its columns after the source one, such as `source,de,fr_CA`.
+///
+/// An empty cell leaves the translation of a term free in that language, unless it is a regional
+/// variant whose language has a column.
+pub struct Glossary {
+    terms: Vec<Term>,
+}
+
+/// A source term found in a string whose mandated translation is missing from its translation.
+pub struct Violation<'a> {
+    pub source: &'a str,
+    pub translation: &'a str,
+}
+
+impl Glossary {
+    pub fn load(path: &Path) -> io::Result<Glossary> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        let invalid = |line: usize, reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {}", path.display(), line, reason),
+            )
+        };
+        let mut rows = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let Some((index, header)) = rows.next() else {
+            return Ok(Glossary { terms: Vec::new() });
+        };
+        let languages = fields(header).ok_or_else(|| invalid(index + 1, "unclosed quote"))?;
+        let mut terms = Vec::new();
+        for (index, line) in rows {
+            let fields = fields(line).ok_or_else(|| invalid(index + 1, "unclosed quote"))?;
+            if fields.len() > languages.len() {
+                return Err(invalid(index + 1, "more fields than languages"));
+            }
+            let mut fields = fields.into_iter();
+            let source = fields.next().unwrap_or_default();
+            if source.is_empty() {
+                return Err(invalid(index + 1, "term without a source"));
+            }
+            let regex = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(&source))).unwrap();
+            let translations = languages[1..]
+                .iter()
+                .cloned()
+                .zip(fields)
+                .filter(|(_, translation)| !translation.is_empty())
+                .collect();
+            terms.push(Term {
+                source,
+                regex,
+                translations,
+            });
+        }
+        Ok(Glossary { terms })
+    }
+
+    /// Returns the terms of a source whose translation into the language a translation lacks.
+    ///
+    /// Translations for a regional variant, such as `fr_CA`, take precedence over those for its
+    /// language.
+    pub fn violations<'a>(
+        &'a self,
+        language: &str,
+        source: &str,
+        translation: &str,
+    ) -> Vec<Violation<'a>> {
+        let translation = translation.to_lowercase();
+        self.terms
+            .iter()
+            .filter(|term| term.regex.is_match(source))
+            .filter_map(|term| {
+                let find = |language: &str| {
+                    term.translations
+                        .iter()
+                        .find(|(other, _)| other.eq_ignore_ascii_case(language))
+                };
+                let (_, required) = find(language).or_else(|| find(base_language(language)))?;
+                if translation.contains(&required.to_lowercase()) {
+                    return None;
+                }
+                Some(Violation {
+                    source: &term.source,
+                    translation: required,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Splits a CSV row into its fields, unquoting them, or returns `None` if a quote is unclosed.
+fn fields(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    fields.push(field.trim().to_string());
+    Some(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loads a glossary from the text of its CSV file.
+    fn load(name: &str, text: &str) -> io::Result<Glossary> {
+        let path =
+            std::env::temp_dir().join(format!("po-parser-{}-{}.csv", name, std::process::id()));
+        fs::write(&path, text)?;
+        let glossary = Glossary::load(&path);
+        fs::remove_file(&path)?;
+        glossary
+    }
+
+    fn violations(
+        glossary: &Glossary,
+        language: &str,
+        source: &str,
+        translation: &str,
+    ) -> Vec<String> {
+        glossary
+            .violations(language, source, translation)
+            .iter()
+            .map(|violation| format!("{} -> {}", violation.source, violation.translation))
+            .collect()
+    }
+
+    #[test]
+    fn reports_terms_translated_otherwise() {
+        let glossary = load(
+            "glossary",
+            "source,fr,fr_CA,de\nfile,fichier,,Datei\n\"e-mail, address\",adresse,courriel,\n",
+        )
+        .unwrap();
+        assert!(violations(&glossary, "fr", "Open the File", "Ouvrir le fichier").is_empty());
+        assert_eq!(
+            violations(&glossary, "fr", "Open the file", "Ouvrir le document"),
+            ["file -> fichier"]
+        );
+        assert!(violations(&glossary, "fr", "Open the profile", "Ouvrir le profil").is_empty());
+        assert_eq!(
+            violations(&glossary, "fr_CA", "Open the file", "Ouvrir le document"),
+            ["file -> fichier"]
+        );
+        assert_eq!(
+            violations(&glossary, "fr_CA", "Your e-mail, address", "Votre adresse"),
+            ["e-mail, address -> courriel"]
+        );
+        assert!(violations(&glossary, "de", "Your e-mail, address", "Ihre Adresse").is_empty());
+        assert!(violations(&glossary, "it", "Open the file", "Apri il documento").is_empty());
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_rows() {
+        let error = |text| load("malformed", text).err().unwrap().to_string();
+        assert!(
+            error("source,fr\nfile,fichier,Datei\n").ends_with(":2: more fields than languages")
+        );
+        assert!(error("source,fr\n\n,fichier\n").ends_with(":3: term without a source"));
+        assert!(error("source,fr\n\"file,fichier\n").ends_with(":2: unclosed quote"));
+        assert!(load("empty", "").unwrap().terms.is_empty());
+    }
+}
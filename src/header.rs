@@ -0,0 +1,201 @@
+use crate::plurals;
+use regex::Regex;
+
+/// The fields a catalog header must declare.
+const REQUIRED_FIELDS: [&str; 3] = ["Content-Type", "Language", "PO-Revision-Date"];
+
+/// The metadata of a catalog, stored as the msgstr of its entry with an empty msgid.
+#[derive(Default)]
+pub struct Header {
+    /// The `Name: value` fields, in the order they appear in
+    pub fields: Vec<(String, String)>,
+}
+
+/// A header field that is required but missing, or present but malformed.
+pub enum HeaderProblem {
+    Missing(&'static str),
+    Invalid(String, String),
+    /// A well-formed field that contradicts another, with the reason
+    Inconsistent(String, String, String),
+}
+
+impl Header {
+    pub fn parse(msgstr: &str) -> Self {
+        let fields = msgstr
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        Header { fields }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.get("Content-Type")
+    }
+
+    /// Returns the charset declared by the Content-Type field.
+    pub fn charset(&self) -> Option<&str> {
+        self.content_type()?
+            .split(';')
+            .find_map(|param| param.trim().strip_prefix("charset="))
+            .map(str::trim)
+    }
+
+    pub fn plural_forms(&self) -> Option<&str> {
+        self.get("Plural-Forms")
+    }
+
+    /// Returns the number of plural forms declared by the Plural-Forms field.
+    pub fn nplurals(&self) -> Option<usize> {
+        self.plural_forms()?
+            .split(';')
+            .find_map(|param| param.trim().strip_prefix("nplurals="))?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.get("Language")
+    }
+
+    pub fn revision_date(&self) -> Option<&str> {
+        self.get("PO-Revision-Date")
+    }
+
+    /// Returns the required fields that are missing and the fields that are malformed.
+    pub fn problems(&self) -> Vec<HeaderProblem> {
+        let mut problems: Vec<_> = REQUIRED_FIELDS
+            .into_iter()
+            .filter(|name| self.get(name).is_none())
+            .map(HeaderProblem::Missing)
+            .collect();
+        let language = Regex::new(r"^[a-z]{2,3}([_-][A-Za-z0-9]+)*(@[a-z]+)?$").unwrap();
+        let date = Regex::new(r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}[+-]\d{4}$").unwrap();
+        let plural_forms = Regex::new(r"^nplurals\s*=\s*\d+\s*;\s*plural\s*=.+;?$").unwrap();
+        for (name, value) in &self.fields {
+            let valid = match name.as_str() {
+                "Content-Type" => self
+                    .charset()
+                    .is_some_and(|charset| !charset.is_empty() && charset != "CHARSET"),
+                "Language" => language.is_match(value),
+                "PO-Revision-Date" => date.is_match(value),
+                "Plural-Forms" => plural_forms.is_match(value),
+                _ => true,
+            };
+            if !valid {
+                problems.push(HeaderProblem::Invalid(name.clone(), value.clone()));
+            }
+        }
+        if let Some(problem) = self.plural_forms_problem() {
+            problems.push(problem);
+        }
+        problems
+    }
+
+    /// Returns the problem of a Plural-Forms field declaring a number of forms the language does
+    /// not have.
+    fn plural_forms_problem(&self) -> Option<HeaderProblem> {
+        let plural_forms = self.plural_forms()?;
+        let nplurals = self.nplurals()?;
+        let language = self.language()?;
+        let (min, max) = plurals::expected(language)?;
+        if (min..=max).contains(&nplurals) {
+            return None;
+        }
+        let expected = if min == max {
+            min.to_string()
+        } else {
+            format!("{} or {}", min, max)
+        };
+        let mut reason = format!(
+            "{} has {} plural forms, but {} are declared",
+            language, expected, nplurals
+        );
+        if plurals::is_english(plural_forms) {
+            reason.push_str(
+                " with the English expression, which may have been copied from an English catalog",
+            );
+        }
+        Some(HeaderProblem::Inconsistent(
+            String::from("Plural-Forms"),
+            plural_forms.to_string(),
+            reason,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the problems of a header, as `missing Name`, `invalid Name` or `inconsistent Name`.
+    fn problems(msgstr: &str) -> Vec<String> {
+        Header::parse(msgstr)
+            .problems()
+            .into_iter()
+            .map(|problem| match problem {
+                HeaderProblem::Missing(name) => format!("missing {}", name),
+                HeaderProblem::Invalid(name, _) => format!("invalid {}", name),
+                HeaderProblem::Inconsistent(name, _, _) => format!("inconsistent {}", name),
+            })
+            .collect()
+    }
+
+    const VALID: &str = "Content-Type: text/plain; charset=UTF-8\nLanguage: pt_BR\n\
+                         PO-Revision-Date: 2024-01-01 00:00+0000\n\
+                         Plural-Forms: nplurals=2; plural=(n > 1);\n";
+
+    #[test]
+    fn reads_the_fields_of_the_header() {
+        let header = Header::parse(VALID);
+        assert_eq!(header.charset(), Some("UTF-8"));
+        assert_eq!(header.get("language"), Some("pt_BR"));
+        assert_eq!(header.nplurals(), Some(2));
+        assert_eq!(Header::parse("").language(), None);
+    }
+
+    #[test]
+    fn reports_missing_and_malformed_fields() {
+        assert!(problems(VALID).is_empty());
+        assert_eq!(
+            problems("Content-Type: text/plain; charset=CHARSET\nLanguage: French\n"),
+            [
+                "missing PO-Revision-Date",
+                "invalid Content-Type",
+                "invalid Language"
+            ]
+        );
+        let header = VALID.replace("2024-01-01 00:00+0000", "YEAR-MO-DA HO:MI+ZONE");
+        assert_eq!(problems(&header), ["invalid PO-Revision-Date"]);
+        let header = VALID.replace("nplurals=2; plural=(n > 1);", "INTEGER");
+        assert_eq!(problems(&header), ["invalid Plural-Forms"]);
+    }
+
+    #[test]
+    fn reports_plural_forms_the_language_does_not_have() {
+        let header = VALID.replace("pt_BR", "ja");
+        assert_eq!(problems(&header), ["inconsistent Plural-Forms"]);
+        let header = VALID.replace("pt_BR", "tlh");
+        assert!(problems(&header).is_empty());
+        let header = VALID.replace("pt_BR", "ru").replace(
+            "nplurals=2; plural=(n > 1);",
+            "nplurals=2; plural=(n != 1);",
+        );
+        match Header::parse(&header).problems().as_slice() {
+            [HeaderProblem::Inconsistent(_, _, reason)] => assert_eq!(
+                reason,
+                "ru has 3 or 4 plural forms, but 2 are declared with the English expression, \
+                 which may have been copied from an English catalog"
+            ),
+            _ => panic!("expected an inconsistent Plural-Forms"),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::diff;
+use crate::report::{self, xml, Run};
+use regex::Regex;
+use std::fmt::Write;
+
+/// The styles of the report, which is a single file that can be sent to anyone.
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 70em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.3em 0.8em; text-align: left; vertical-align: top; }
+td.number { text-align: right; }
+.bar { background: #eee; width: 10em; height: 0.8em; }
+.bar div { background: #4a4; height: 100%; }
+details { margin-bottom: 1em; }
+summary { cursor: pointer; font-weight: bold; }
+.error { color: #b00; }
+.warning { color: #a60; }
+code { background: #f4f4f4; padding: 0 0.2em; white-space: pre-wrap; }
+mark { background: #dde8ff; }
+mark.missing { background: #ffd6d6; }
+mark.unexpected { background: #ffe9b3; }
+";
+
+/// Returns a string escaped for HTML, with its placeholders highlighted, those in `marked` with a
+/// class.
+fn highlight(value: &str, marked: &[String], class: &str) -> String {
+    let placeholder = Regex::new(diff::PLACEHOLDER).unwrap();
+    let mut html = String::new();
+    let mut end = 0;
+    for found in placeholder.find_iter(value) {
+        html.push_str(&xml(&value[end..found.start()]));
+        if marked.iter().any(|marked| marked == found.as_str()) {
+            let _ = write!(html, "<mark class=\"{}\">", class);
+        } else {
+            html.push_str("<mark>");
+        }
+        html.push_str(&xml(found.as_str()));
+        html.push_str("</mark>");
+        end = found.end();
+    }
+    html.push_str(&xml(&value[end..]));
+    html
+}
+
+fn count(diagnostics: &[&Diagnostic], severity: Severity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == severity)
+        .count()
+}
+
+/// Returns a diagnostic as a row of the table of its file.
+fn row(diagnostic: &Diagnostic) -> String {
+    let rule = diagnostic.rule();
+    let mut html = String::from("<tr>");
+    let _ = write!(
+        html,
+        "<td class=\"number\">{}</td><td class=\"{}\">{}</td><td title=\"{}\">{} {}</td>",
+        diagnostic.line_index,
+        diagnostic.severity.name(),
+        diagnostic.message,
+        xml(rule.description),
+        rule.code,
+        rule.name
+    );
+    html.push_str("<td>");
+    if let Some(context) = &diagnostic.context {
+        if let Some(msgctxt) = &context.msgctxt {
+            let _ = write!(html, "<div>Context: <code>{}</code></div>", xml(msgctxt));
+        }
+        let _ = write!(
+            html,
+            "<div>Source: <code>{}</code></div>",
+            highlight(&context.msgid, &diagnostic.missing, "missing")
+        );
+        if let Some(msgstr) = &context.msgstr {
+            let _ = write!(
+                html,
+                "<div>Translation: <code>{}</code></div>",
+                highlight(msgstr, &diagnostic.unexpected, "unexpected")
+            );
+        }
+    }
+    for line in report::details(diagnostic) {
+        let _ = write!(html, "<div>{}</div>", xml(line));
+    }
+    html.push_str("</td></tr>\n");
+    html
+}
+
+/// Returns a run as a standalone HTML page: how many errors and warnings each file has, the
+/// coverage of each language, then the diagnostics of each file in a section that expands when
+/// clicked.
+pub fn report(run: &Run) -> String {
+    let by_file = report::by_file(run.files, run.diagnostics);
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Translation report</title>\n");
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+    html.push_str("<h1>Translation report</h1>\n");
+
+    html.push_str("<h2>Summary</h2>\n<table>\n");
+    html.push_str("<tr><th>File</th><th>Errors</th><th>Warnings</th></tr>\n");
+    for (path, diagnostics) in &by_file {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+            xml(&path.display().to_string()),
+            count(diagnostics, Severity::Error),
+            count(diagnostics, Severity::Warning)
+        );
+    }
+    html.push_str("</table>\n");
+
+    if !run.languages.is_empty() {
+        html.push_str("<h2>Coverage</h2>\n<table>\n");
+        html.push_str(
+            "<tr><th>Language</th><th>Files</th><th>Translated</th><th>Fuzzy</th>\
+             <th>Untranslated</th><th colspan=\"2\">Coverage</th></tr>\n",
+        );
+        for (language, stats) in run.languages {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td>\
+                 <td class=\"number\">{}</td><td class=\"number\">{}</td>\
+                 <td class=\"number\">{:.1}%</td>\
+                 <td><div class=\"bar\"><div style=\"width: {:.1}%\"></div></div></td></tr>",
+                xml(language),
+                stats.files,
+                stats.translated,
+                stats.fuzzy,
+                stats.untranslated,
+                stats.coverage(),
+                stats.coverage()
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Files</h2>\n");
+    for (path, diagnostics) in &by_file {
+        if diagnostics.is_empty() {
+            continue;
+        }
+        let _ = writeln!(
+            html,
+            "<details>\n<summary>{} — {} errors, {} warnings</summary>\n<table>",
+            xml(&path.display().to_string()),
+            count(diagnostics, Severity::Error),
+            count(diagnostics, Severity::Warning)
+        );
+        html.push_str("<tr><th>Line</th><th>Problem</th><th>Rule</th><th>Entry</th></tr>\n");
+        for diagnostic in diagnostics {
+            html.push_str(&row(diagnostic));
+        }
+        html.push_str("</table>\n</details>\n");
+    }
+    html.push_str("</body>\n</html>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Context;
+    use crate::stats::Stats;
+    use std::collections::{BTreeMap, HashMap};
+    use std::path::PathBuf;
+
+    #[test]
+    fn highlights_placeholders_and_marks_those_at_fault() {
+        assert_eq!(
+            highlight("<b>{user}</b> has %d", &[String::from("%d")], "missing"),
+            "&lt;b&gt;<mark>{user}</mark>&lt;/b&gt; has <mark class=\"missing\">%d</mark>"
+        );
+    }
+
+    #[test]
+    fn reports_the_diagnostics_and_coverage_of_a_run() {
+        let files = [PathBuf::from("de.po"), PathBuf::from("fr.po")];
+        let diagnostics = [Diagnostic {
+            message: "Missing interpolation",
+            severity: Severity::Error,
+            context: Some(Context {
+                msgctxt: Some(String::from("menu")),
+                msgid: String::from("Hello {user}"),
+                msgstr: Some(String::from("Bonjour")),
+                references: Vec::new(),
+            }),
+            missing: vec![String::from("{user}")],
+            unexpected: Vec::new(),
+            path: PathBuf::from("fr.po"),
+            line_index: 3,
+            column: 1,
+            lines: vec![String::from("missing: {user}")],
+            rule: None,
+        }];
+        let languages = BTreeMap::from([(
+            String::from("fr"),
+            Stats {
+                files: 1,
+                translated: 3,
+                fuzzy: 0,
+                untranslated: 1,
+            },
+        )]);
+        let html = report(&Run {
+            files: &files,
+            diagnostics: &diagnostics,
+            languages: &languages,
+            file_languages: &HashMap::new(),
+            message_format: None,
+        });
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.ends_with("</html>"));
+        assert!(html.contains(
+            "<tr><td>fr.po</td><td class=\"number\">1</td><td class=\"number\">0</td></tr>"
+        ));
+        assert!(html.contains("<td class=\"number\">75.0%</td>"));
+        assert!(html.contains("<summary>fr.po — 1 errors, 0 warnings</summary>"));
+        assert!(!html.contains("<summary>de.po"));
+        assert!(html.contains(
+            "<div>Context: <code>menu</code></div>\
+             <div>Source: <code>Hello <mark class=\"missing\">{user}</mark></code></div>\
+             <div>Translation: <code>Bonjour</code></div><div>missing: {user}</div>"
+        ));
+    }
+}
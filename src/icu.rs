@@ -0,0 +1,349 @@
+use crate::format::{Argument, Invalid, Key};
+
+/// The plural categories CLDR defines.
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// An argument of an ICU message, such as `{name}`, `{n, number}` or `{n, plural, ...}`.
+struct Placeholder {
+    name: String,
+    /// The argument type, empty for a plain `{name}`
+    kind: String,
+    start: usize,
+    /// The branches of plural, selectordinal and select arguments, by selector
+    branches: Vec<(String, Vec<Placeholder>)>,
+}
+
+/// Reads an ICU MessageFormat pattern.
+struct Reader<'a> {
+    value: &'a str,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<char> {
+        self.value[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Reads a name, type or selector, up to the next syntax character or whitespace.
+    fn word(&mut self) -> &'a str {
+        let start = self.offset;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !matches!(c, '{' | '}' | ',' | '\''))
+        {
+            self.bump();
+        }
+        &self.value[start..self.offset]
+    }
+
+    fn invalid(&self, start: usize, reason: &'static str) -> Invalid {
+        let end = self.value[self.offset..]
+            .chars()
+            .next()
+            .map_or(self.offset, |c| self.offset + c.len_utf8());
+        Invalid {
+            offset: start,
+            directive: self.value[start..end].trim_end().to_string(),
+            reason,
+        }
+    }
+
+    fn expect(
+        &mut self,
+        expected: char,
+        start: usize,
+        reason: &'static str,
+    ) -> Result<(), Invalid> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            _ => Err(self.invalid(start, reason)),
+        }
+    }
+
+    /// Reads a message up to the `}` closing the branch it is in, or to the end.
+    fn message(&mut self, nested: bool) -> Result<Vec<Placeholder>, Invalid> {
+        let mut placeholders = Vec::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '{' => placeholders.push(self.argument()?),
+                '}' if nested => break,
+                '}' => return Err(self.invalid(self.offset, "single '}' outside of an argument")),
+                '\'' => self.quoted(),
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+        Ok(placeholders)
+    }
+
+    /// Skips an apostrophe, which quotes the syntax characters that follow it up to the next one.
+    fn quoted(&mut self) {
+        self.bump();
+        match self.peek() {
+            // A doubled apostrophe is a literal one.
+            Some('\'') => {
+                self.bump();
+            }
+            Some('{' | '}' | '#' | '|') => {
+                while let Some(c) = self.bump() {
+                    if c == '\'' {
+                        if self.peek() != Some('\'') {
+                            break;
+                        }
+                        self.bump();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn argument(&mut self) -> Result<Placeholder, Invalid> {
+        let start = self.offset;
+        self.bump();
+        self.skip_whitespace();
+        let name = self.word().to_string();
+        if name.is_empty() {
+            return Err(self.invalid(start, "argument without a name"));
+        }
+        self.skip_whitespace();
+        let mut placeholder = Placeholder {
+            name,
+            kind: String::new(),
+            start,
+            branches: Vec::new(),
+        };
+        if self.peek() == Some(',') {
+            self.bump();
+            self.skip_whitespace();
+            placeholder.kind = self.word().to_string();
+            self.skip_whitespace();
+            match placeholder.kind.as_str() {
+                "plural" | "selectordinal" | "select" => {
+                    self.expect(',', start, "missing branches")?;
+                    self.branches(&mut placeholder)?;
+                }
+                "" => return Err(self.invalid(start, "argument without a type")),
+                _ => self.style(start)?,
+            }
+        }
+        self.expect('}', start, "unclosed argument")?;
+        Ok(placeholder)
+    }
+
+    /// Skips the style of a simple argument, such as `currency` or a `::` skeleton.
+    fn style(&mut self, start: usize) -> Result<(), Invalid> {
+        if self.peek() != Some(',') {
+            return Ok(());
+        }
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => return Ok(()),
+                '}' => depth -= 1,
+                '\'' => {
+                    self.quoted();
+                    continue;
+                }
+                _ => {}
+            }
+            self.bump();
+        }
+        Err(self.invalid(start, "unclosed argument"))
+    }
+
+    fn branches(&mut self, placeholder: &mut Placeholder) -> Result<(), Invalid> {
+        let start = placeholder.start;
+        let plural = placeholder.kind != "select";
+        self.skip_whitespace();
+        if plural && self.value[self.offset..].starts_with("offset:") {
+            self.offset += "offset:".len();
+            self.skip_whitespace();
+            self.word();
+        }
+        loop {
+            self.skip_whitespace();
+            if matches!(self.peek(), Some('}') | None) {
+                break;
+            }
+            let selector_start = self.offset;
+            let selector = self.word().to_string();
+            let valid = if plural {
+                PLURAL_CATEGORIES.contains(&selector.as_str())
+                    || selector
+                        .strip_prefix('=')
+                        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            } else {
+                !selector.is_empty()
+            };
+            if !valid && plural {
+                return Err(self.invalid(selector_start, "unknown plural category"));
+            } else if !valid {
+                return Err(self.invalid(selector_start, "branch without a selector"));
+            }
+            if placeholder
+                .branches
+                .iter()
+                .any(|(other, _)| *other == selector)
+            {
+                return Err(self.invalid(selector_start, "repeated selector"));
+            }
+            self.skip_whitespace();
+            self.expect('{', selector_start, "selector without a message")?;
+            let message = self.message(true)?;
+            self.expect('}', selector_start, "unclosed branch")?;
+            placeholder.branches.push((selector, message));
+        }
+        if !placeholder
+            .branches
+            .iter()
+            .any(|(selector, _)| selector == "other")
+        {
+            return Err(self.invalid(start, "missing 'other' branch"));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the arguments of an ICU message, those nested in branches included.
+///
+/// Select-like arguments keep the names of the arguments nested in them, and select arguments
+/// their selectors, to compare the structure of messages as well.
+pub fn parse(value: &str) -> Result<Vec<Argument>, Invalid> {
+    let mut reader = Reader { value, offset: 0 };
+    let placeholders = reader.message(false)?;
+    let mut arguments = Vec::new();
+    flatten(&placeholders, &mut arguments);
+    Ok(arguments)
+}
+
+fn flatten(placeholders: &[Placeholder], arguments: &mut Vec<Argument>) {
+    for placeholder in placeholders {
+        let directive = match placeholder.kind.as_str() {
+            "" => format!("{{{}}}", placeholder.name),
+            kind => format!("{{{}, {}}}", placeholder.name, kind),
+        };
+        // Plural categories depend on the language, unlike the values a select chooses between.
+        let mut selectors: Vec<_> = match placeholder.kind.as_str() {
+            "select" => placeholder
+                .branches
+                .iter()
+                .map(|(selector, _)| selector.clone())
+                .collect(),
+            _ => Vec::new(),
+        };
+        selectors.sort();
+        let mut nested = Vec::new();
+        for (_, message) in &placeholder.branches {
+            flatten(message, &mut nested);
+        }
+        let mut names: Vec<_> = nested
+            .iter()
+            .filter_map(|argument| match &argument.key {
+                Key::Name(name) => Some(name.clone()),
+                Key::Position(_) => None,
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        arguments.push(Argument {
+            key: Key::Name(placeholder.name.clone()),
+            kind: placeholder.kind.clone(),
+            directive,
+            offset: placeholder.start,
+            selectors,
+            nested: names,
+        });
+        arguments.append(&mut nested);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the name, type, selectors and nested names of each argument of a message, or the
+    /// reason it is invalid.
+    fn arguments(value: &str) -> Vec<String> {
+        match parse(value) {
+            Ok(arguments) => arguments
+                .iter()
+                .map(|argument| {
+                    format!(
+                        "{} {} [{}] [{}]",
+                        argument.key,
+                        argument.kind,
+                        argument.selectors.join(" "),
+                        argument.nested.join(" ")
+                    )
+                })
+                .collect(),
+            Err(invalid) => vec![format!("{}: {}", invalid.reason, invalid.directive)],
+        }
+    }
+
+    #[test]
+    fn parses_simple_arguments() {
+        assert_eq!(
+            arguments("Hello {name}, you owe {amount, number, ::currency/EUR}"),
+            ["argument 'name'  [] []", "argument 'amount' number [] []"]
+        );
+        assert_eq!(arguments("It''s '{literal}' {x}"), ["argument 'x'  [] []"]);
+    }
+
+    #[test]
+    fn keeps_the_structure_of_select_like_arguments() {
+        assert_eq!(
+            arguments("{count, plural, offset:1 =0 {none} one {# by {user}} other {#}}"),
+            [
+                "argument 'count' plural [] [user]",
+                "argument 'user'  [] []"
+            ]
+        );
+        assert_eq!(
+            arguments("{gender, select, male {he} female {she} other {they}}"),
+            ["argument 'gender' select [female male other] []"]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_messages() {
+        assert_eq!(arguments("{}"), ["argument without a name: {}"]);
+        assert_eq!(arguments("{n,}"), ["argument without a type: {n,}"]);
+        assert_eq!(arguments("{n"), ["unclosed argument: {n"]);
+        assert_eq!(arguments("a } b"), ["single '}' outside of an argument: }"]);
+        assert_eq!(
+            arguments("{n, plural, one {#}}"),
+            ["missing 'other' branch: {n, plural, one {#}}"]
+        );
+        assert_eq!(
+            arguments("{n, plural, several {#} other {#}}"),
+            ["unknown plural category: several"]
+        );
+        assert_eq!(
+            arguments("{n, select, a {x} a {y} other {z}}"),
+            ["repeated selector: a"]
+        );
+        assert_eq!(
+            arguments("{n, plural, other}"),
+            ["selector without a message: other}"]
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use crate::parser::{escape, quoted, unescape};
+use console::{Style, Term};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+/// A correction fix proposes to make to an entry, with --interactive.
+pub struct Proposal<'a> {
+    pub path: &'a Path,
+    pub line_index: u32,
+    pub msgctxt: Option<&'a str>,
+    pub msgid: &'a str,
+    /// The keyword of the translation changed
+    pub keyword: &'a str,
+    pub old: &'a str,
+    /// The translation fix proposes, or none when it proposes to mark the entry fuzzy
+    pub new: Option<&'a str>,
+    /// What the correction does, such as "restored the whitespace of the source"
+    pub description: &'a str,
+}
+
+/// What is made of a correction.
+#[derive(PartialEq)]
+pub enum Decision {
+    Accept,
+    Skip,
+    /// The translation is replaced with this one instead
+    Edit(String),
+    /// The entry is marked fuzzy rather than corrected
+    Fuzzy,
+    /// This correction and all the later ones are skipped
+    Quit,
+}
+
+/// Decides what is made of each correction.
+pub type Prompt<'a> = dyn FnMut(&Proposal) -> io::Result<Decision> + 'a;
+
+const HELP: &str = "y - make this correction
+n - skip this correction
+e - type the translation instead, with the escape sequences of a PO file
+f - mark the entry fuzzy instead
+q - skip this correction and all the later ones
+? - print this help";
+
+/// Reads a line of stdin, or none at its end.
+fn read_line() -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+/// Shows a correction and asks what to make of it on stderr, as `git add -p` does, reading the
+/// answer from stdin.
+pub fn ask(proposal: &Proposal) -> io::Result<Decision> {
+    let term = Term::stderr();
+    let bold = Style::new().bold().for_stderr();
+    let red = Style::new().red().for_stderr();
+    let green = Style::new().green().for_stderr();
+    term.write_line("")?;
+    term.write_line(
+        &bold
+            .apply_to(format!(
+                "{}:{}: {}",
+                proposal.path.display(),
+                proposal.line_index,
+                proposal.description
+            ))
+            .to_string(),
+    )?;
+    if let Some(msgctxt) = proposal.msgctxt {
+        term.write_line(&quoted("msgctxt", msgctxt))?;
+    }
+    term.write_line(&quoted("msgid", proposal.msgid))?;
+    let old = quoted(proposal.keyword, proposal.old);
+    match proposal.new {
+        Some(new) => {
+            let new = quoted(proposal.keyword, new);
+            term.write_line(&red.apply_to(format!("-{}", old)).to_string())?;
+            term.write_line(&green.apply_to(format!("+{}", new)).to_string())?;
+        }
+        None => {
+            term.write_line(&green.apply_to("+#, fuzzy").to_string())?;
+            term.write_line(&format!(" {}", old))?;
+        }
+    }
+    let choices = if proposal.new.is_some() {
+        "y,n,e,f,q,?"
+    } else {
+        "y,n,e,q,?"
+    };
+    loop {
+        term.write_str(
+            &bold
+                .apply_to(format!("Make this correction [{}]? ", choices))
+                .to_string(),
+        )?;
+        // The corrections left are skipped at the end of the input, as git does.
+        let Some(answer) = read_line()? else {
+            term.write_line("")?;
+            return Ok(Decision::Quit);
+        };
+        match answer.trim() {
+            "y" => return Ok(Decision::Accept),
+            "n" => return Ok(Decision::Skip),
+            "f" if proposal.new.is_some() => return Ok(Decision::Fuzzy),
+            "q" => return Ok(Decision::Quit),
+            "e" => {
+                term.write_str(&format!("{} \"", proposal.keyword))?;
+                // On a terminal, the translation proposed is there to be edited.
+                let proposed = escape(proposal.new.unwrap_or(proposal.old));
+                let line = if term.is_term() && io::stdin().is_terminal() {
+                    term.read_line_initial_text(&proposed)?
+                } else {
+                    match read_line()? {
+                        Some(line) => line,
+                        None => return Ok(Decision::Quit),
+                    }
+                };
+                // The closing quote may be typed or not.
+                let line = line.strip_suffix('"').unwrap_or(&line);
+                let (translation, invalid) = unescape(line);
+                if let Some((_, sequence)) = invalid.first() {
+                    term.write_line(&format!("Invalid escape sequence {}", sequence))?;
+                    continue;
+                }
+                return Ok(Decision::Edit(translation));
+            }
+            _ => term.write_line(HELP)?,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+/// Returns the name of a character that is invisible and has no place in a translation, such as a
+/// control character, a zero-width space or a byte order mark.
+///
+/// Tabs and newlines, and the zero-width joiners that scripts such as Persian or Devanagari are
+/// written with, are not among them.
+pub fn name(c: char) -> Option<&'static str> {
+    match c {
+        '\t' | '\n' => None,
+        '\0' => Some("null"),
+        '\r' => Some("carriage return"),
+        '\u{1b}' => Some("escape"),
+        '\u{0}'..='\u{1f}' | '\u{7f}' => Some("control character"),
+        '\u{80}'..='\u{9f}' => Some("C1 control character"),
+        '\u{200b}' => Some("zero width space"),
+        '\u{2060}' => Some("word joiner"),
+        '\u{feff}' => Some("byte order mark"),
+        '\u{fff9}'..='\u{fffb}' => Some("interlinear annotation character"),
+        '\u{fffc}' => Some("object replacement character"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_invisible_characters() {
+        assert_eq!(name('\u{feff}'), Some("byte order mark"));
+        assert_eq!(name('\u{200b}'), Some("zero width space"));
+        assert_eq!(name('\r'), Some("carriage return"));
+        assert_eq!(name('\u{7}'), Some("control character"));
+        assert_eq!(name('\u{85}'), Some("C1 control character"));
+    }
+
+    #[test]
+    fn leaves_whitespace_and_joiners_alone() {
+        for c in ['\t', '\n', ' ', '\u{a0}', '\u{200c}', '\u{200d}', 'a'] {
+            assert_eq!(name(c), None, "{:?}", c);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::fmt::{self, Write};
+
+/// A JSON value, objects keeping their members in order.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Returns the value of a member of an object.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find(|(member, _)| member == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Writes the value with each member and element on its own line, indented by two spaces.
+    pub fn pretty(&self) -> String {
+        let mut text = String::new();
+        self.write(&mut text, Some(0)).unwrap();
+        text
+    }
+
+    fn write(&self, out: &mut String, indent: Option<usize>) -> fmt::Result {
+        let newline = |out: &mut String, depth: usize| {
+            if let Some(indent) = indent {
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent + depth));
+            }
+        };
+        let nested = indent.map(|indent| indent + 1);
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(value) => write!(out, "{}", value)?,
+            Value::Number(value) if value.is_finite() => write!(out, "{}", value)?,
+            Value::Number(_) => out.push_str("null"),
+            Value::String(value) => write_string(out, value)?,
+            Value::Array(values) if values.is_empty() => out.push_str("[]"),
+            Value::Array(values) => {
+                out.push('[');
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    newline(out, 1);
+                    value.write(out, nested)?;
+                }
+                newline(out, 0);
+                out.push(']');
+            }
+            Value::Object(members) if members.is_empty() => out.push_str("{}"),
+            Value::Object(members) => {
+                out.push('{');
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    newline(out, 1);
+                    write_string(out, name)?;
+                    out.push(':');
+                    if indent.is_some() {
+                        out.push(' ');
+                    }
+                    value.write(out, nested)?;
+                }
+                newline(out, 0);
+                out.push('}');
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value {
+        Value::String(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Value {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Value {
+        Value::Number(f64::from(value))
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Value {
+        Value::Number(value as f64)
+    }
+}
+
+/// Writes the value on a single line.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut text = String::new();
+        self.write(&mut text, None)?;
+        f.write_str(&text)
+    }
+}
+
+fn write_string(out: &mut String, value: &str) -> fmt::Result {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => write!(out, "\\u{:04x}", u32::from(c))?,
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    Ok(())
+}
+
+/// Reads JSON text, as it is written by `Value`.
+struct Reader<'a> {
+    text: &'a str,
+    index: usize,
+}
+
+impl Reader<'_> {
+    fn error<T>(&self, expected: &str) -> Result<T, String> {
+        let line = self.text[..self.index].matches('\n').count() + 1;
+        Err(format!("expected {} at line {}", expected, line))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.index..];
+        self.index += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.text[self.index..].starts_with(token);
+        if found {
+            self.index += token.len();
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        let rest = &self.text[self.index..];
+        if self.eat("null") {
+            Ok(Value::Null)
+        } else if self.eat("true") {
+            Ok(Value::Bool(true))
+        } else if self.eat("false") {
+            Ok(Value::Bool(false))
+        } else if rest.starts_with('"') {
+            Ok(Value::String(self.string()?))
+        } else if self.eat("[") {
+            let mut values = Vec::new();
+            if !self.eat("]") {
+                loop {
+                    values.push(self.value()?);
+                    if self.eat("]") {
+                        break;
+                    }
+                    if !self.eat(",") {
+                        return self.error("',' or ']'");
+                    }
+                }
+            }
+            Ok(Value::Array(values))
+        } else if self.eat("{") {
+            let mut members = Vec::new();
+            if !self.eat("}") {
+                loop {
+                    self.skip_whitespace();
+                    let name = self.string()?;
+                    if !self.eat(":") {
+                        return self.error("':'");
+                    }
+                    members.push((name, self.value()?));
+                    if self.eat("}") {
+                        break;
+                    }
+                    if !self.eat(",") {
+                        return self.error("',' or '}'");
+                    }
+                }
+            }
+            Ok(Value::Object(members))
+        } else {
+            let length = rest
+                .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+                .unwrap_or(rest.len());
+            match rest[..length].parse() {
+                Ok(number) if length > 0 => {
+                    self.index += length;
+                    Ok(Value::Number(number))
+                }
+                _ => self.error("a value"),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if !self.text[self.index..].starts_with('"') {
+            return self.error("a string");
+        }
+        self.index += 1;
+        let mut value = String::new();
+        let mut chars = self.text[self.index..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.index += offset + 1;
+                    return Ok(value);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let code = |chars: &mut std::str::CharIndices| {
+                                let digits: String =
+                                    chars.by_ref().take(4).map(|(_, c)| c).collect();
+                                u32::from_str_radix(&digits, 16).ok()
+                            };
+                            let high = code(&mut chars);
+                            // Characters outside the Basic Multilingual Plane are written as
+                            // surrogate pairs.
+                            let code = match high {
+                                Some(high @ 0xD800..=0xDBFF) => {
+                                    let rest = chars.as_str();
+                                    match rest.strip_prefix("\\u") {
+                                        Some(_) => {
+                                            chars.next();
+                                            chars.next();
+                                            code(&mut chars)
+                                                .filter(|low| (0xDC00..=0xDFFF).contains(low))
+                                                .map(|low| {
+                                                    0x10000
+                                                        + ((high - 0xD800) << 10)
+                                                        + (low - 0xDC00)
+                                                })
+                                        }
+                                        None => None,
+                                    }
+                                }
+                                code => code,
+                            };
+                            code.and_then(char::from_u32).unwrap_or('\u{FFFD}')
+                        }
+                        Some(c) => c,
+                        None => break,
+                    };
+                    value.push(escaped);
+                }
+                c => value.push(c),
+            }
+        }
+        self.index = self.text.len();
+        self.error("'\"'")
+    }
+}
+
+/// Parses JSON text.
+pub fn parse(text: &str) -> Result<Value, String> {
+    let mut reader = Reader { text, index: 0 };
+    let value = reader.value()?;
+    reader.skip_whitespace();
+    if reader.index < text.len() {
+        return reader.error("the end of the text");
+    }
+    Ok(value)
+}
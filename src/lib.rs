@@ -0,0 +1,51 @@
+//! Checks the translations of gettext catalogs: that they keep the placeholders, markup and
+//! formats of their source, and are well formed.
+//!
+//! [`check`] returns the diagnostics of a catalog with the [`CheckOptions`] of a run, and
+//! [`Catalog`] reads and writes one entry by entry; the `catalog` module also converts catalog
+//! files to another charset and compares them with their template, and `check::fix_file` makes
+//! the corrections of the fix command. Library users check entries with rules of their own by
+//! implementing [`Rule`] and adding them to `CheckOptions::rules`.
+
+pub mod bidi;
+pub mod catalog;
+pub mod check;
+pub mod consistency;
+pub mod diagnostic;
+pub mod diff;
+pub mod explain;
+pub mod format;
+pub mod gitignore;
+pub mod glob;
+pub mod glossary;
+pub mod header;
+pub mod html;
+pub mod icu;
+pub mod interactive;
+pub mod invisible;
+pub mod json;
+pub mod links;
+pub mod markdown;
+pub mod markup;
+pub mod mojibake;
+pub mod normalization;
+pub mod numbers;
+pub mod parser;
+pub mod plurals;
+pub mod punctuation;
+pub mod report;
+pub mod rules;
+pub mod spelling;
+pub mod stats;
+pub mod strftime;
+pub mod suppressions;
+pub mod template;
+pub mod toml;
+pub mod typography;
+pub mod unicode_tables;
+pub mod walk;
+
+pub use catalog::Catalog;
+pub use check::{check, CheckContext, CheckOptions, Rule};
+pub use diagnostic::{Diagnostic, Severity};
+pub use parser::Entry;
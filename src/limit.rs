@@ -0,0 +1,104 @@
+use crate::diagnostic::Diagnostic;
+use crate::parser::escape;
+use crate::report;
+use crate::totals::counted;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// What a diagnostic is a repetition of: the same rule reporting the same problem of the same
+/// message, whichever catalog it is in.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Repeated {
+    rule: &'static str,
+    message: &'static str,
+    msgctxt: Option<String>,
+    msgid: String,
+    details: String,
+}
+
+/// Which diagnostics are printed, so that a message broken in every catalog, or a catalog broken
+/// throughout, does not bury the others.
+///
+/// The diagnostics that are not printed still fail the run.
+#[derive(Default)]
+pub struct Limit {
+    /// The most diagnostics of a catalog that are printed
+    per_file: Option<usize>,
+    /// Whether the diagnostics that repeat an earlier one are left out
+    deduplicate: bool,
+    /// The diagnostics printed and left out of each catalog
+    counts: HashMap<PathBuf, (usize, usize)>,
+    /// How many times each diagnostic about a message was reported
+    repeated: BTreeMap<Repeated, usize>,
+}
+
+impl Limit {
+    pub fn new(per_file: Option<usize>, deduplicate: bool) -> Limit {
+        Limit {
+            per_file,
+            deduplicate,
+            ..Limit::default()
+        }
+    }
+
+    /// Whether a diagnostic is printed, counting it either way.
+    pub fn shows(&mut self, diagnostic: &Diagnostic) -> bool {
+        if let (true, Some(context)) = (self.deduplicate, &diagnostic.context) {
+            let key = Repeated {
+                rule: diagnostic.rule().code,
+                message: diagnostic.message,
+                msgctxt: context.msgctxt.clone(),
+                msgid: context.msgid.clone(),
+                details: report::details(diagnostic).join("; "),
+            };
+            let repeated = self.repeated.entry(key).or_default();
+            *repeated += 1;
+            if *repeated > 1 {
+                return false;
+            }
+        }
+        let (shown, hidden) = self.counts.entry(diagnostic.path.clone()).or_default();
+        if self.per_file.is_some_and(|max| *shown >= max) {
+            *hidden += 1;
+            return false;
+        }
+        *shown += 1;
+        true
+    }
+
+    /// Returns what was left out, once the run is over.
+    pub fn notes(&self) -> Vec<String> {
+        let mut notes = Vec::new();
+        for (repeated, count) in &self.repeated {
+            if *count > 1 {
+                let details = if repeated.details.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", repeated.details)
+                };
+                notes.push(format!(
+                    "{} {} of \"{}\"{} was reported {} times, printed once",
+                    repeated.rule,
+                    repeated.message,
+                    escape(&repeated.msgid),
+                    details,
+                    count
+                ));
+            }
+        }
+        let mut hidden: Vec<_> = self
+            .counts
+            .iter()
+            .filter(|(_, (_, hidden))| *hidden > 0)
+            .collect();
+        hidden.sort();
+        for (path, (_, hidden)) in hidden {
+            notes.push(format!(
+                "{} of {} not printed, past --max-errors-per-file",
+                counted(*hidden, "diagnostic", "diagnostics"),
+                path.display()
+            ));
+        }
+        notes
+    }
+}
@@ -0,0 +1,87 @@
+use regex::Regex;
+
+/// A URL or email address of a string.
+pub struct Link<'a> {
+    pub text: &'a str,
+    pub offset: usize,
+}
+
+/// Returns the URLs, such as `https://example.com/a` or `www.example.com`, and email addresses of
+/// a string, in order.
+///
+/// Punctuation a URL ends with is taken for that of the sentence it is in.
+pub fn links(value: &str) -> Vec<Link<'_>> {
+    let link = Regex::new(
+        r"(?i)\b(?:(?:https?|ftp)://|www\.)[^\s<>{}|\\^`\x22']+|\b[\w.+-]+@[\w-]+(?:\.[\w-]+)+",
+    )
+    .unwrap();
+    link.find_iter(value)
+        .map(|found| {
+            let mut text = found
+                .as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            // A closing parenthesis belongs to the URL only if it opens one.
+            while text.ends_with(')') && text.matches('(').count() < text.matches(')').count() {
+                text = text[..text.len() - 1].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            }
+            Link {
+                text,
+                offset: found.start(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the links of `actual` that `expected` has fewer of.
+pub fn difference<'a, 'b>(expected: &[Link], actual: &'b [Link<'a>]) -> Vec<&'b Link<'a>> {
+    let mut remaining: Vec<_> = expected.iter().map(|link| link.text).collect();
+    actual
+        .iter()
+        .filter(
+            |link| match remaining.iter().position(|other| *other == link.text) {
+                Some(index) => {
+                    remaining.remove(index);
+                    false
+                }
+                None => true,
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(value: &str) -> Vec<&str> {
+        links(value).iter().map(|link| link.text).collect()
+    }
+
+    #[test]
+    fn finds_urls_and_email_addresses() {
+        assert_eq!(
+            texts("See https://example.com/a?b=1, or www.example.org. Mail help@example.co.uk!"),
+            [
+                "https://example.com/a?b=1",
+                "www.example.org",
+                "help@example.co.uk"
+            ]
+        );
+        assert_eq!(
+            texts("(see https://en.wikipedia.org/wiki/Rust_(language))"),
+            ["https://en.wikipedia.org/wiki/Rust_(language)"]
+        );
+        assert!(texts("example.com and @user").is_empty());
+    }
+
+    #[test]
+    fn compares_links_by_their_text() {
+        let source = links("https://a.example and https://b.example");
+        let translation = links("https://b.example et https://a.example/fr");
+        let changed: Vec<_> = difference(&source, &translation)
+            .iter()
+            .map(|link| link.text)
+            .collect();
+        assert_eq!(changed, ["https://a.example/fr"]);
+    }
+}
@@ -0,0 +1,137 @@
+use clap::ValueEnum;
+use console::{Style, Term};
+use indicatif::ProgressBar;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the program tells about what it does, diagnostics aside.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    const ALL: [Level; 6] = [
+        Level::Off,
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    fn label(self) -> (Style, &'static str) {
+        match self {
+            Level::Off => (Style::new(), ""),
+            Level::Error => (Style::new().red(), "[ERROR]"),
+            Level::Warn => (Style::new().yellow(), "[WARN] "),
+            Level::Info => (Style::new().cyan(), "[INFO] "),
+            Level::Debug => (Style::new().white(), "[DEBUG]"),
+            Level::Trace => (Style::new().black().bright(), "[TRACE]"),
+        }
+    }
+
+    fn parse(name: &str) -> Option<Level> {
+        let name = name.trim().to_ascii_lowercase();
+        let name = if name == "warning" { "warn" } else { &name };
+        Level::ALL
+            .into_iter()
+            .find(|level| format!("{:?}", level).to_ascii_lowercase() == name)
+    }
+
+    /// Returns the level `RUST_LOG` sets for this program: that of a `po_parser=level` directive,
+    /// or of a bare `level` one.
+    pub fn from_env(value: &str) -> Option<Level> {
+        let mut level = None;
+        for directive in value.split(',') {
+            match directive.split_once('=') {
+                Some((target, name)) if matches!(target.trim(), "po_parser" | "po-parser") => {
+                    return Level::parse(name);
+                }
+                Some(_) => {}
+                None => level = Level::parse(directive).or(level),
+            }
+        }
+        level
+    }
+}
+
+/// When output is colored.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// When it goes to a terminal and the NO_COLOR environment variable is not set
+    Auto,
+    Always,
+    Never,
+}
+
+/// Enables or disables the colors of stdout and stderr.
+pub fn set_colors(choice: ColorChoice) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let (stdout, stderr) = match choice {
+        ColorChoice::Always => (true, true),
+        ColorChoice::Never => (false, false),
+        ColorChoice::Auto if no_color => (false, false),
+        ColorChoice::Auto => (Term::stdout().is_term(), Term::stderr().is_term()),
+    };
+    console::set_colors_enabled(stdout);
+    console::set_colors_enabled_stderr(stderr);
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Prints a message on stderr if its level is enabled, above the progress bar.
+pub fn log(pb: &ProgressBar, level: Level, message: impl Display) {
+    if !enabled(level) {
+        return;
+    }
+    let (style, label) = level.label();
+    let text = format!("{} {}", label, message);
+    pb.suspend(|| eprintln!("{}", style.for_stderr().apply_to(text)));
+}
+
+pub fn error(pb: &ProgressBar, message: impl Display) {
+    log(pb, Level::Error, message)
+}
+
+pub fn info(pb: &ProgressBar, message: impl Display) {
+    log(pb, Level::Info, message)
+}
+
+pub fn debug(pb: &ProgressBar, message: impl Display) {
+    log(pb, Level::Debug, message)
+}
+
+pub fn trace(pb: &ProgressBar, message: impl Display) {
+    log(pb, Level::Trace, message)
+}
+
+/// Prints a message for the user on stderr whatever the level, above the progress bar, so that it
+/// is kept apart from a report printed on stdout.
+pub fn status(pb: &ProgressBar, text: impl Display) {
+    pb.suspend(|| eprintln!("{}", text));
+}
+
+/// Prints results, such as diagnostics, on stdout, above the progress bar.
+///
+/// Write errors are ignored, so that piping the output into a command that stops reading it, such
+/// as `head`, does not make the program panic.
+pub fn output(pb: &ProgressBar, text: impl Display) {
+    pb.suspend(|| {
+        let _ = writeln!(io::stdout(), "{}", text);
+    });
+}
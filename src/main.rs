@@ -1,94 +1,821 @@
-#![allow(unused)]
-
-use clap::Parser;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 use indicatif::{ProgressBar, ProgressStyle};
 use regex::Regex;
-use std::fmt::format;
-use std::fs::{read_dir, File};
-use std::io::{self, BufRead, Write};
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
 use tokio::task;
 
+mod baseline;
+mod completions;
+mod config;
+mod editor;
+mod git;
+mod limit;
+mod logging;
+mod man;
+mod totals;
+mod tui;
+mod watch;
+
+use baseline::Baseline;
+use limit::Limit;
+use po_parser::check::{
+    check_file, combined, default_rules, fix_file, is_catalog, is_of_languages, sort, CheckOptions,
+    Preset, ProtectedTerm, Summary, DEFAULT_PATTERN,
+};
+use po_parser::consistency::Translation;
+use po_parser::diagnostic::{Diagnostic, Severity};
+use po_parser::glob::Pattern;
+use po_parser::glossary::Glossary;
+use po_parser::interactive::{Decision, Prompt, Proposal};
+use po_parser::parser::quoted;
+use po_parser::report::OutputFormat;
+use po_parser::rules::RuleInfo;
+use po_parser::spelling::Spellchecker;
+use po_parser::stats::Stats;
+use po_parser::template::Template;
+use po_parser::{
+    catalog, consistency, diagnostic, explain, interactive, json, parser, report, rules, stats,
+    toml, typography, walk,
+};
+use totals::{Timing, Totals};
+
 /// Search for interpolation errors in .po files and display the lines containing them.
 #[derive(Parser)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    args_override_self = true,
+    after_help = "Exits with 0 when no error is found, 1 when some are, 2 on wrong arguments or \
+                  settings, and 3 when a file cannot be read or written."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// The arguments of the check command, which is run when no command is given
+    #[command(flatten)]
+    args: Args,
+}
+
+/// The catalogs a run checks and how, given to the check, stats and fix commands.
+#[derive(clap::Args)]
+#[command(args_override_self = true)]
 struct Args {
-    /// The path to the .po files folder
-    path: std::path::PathBuf,
-    /// The regex pattern to match translation interpolations
-    #[arg(short, long, default_value_t = String::from(r"\{\{.*\}\}|\{.*\}"))]
-    pattern: String,
-}
-
-struct InterpolationParams<'a> {
-    pb: &'a ProgressBar,
-    path: &'a Path,
-    pattern: &'a String,
-    last_msgid: &'a str,
-    line: &'a str,
-    line_index: u32,
-}
-
-fn find_missing_interpolations(params: InterpolationParams) -> Option<String> {
-    let regex = Regex::new(&params.pattern).unwrap();
-    let msgid_interpolations: Vec<_> = regex.find_iter(params.last_msgid).collect();
-    let msgstr_interpolations: Vec<_> = regex.find_iter(params.line).collect();
-    if msgid_interpolations.len() != msgstr_interpolations.len() {
-        for cap in msgid_interpolations {
-            let regex_match = &cap.as_str();
-            if !params.line.contains(regex_match)
-                && params.line != "msgstr \"\""
-                && params.line != "\"\""
-            {
-                return Some(format!(
-                    "{}\x1b[31m[ERROR] Missing interpolation in {}:{}\n\t{}\n\t{}\x1b[0m",
-                    params.pb.message(),
-                    params.path.display(),
-                    params.line_index,
-                    params.last_msgid,
-                    params.line
-                ));
-            }
+    /// The .po and .pot files to check, folders of them, or patterns such as
+    /// `locales/**/django.po`; `-` reads a catalog from stdin
+    #[arg(required_unless_present = "stdin", value_name = "PATH")]
+    paths: Vec<std::path::PathBuf>,
+    /// Read the settings of this file instead of the po-parser.toml of the folder checked or of
+    /// one above it
+    ///
+    /// Its keys are the names of flags, such as `fail-on-fuzzy = true` or
+    /// `enable = ["PO033", "PO034"]`, which the command line overrides.
+    /// A table such as `[language.fr]` has the `enable`, `disable` and `severity` of the catalogs
+    /// of a language, by their Language header or else their folder.
+    #[arg(long, value_name = "FILE", conflicts_with = "no_config")]
+    config: Option<std::path::PathBuf>,
+    /// Do not read a po-parser.toml file
+    #[arg(long)]
+    no_config: bool,
+    /// Read a catalog from stdin, as the `-` path does
+    #[arg(long)]
+    stdin: bool,
+    /// The name of the catalog read from stdin, which diagnostics are reported in, and whose
+    /// extension tells a template
+    #[arg(long, value_name = "NAME", default_value = "<stdin>")]
+    stdin_filename: std::path::PathBuf,
+    /// Also check the catalogs of the subfolders, such as `locales/<lang>/LC_MESSAGES/*.po`,
+    /// hidden ones aside
+    #[arg(short, long)]
+    recursive: bool,
+    /// Do not check the catalogs this pattern matches, such as `vendor/**` or `*.pot`, besides
+    /// those of the .poparserignore file of each folder checked
+    ///
+    /// Patterns without a `/` match the name of a file or folder, the others its whole path
+    /// relative to the folder checked.
+    #[arg(long, value_name = "GLOB", value_parser = Pattern::new)]
+    exclude: Vec<Pattern>,
+    /// Only check the catalogs of these languages, such as `fr,de,pt-BR`, by their Language header,
+    /// or else the folder above LC_MESSAGES or their name; a language without a region also
+    /// matches its regions
+    #[arg(long, value_name = "LANGUAGES", value_delimiter = ',')]
+    lang: Vec<String>,
+    /// Only check the entries whose msgid or msgctxt this regex matches, such as `^Save` to look
+    /// into a string across every catalog
+    #[arg(long, value_name = "REGEX", value_parser = Regex::new)]
+    filter: Option<Regex>,
+    /// Only check the catalogs added or modified since this commit, HEAD when none is given, as
+    /// git tells, for pre-push hooks and the CI of pull requests
+    #[arg(long, value_name = "REF", num_args = 0..=1, default_missing_value = "HEAD")]
+    changed: Option<String>,
+    /// Also check the catalogs git ignores, by .gitignore files or .git/info/exclude
+    #[arg(long)]
+    no_ignore: bool,
+    /// Print the catalogs that would be checked, once every pattern, ignore file and language
+    /// leaves them out or not, without checking them; -vv also prints why the others are skipped
+    #[arg(long, conflicts_with_all = ["watch", "tui", "open"])]
+    list_files: bool,
+    /// Also walk the folders symbolic links point to, such as the catalogs brands share, checking
+    /// their catalogs by each of their paths or, with `=once`, once; a link to a folder it is in
+    /// is skipped
+    #[arg(
+        long,
+        value_enum,
+        value_name = "HOW",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "each"
+    )]
+    follow_symlinks: Option<walk::Symlinks>,
+    /// The regex pattern to match translation interpolations, given several times for projects
+    /// mixing placeholder styles such as `{{x}}`, `%s` and `%(x)s`
+    ///
+    /// Defaults to matching `{name}` and `{{name}}` placeholders, each on its own even when
+    /// several share a line, unless a preset is used.
+    #[arg(short, long, value_parser = pattern)]
+    pattern: Vec<String>,
+    /// Check the placeholders of a framework: every entry as a format string of its language, or
+    /// with its pattern of interpolations, instead of the default pattern
+    #[arg(long, value_enum)]
+    preset: Option<Preset>,
+    /// Report fuzzy entries as errors instead of skipping them
+    #[arg(long)]
+    fail_on_fuzzy: bool,
+    /// Report untranslated entries as errors
+    #[arg(long)]
+    fail_on_untranslated: bool,
+    /// Print the number of translated, fuzzy and untranslated entries of each language
+    #[arg(long)]
+    coverage: bool,
+    /// Fail when the percentage of translated entries of a language is below this
+    #[arg(long, value_name = "PERCENT")]
+    min_coverage: Option<f64>,
+    /// Check obsolete (#~) entries instead of ignoring them
+    #[arg(long)]
+    include_obsolete: bool,
+    /// Report malformed constructs that are otherwise parsed on a best-effort basis
+    #[arg(long)]
+    strict_syntax: bool,
+    /// Compare the placeholders of the source and translation as sets, ignoring how many times
+    /// each one is repeated
+    #[arg(long)]
+    ignore_repeats: bool,
+    /// Check that translations have exactly one accelerator marked with this character, such as
+    /// `&` or `_`, when their source has one
+    #[arg(long = "accelerator", value_name = "CHAR")]
+    accelerators: Vec<char>,
+    /// Do not report translations that drop, add or change the punctuation ending their source
+    #[arg(long)]
+    no_punctuation: bool,
+    /// Do not report translations that drop or change the URLs and email addresses of their source
+    #[arg(long)]
+    no_links: bool,
+    /// Report translations identical to their source, unless it has nothing to translate
+    #[arg(long)]
+    same_as_source: bool,
+    /// A name that is left untranslated, not counted as words of the source by --same-as-source
+    #[arg(long = "brand", value_name = "NAME")]
+    brands: Vec<String>,
+    /// Report translations whose length is outside this range of percentages of the length of
+    /// their source, such as `30,300`
+    #[arg(long, value_name = "MIN,MAX", value_parser = length_ratio)]
+    length_ratio: Option<(f64, f64)>,
+    /// Report messages translated differently in catalogs of the same language
+    #[arg(long)]
+    cross_file: bool,
+    /// Report translations that do not use the translations of terms this CSV file mandates
+    #[arg(long, value_name = "FILE")]
+    glossary: Option<std::path::PathBuf>,
+    /// A product name, trademark or legal phrase that translations must keep as it is
+    #[arg(long = "protect", value_name = "TERM")]
+    protected_terms: Vec<String>,
+    /// A file of protected terms, one per line
+    #[arg(long, value_name = "FILE")]
+    protected_terms_file: Option<std::path::PathBuf>,
+    /// Report the words of translations missing from the Hunspell dictionary of their language,
+    /// as warnings
+    #[arg(long)]
+    spellcheck: bool,
+    /// The folder of the Hunspell dictionaries, named after their language, such as `de_DE.dic`
+    #[arg(long, value_name = "DIR", default_value = "/usr/share/hunspell")]
+    dictionaries: std::path::PathBuf,
+    /// A file of words the spellchecker accepts in every language, one per line
+    #[arg(long, value_name = "FILE")]
+    wordlist: Option<std::path::PathBuf>,
+    /// Check that translations keep the code spans, links, emphasis and list markers of Markdown
+    /// sources
+    #[arg(long)]
+    markdown: bool,
+    /// Report translations that drop or change the numbers of their source
+    #[arg(long)]
+    numbers: bool,
+    /// Do not report translations that are not in Unicode Normalization Form C
+    #[arg(long)]
+    no_nfc: bool,
+    /// Do not check this typography rule of French catalogs
+    #[arg(long = "no-typography", value_enum, value_name = "RULE")]
+    disabled_typography: Vec<typography::Rule>,
+    /// Rewrite the catalogs with their translations in Normalization Form C, instead of reporting
+    /// those that are not
+    #[arg(long)]
+    normalize: bool,
+    /// Print the corrections of fix and --normalize without rewriting the catalogs
+    #[arg(long)]
+    dry_run: bool,
+    /// Print the corrections of fix and --normalize as a unified diff instead of rewriting the
+    /// catalogs, for `git apply` to apply them, and no diagnostics
+    #[arg(long, conflicts_with_all = ["dry_run", "stdin", "watch", "tui"])]
+    diff: bool,
+    /// Ask before each correction of fix and --normalize whether to make it, skip it, type the
+    /// translation instead or mark the entry fuzzy, as `git add -p` does, before anything is
+    /// written
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["dry_run", "stdin", "watch", "tui"]
+    )]
+    interactive: bool,
+    /// The mark a translator puts where the placeholders a translation lacks go, for fix to
+    /// insert them there in the order of the source
+    #[arg(long, value_name = "TEXT", default_value = "@@")]
+    cursor: String,
+    /// Turn on a rule that is off by default, by its code or name, such as PO033 or
+    /// same-as-source, as its own flag does
+    #[arg(long, value_name = "RULE", value_parser = rule)]
+    enable: Vec<&'static RuleInfo>,
+    /// Do not report the diagnostics of a rule, by its code or name
+    #[arg(long, value_name = "RULE", value_parser = rule)]
+    disable: Vec<&'static RuleInfo>,
+    /// Report the diagnostics of a rule with a severity, as in `PO001=warning`
+    #[arg(long, value_name = "RULE=SEVERITY", value_parser = rule_severity)]
+    severity: Vec<(&'static RuleInfo, Severity)>,
+    /// How diagnostics are printed, text ones as soon as they are found and the others once every
+    /// catalog is checked
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Print text diagnostics on a single line with this template, such as
+    /// `{file}:{line}: {rule}: {detail}`, for the error format of an editor
+    ///
+    /// The fields are file, line, column, rule, name, severity, message, detail, msgctxt, msgid,
+    /// msgstr and references; literal braces are written `{{` and `}}`.
+    #[arg(long, value_name = "TEMPLATE", value_parser = Template::parse)]
+    message_format: Option<Template>,
+    /// When to color the output
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = logging::ColorChoice::Auto)]
+    color: logging::ColorChoice,
+    /// Check this many catalogs at a time, as many as the computer has processors by default
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<std::num::NonZeroUsize>,
+    /// Only print diagnostics and errors, without progress
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Also print what is being done, then every step of it when repeated; RUST_LOG=debug or
+    /// RUST_LOG=trace do the same
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Write the report of --format to this file, and print the diagnostics as text instead
+    #[arg(long, value_name = "FILE")]
+    output: Option<std::path::PathBuf>,
+    /// Print at most this many diagnostics of each catalog, warnings included, the others still
+    /// failing the run
+    #[arg(long, value_name = "N")]
+    max_errors_per_file: Option<usize>,
+    /// Print a diagnostic once when its rule reports it on the same message of several catalogs,
+    /// with how many times it was reported once the run is over
+    #[arg(long)]
+    deduplicate: bool,
+    /// Print the diagnostics of each catalog as soon as they are found, instead of sorted by line
+    /// once it is checked
+    #[arg(long)]
+    unsorted: bool,
+    /// Stop checking at the first error, once it is printed
+    #[arg(long)]
+    fail_fast: bool,
+    /// Succeed with up to this many errors, for a migration to fix known ones
+    #[arg(long, value_name = "N")]
+    max_errors: Option<usize>,
+    /// Fail with more than this many warnings, which do not fail the run otherwise
+    #[arg(long, value_name = "N")]
+    max_warnings: Option<usize>,
+    /// Keep running once the catalogs are checked, and check again those that change or are
+    /// added, printing their diagnostics as text
+    #[arg(long, conflicts_with = "stdin")]
+    watch: bool,
+    /// Browse the diagnostics by file once every catalog is checked, to read the entry of each
+    /// one and acknowledge those that are fine. Acknowledged diagnostics do not fail the run, and
+    /// are added to the file of --baseline when one is given
+    #[arg(
+        long,
+        conflicts_with_all = ["stdin", "watch", "output", "format", "github", "message_format"]
+    )]
+    tui: bool,
+    /// Open the catalog of the first diagnostic at its line in $VISUAL or $EDITOR once the run is
+    /// over
+    #[arg(long, conflicts_with_all = ["stdin", "watch", "tui"])]
+    open: bool,
+    /// The command --open and the o key of --tui run, in which {path}, {line} and {column} are
+    /// replaced, such as `code -g {path}:{line}:{column}`
+    #[arg(long, value_name = "COMMAND")]
+    editor: Option<String>,
+    /// Print the errors and warnings of each rule and the slowest catalogs once the run is over,
+    /// besides its summary
+    #[arg(long)]
+    stats: bool,
+    /// Print GitHub Actions annotations, as --format github does
+    #[arg(long, conflicts_with = "format")]
+    github: bool,
+    /// Record the diagnostics of the run in this JSON file, for --baseline to accept them, and
+    /// succeed
+    #[arg(long, value_name = "FILE", conflicts_with = "baseline")]
+    write_baseline: Option<std::path::PathBuf>,
+    /// Do not report the diagnostics recorded in this file by --write-baseline, so that only new
+    /// ones fail the run
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<std::path::PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check catalogs and report their diagnostics, as is done when no command is given
+    Check(Args),
+    /// Print the number of translated, fuzzy and untranslated entries of each language, without
+    /// reporting diagnostics
+    Stats(Args),
+    /// Rewrite catalogs with the corrections that are safe to make: the placeholders a translator
+    /// marked the place of inserted, the leading and trailing whitespace and newlines of the
+    /// source restored, translations put in Normalization Form C, and the entries that still have
+    /// errors marked fuzzy
+    Fix(Args),
+    /// Rewrite catalogs in another charset, with the Content-Type of their header
+    Convert {
+        /// The .po and .pot files to rewrite
+        #[arg(required = true, value_name = "FILE")]
+        paths: Vec<PathBuf>,
+        /// The charset to write them in, such as ISO-8859-1
+        #[arg(long, value_name = "CHARSET", default_value = "UTF-8", value_parser = charset)]
+        to: String,
+    },
+    /// List the messages of a template that a catalog lacks, and those it has that the template
+    /// does not, failing when there are some
+    Compare {
+        /// The .po file
+        catalog: PathBuf,
+        /// The .pot file it is translated from
+        template: PathBuf,
+    },
+    /// Print what a rule reports and why, with an entry it reports and the same entry fixed, and
+    /// how to suppress it, or list the rules
+    Explain {
+        /// The code or name of the rule, such as PO001 or missing-interpolation
+        #[arg(value_parser = rule)]
+        rule: Option<&'static RuleInfo>,
+    },
+    /// Print the script completing the arguments of po-parser in a shell, to be sourced by it or
+    /// installed where it looks for completions
+    Completions {
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+    /// Print the manual page of po-parser, in roff, as `man` reads it
+    Man,
+}
+
+/// What a run does with the catalogs it checks.
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Check,
+    Stats,
+    Fix,
+}
+
+/// Parses a charset supported by encoding_rs, by one of its labels.
+fn charset(value: &str) -> Result<String, String> {
+    match encoding_rs::Encoding::for_label(value.as_bytes()) {
+        Some(_) => Ok(value.to_string()),
+        None => Err(format!("unknown charset {}", value)),
+    }
+}
+
+/// Parses a rule given by its code or name.
+fn rule(value: &str) -> Result<&'static RuleInfo, String> {
+    rules::find(value).ok_or_else(|| format!("unknown rule {}", value))
+}
+
+/// Parses the `RULE=SEVERITY` value of `--severity`.
+fn rule_severity(value: &str) -> Result<(&'static RuleInfo, Severity), String> {
+    let (name, severity) = value
+        .split_once('=')
+        .ok_or_else(|| String::from("expected a rule and a severity separated by ="))?;
+    let severity = match severity.trim() {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        other => {
+            return Err(format!(
+                "unknown severity {}, expected error or warning",
+                other
+            ))
         }
+    };
+    Ok((rule(name.trim())?, severity))
+}
+
+/// Returns the terms given with `--protect` and those of `--protected-terms-file`.
+fn protected_terms(args: &Args) -> io::Result<Vec<ProtectedTerm>> {
+    let mut terms = args.protected_terms.clone();
+    if let Some(path) = &args.protected_terms_file {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        terms.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from),
+        );
     }
-    None
-}
-
-fn process_file(pb: &ProgressBar, path: &Path, pattern: &String) -> io::Result<Vec<String>> {
-    let file = File::open(&path)?;
-    let reader = io::BufReader::new(file);
-    let mut last_msgid = String::new();
-    let mut lines = reader.lines();
-    let mut line_index = 1;
-    let mut errors = Vec::new();
-    for line in lines {
-        let mut line = line?;
-        if line.starts_with("msgid") {
-            last_msgid = line.clone();
-        } else if line.starts_with("msgstr") || line.starts_with("\"") {
-            let params = InterpolationParams {
-                pb: &pb,
-                path: &path,
-                pattern: &pattern,
-                last_msgid: &last_msgid,
-                line: &line,
-                line_index,
-            };
-            if let Some(error) = find_missing_interpolations(params) {
-                errors.push(error);
+    terms.iter().map(|term| ProtectedTerm::new(term)).collect()
+}
+
+/// Parses the bounds of `--length-ratio`.
+fn length_ratio(value: &str) -> Result<(f64, f64), String> {
+    let (min, max) = value
+        .split_once(',')
+        .ok_or_else(|| String::from("expected two percentages separated by a comma"))?;
+    let min: f64 = min.trim().parse().map_err(|e| format!("{}: {}", min, e))?;
+    let max: f64 = max.trim().parse().map_err(|e| format!("{}: {}", max, e))?;
+    if min > max {
+        return Err(format!("{} is greater than {}", min, max));
+    }
+    Ok((min, max))
+}
+
+/// Parses a pattern of placeholders, which must be a valid regex.
+fn pattern(value: &str) -> Result<String, String> {
+    Regex::new(value).map_err(|e| e.to_string())?;
+    Ok(value.to_string())
+}
+
+/// What is known of a file once it has been processed.
+struct Processed {
+    summary: Option<Summary>,
+    timing: Timing,
+    /// The diff of the corrections of the catalog, with --diff
+    patch: String,
+    /// The entries with errors, by msgctxt and msgid, which fix marks fuzzy unless it corrects
+    /// them
+    broken: HashSet<(Option<String>, String)>,
+}
+
+/// Makes the corrections of fix and --normalize to a catalog and tells which they are, returning
+/// the diff of --diff. A file that cannot be rewritten is reported.
+fn fix(
+    pb: &ProgressBar,
+    path: &Path,
+    options: &CheckOptions,
+    broken: &HashSet<(Option<String>, String)>,
+    prompt: Option<&mut Prompt>,
+    report: &mut dyn FnMut(Diagnostic),
+) -> String {
+    let (repairs, patch) = match fix_file(path, options, broken, prompt) {
+        Ok(fixed) => fixed,
+        Err(e) => {
+            report(Diagnostic {
+                message: "Unwritable file",
+                severity: Severity::Error,
+                context: None,
+                missing: Vec::new(),
+                unexpected: Vec::new(),
+                path: path.to_path_buf(),
+                line_index: 1,
+                column: 1,
+                lines: vec![e.to_string()],
+                rule: None,
+            });
+            return String::new();
+        }
+    };
+    for repair in &repairs {
+        let line = format!(
+            "{}:{}: {}",
+            path.display(),
+            repair.line_index,
+            repair.description
+        );
+        if options.dry_run {
+            logging::info(pb, line);
+        } else {
+            logging::debug(pb, line);
+        }
+    }
+    let mut fixed: Vec<_> = repairs.iter().map(|repair| repair.line_index).collect();
+    fixed.dedup();
+    if !fixed.is_empty() {
+        let verb = if options.dry_run || options.diff {
+            "Would fix"
+        } else {
+            "Fixed"
+        };
+        logging::info(
+            pb,
+            format!(
+                "{} {} in {}",
+                verb,
+                totals::counted(fixed.len(), "entry", "entries"),
+                path.display()
+            ),
+        );
+    }
+    patch
+}
+
+fn process_file(
+    pb: &ProgressBar,
+    path: &Path,
+    input: Option<&[u8]>,
+    options: &CheckOptions,
+    sender: mpsc::UnboundedSender<Diagnostic>,
+) -> Processed {
+    let start = std::time::Instant::now();
+    let mut entries = 0;
+    let mut broken = HashSet::new();
+    // Once fix and --normalize rewrite the catalog, it is checked again, for what they did not
+    // correct to be reported rather than what they did.
+    let rewritten = options.normalize
+        && !options.interactive
+        && !options.dry_run
+        && !options.diff
+        && input.is_none();
+    // The receiver is only dropped once the program exits.
+    let mut summary = check_file(path, input, options, &mut entries, &mut |diagnostic| {
+        if let Some(context) = &diagnostic.context {
+            if options.fix && diagnostic.severity == Severity::Error {
+                broken.insert((context.msgctxt.clone(), context.msgid.clone()));
             }
         }
-        line_index += 1
+        if !rewritten {
+            let _ = sender.send(diagnostic);
+        }
+    });
+    let timing = Timing {
+        path: path.to_path_buf(),
+        entries,
+        elapsed: start.elapsed(),
+    };
+    // The corrections of --interactive are asked for one file after the other, in the order the
+    // files are checked.
+    let mut patch = String::new();
+    if options.normalize && !options.interactive {
+        patch = fix(pb, path, options, &broken, None, &mut |diagnostic| {
+            let _ = sender.send(diagnostic);
+        });
     }
+    if rewritten {
+        summary = check_file(path, None, options, &mut 0, &mut |diagnostic| {
+            let _ = sender.send(diagnostic);
+        });
+    }
+    logging::debug(pb, format!("Checked {}", path.display()));
     pb.inc(1);
-    Ok(errors)
+    Processed {
+        summary,
+        timing,
+        patch,
+        broken,
+    }
+}
+
+/// Parses the command line, keeping the matches that tell which flags are given.
+fn parse(arguments: Vec<OsString>) -> (Cli, ArgMatches) {
+    let matches = Cli::command().get_matches_from(arguments);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    (cli, matches)
+}
+
+/// Returns what a run of the check, stats or fix command does, with its arguments and the
+/// matches of them.
+fn checking(cli: Cli, matches: ArgMatches) -> (Mode, Args, ArgMatches) {
+    let (mode, args, name) = match cli.command {
+        None => return (Mode::Check, cli.args, matches),
+        Some(Command::Check(args)) => (Mode::Check, args, "check"),
+        Some(Command::Stats(args)) => (Mode::Stats, args, "stats"),
+        Some(Command::Fix(args)) => (Mode::Fix, args, "fix"),
+        Some(_) => unreachable!("the other commands do not check catalogs"),
+    };
+    let matches = matches
+        .subcommand_matches(name)
+        .cloned()
+        .unwrap_or_default();
+    (mode, args, matches)
+}
+
+/// Prints the messages of a template that a catalog lacks, and those of the catalog the template
+/// does not have, returning whether there are some.
+fn compare(catalog: &Path, template: &Path) -> io::Result<bool> {
+    let (missing, extra) = catalog::compare(catalog, template)?;
+    let pb = ProgressBar::hidden();
+    let list = |heading: String, messages: &[catalog::Message]| {
+        if messages.is_empty() {
+            return;
+        }
+        logging::output(&pb, heading);
+        for (msgctxt, msgid) in messages {
+            match msgctxt {
+                Some(msgctxt) => logging::output(
+                    &pb,
+                    format!(
+                        "\t{} {}",
+                        quoted("msgctxt", msgctxt),
+                        quoted("msgid", msgid)
+                    ),
+                ),
+                None => logging::output(&pb, format!("\t{}", quoted("msgid", msgid))),
+            }
+        }
+    };
+    list(
+        format!(
+            "{} lacks {} of {}:",
+            catalog.display(),
+            totals::counted(missing.len(), "message", "messages"),
+            template.display()
+        ),
+        &missing,
+    );
+    list(
+        format!(
+            "{} has {} that {} does not:",
+            catalog.display(),
+            totals::counted(extra.len(), "message", "messages"),
+            template.display()
+        ),
+        &extra,
+    );
+    Ok(!missing.is_empty() || !extra.is_empty())
+}
+
+/// Whether the flag turning on a rule is given, on the command line or in the configuration file.
+fn is_flag_given(matches: &ArgMatches, rule: &RuleInfo) -> bool {
+    let command = Cli::command();
+    let arg = rule.flag.and_then(|flag| {
+        command
+            .get_arguments()
+            .find(|arg| arg.get_long() == flag.strip_prefix("--"))
+    });
+    arg.is_some_and(|arg| {
+        matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+    })
+}
+
+/// The exit code of a run that found errors.
+const FINDINGS: i32 = 1;
+/// The exit code of wrong arguments or settings, as clap exits with for its own errors.
+const USAGE: i32 = 2;
+/// The exit code of a file that cannot be read or written.
+const FAILURE: i32 = 3;
+
+/// Makes the error of loading a settings file, such as the configuration or a baseline, a usage
+/// error.
+fn settings_error(e: io::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e.to_string())
 }
 
 #[tokio::main]
-async fn main() -> io::Result<()> {
-    let args = Args::parse();
-    let dir = Path::new(args.path.to_str().unwrap());
-    let entries = read_dir(dir)?.count();
-    let pb = ProgressBar::new(entries as u64);
+async fn main() {
+    if let Err(e) = run().await {
+        let code = if e.kind() == io::ErrorKind::InvalidInput {
+            USAGE
+        } else {
+            FAILURE
+        };
+        logging::error(&ProgressBar::hidden(), e);
+        std::process::exit(code);
+    }
+}
+
+async fn run() -> io::Result<()> {
+    let start = std::time::Instant::now();
+    let (cli, matches) = parse(std::env::args_os().collect());
+    match &cli.command {
+        Some(Command::Explain { rule }) => {
+            print!("{}", rule.map_or_else(explain::list, explain::explain));
+            return Ok(());
+        }
+        Some(Command::Completions { shell }) => {
+            print!("{}", completions::script(*shell, &mut Cli::command()));
+            return Ok(());
+        }
+        Some(Command::Man) => {
+            print!("{}", man::page(&mut Cli::command()));
+            return Ok(());
+        }
+        Some(Command::Convert { paths, to }) => {
+            let pb = ProgressBar::hidden();
+            for path in paths {
+                if catalog::convert(path, to)? {
+                    logging::info(&pb, format!("Converted {} to {}", path.display(), to));
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Compare { catalog, template }) => {
+            if compare(catalog, template)? {
+                std::process::exit(FINDINGS);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+    let has_command = cli.command.is_some();
+    let (mode, mut args, mut matches) = checking(cli, matches);
+    let config = match &args.config {
+        Some(path) => Some(path.clone()),
+        None if !args.no_config => {
+            let path = args.paths.iter().find(|path| path.as_os_str() != "-");
+            config::find(path.map_or(Path::new("."), PathBuf::as_path))
+        }
+        None => None,
+    };
+    let mut languages = Vec::new();
+    if let Some(config) = &config {
+        let mut arguments: Vec<OsString> = std::env::args_os().collect();
+        let settings = config::read(config, &Cli::command(), &matches).map_err(settings_error)?;
+        // They follow the name of the command, when one is given.
+        let index = 1 + usize::from(has_command);
+        arguments.splice(index..index, settings.arguments);
+        let (cli, all) = parse(arguments);
+        (_, args, matches) = checking(cli, all);
+        languages = settings.languages;
+    }
+    let level = match (args.quiet, args.verbose) {
+        (true, _) => logging::Level::Error,
+        (false, 0) => std::env::var("RUST_LOG")
+            .ok()
+            .and_then(|value| logging::Level::from_env(&value))
+            .unwrap_or(logging::Level::Info),
+        (false, 1) => logging::Level::Debug,
+        (false, _) => logging::Level::Trace,
+    };
+    logging::set_level(level);
+    logging::set_colors(args.color);
+    if let Some(config) = &config {
+        logging::debug(
+            &ProgressBar::hidden(),
+            format!("Read the settings of {}", config.display()),
+        );
+    }
+    args.normalize |= mode == Mode::Fix;
+    args.coverage |= mode == Mode::Stats;
+    let stdin = Path::new("-");
+    let input = if args.stdin || args.paths.iter().any(|path| path == stdin) {
+        if args.normalize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a catalog read from stdin cannot be rewritten",
+            ));
+        }
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input)?;
+        Some(Arc::new(input))
+    } else {
+        None
+    };
+    let arguments: Vec<PathBuf> = args
+        .paths
+        .iter()
+        .filter(|path| *path != stdin)
+        .cloned()
+        .collect();
+    // The paths of baselines are relative to the folder checked, when a single one is.
+    let dir = match arguments.as_slice() {
+        [dir] if dir.is_dir() => dir.as_path(),
+        _ => Path::new(""),
+    };
+    let checked = args
+        .paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let walk = walk::Options {
+        excluded: args.exclude.clone(),
+        gitignore: !args.no_ignore,
+        symlinks: args.follow_symlinks,
+    };
+    let mut paths = walk::arguments(&arguments, args.recursive, &walk)?;
+    if let Some(base) = &args.changed {
+        retain_changed(&arguments, base, &mut paths)?;
+    }
+    let catalogs =
+        paths.iter().filter(|path| is_catalog(path)).count() + usize::from(input.is_some());
+    let pb = ProgressBar::new(catalogs as u64);
+    if args.quiet {
+        pb.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+    }
     let mut has_po_files = false;
 
     pb.set_style(
@@ -98,54 +825,610 @@ async fn main() -> io::Result<()> {
             .progress_chars("=>-"),
     );
 
+    // A rule off by default that some languages turn on is still off for the others, unless it is
+    // turned on for every language.
+    let restricted = languages
+        .iter()
+        .flat_map(|(_, settings)| &settings.enabled)
+        .filter(|rule| !args.enable.contains(rule) && !is_flag_given(&matches, rule))
+        .copied()
+        .collect();
+    let settings = rules::Settings {
+        enabled: args.enable.clone(),
+        disabled: args.disable.clone(),
+        severities: args.severity.clone(),
+        languages,
+        restricted,
+    };
+    let mut enabled = settings.enabled.iter().chain(
+        settings
+            .languages
+            .iter()
+            .flat_map(|(_, settings)| &settings.enabled),
+    );
+    if let Some(rule) = enabled.find(|rule| rule.needs_value) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} {} is turned on with {}",
+                rule.code,
+                rule.name,
+                rule.flag.unwrap_or_default()
+            ),
+        ));
+    }
+    let options = CheckOptions {
+        pattern: match (args.pattern.is_empty(), args.preset) {
+            (false, _) => Some(combined(&args.pattern)?),
+            (true, Some(preset)) => preset
+                .pattern()
+                .map(|pattern| combined(&[pattern]))
+                .transpose()?,
+            (true, None) => Some(combined(&[DEFAULT_PATTERN])?),
+        },
+        default_format: args.preset.and_then(Preset::format),
+        fail_on_fuzzy: args.fail_on_fuzzy || settings.enables("fuzzy"),
+        fail_on_untranslated: args.fail_on_untranslated || settings.enables("untranslated"),
+        include_obsolete: args.include_obsolete,
+        strict_syntax: args.strict_syntax,
+        ignore_repeats: args.ignore_repeats,
+        accelerators: args.accelerators.clone(),
+        punctuation: !args.no_punctuation,
+        links: !args.no_links,
+        same_as_source: args.same_as_source || settings.enables("same-as-source"),
+        brands: args.brands.clone(),
+        length_ratio: args.length_ratio,
+        cross_file: args.cross_file || settings.enables("inconsistent-translation"),
+        glossary: match &args.glossary {
+            Some(path) => Some(Arc::new(Glossary::load(path).map_err(settings_error)?)),
+            None => None,
+        },
+        protected_terms: protected_terms(&args)?,
+        spellchecker: if args.spellcheck
+            || settings.enables("misspelled-word")
+            || settings.enables("missing-dictionary")
+        {
+            let wordlist = args.wordlist.as_deref();
+            let spellchecker =
+                Spellchecker::new(args.dictionaries.clone(), wordlist).map_err(settings_error)?;
+            Some(Arc::new(spellchecker))
+        } else {
+            None
+        },
+        markdown: args.markdown || settings.enables("markdown-mismatch"),
+        numbers: args.numbers || settings.enables("number-mismatch"),
+        nfc: !args.no_nfc,
+        typography: typography::Rule::ALL
+            .into_iter()
+            .filter(|rule| !args.disabled_typography.contains(rule))
+            .collect(),
+        normalize: args.normalize,
+        fix: mode == Mode::Fix,
+        dry_run: args.dry_run,
+        diff: args.diff,
+        interactive: args.interactive,
+        cursor: args.cursor.clone(),
+        filter: args.filter.clone(),
+        settings: Arc::new(settings),
+        rules: default_rules(),
+    };
+    let mut baseline = match &args.baseline {
+        Some(path) => Some(Baseline::load(path, dir).map_err(settings_error)?),
+        None => None,
+    };
+    let mut recorded = args.write_baseline.as_ref().map(|_| Baseline::new(dir));
     let mut tasks = vec![];
-    for entry in read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("po") {
-            has_po_files = true;
-            let pb = pb.clone();
-            let pattern = args.pattern.clone();
-            tasks.push(task::spawn(
-                async move { process_file(&pb, &path, &pattern) },
-            ));
+    let mut files = Vec::new();
+    let mut sources = Vec::new();
+    if let Some(input) = input {
+        sources.push((args.stdin_filename.clone(), Some(input)));
+    }
+    for path in paths {
+        if !is_catalog(&path) {
+            logging::trace(&pb, format!("Skipping {}", path.display()));
+        } else {
+            sources.push((path, None));
         }
     }
+    if !args.lang.is_empty() {
+        sources.retain(|(path, input)| {
+            let kept = is_of_languages(path, input.as_deref().map(Vec::as_slice), &args.lang);
+            if !kept {
+                logging::trace(
+                    &pb,
+                    format!("Skipping {}, of another language", path.display()),
+                );
+            }
+            kept
+        });
+        pb.set_length(sources.len() as u64);
+    }
+    if args.list_files && !sources.is_empty() {
+        pb.finish_and_clear();
+        for (path, _) in &sources {
+            logging::output(&pb, path.display());
+        }
+        return Ok(());
+    }
+    // The catalogs wait for a permit to be checked, in the order they are reported.
+    let jobs = args
+        .jobs
+        .or_else(|| std::thread::available_parallelism().ok())
+        .map_or(1, usize::from);
+    logging::debug(&pb, format!("Checking {} catalogs at a time", jobs));
+    let permits = Arc::new(Semaphore::new(jobs));
+    // Once --fail-fast stops the run, the catalogs that are not being checked yet are skipped.
+    let cancelled = Arc::new(AtomicBool::new(false));
+    for (path, input) in sources {
+        has_po_files = true;
+        files.push(path.clone());
+        let pb = pb.clone();
+        let options = options.clone();
+        let permits = permits.clone();
+        let cancelled = cancelled.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let task = task::spawn(async move {
+            let permit = permits.acquire_owned().await;
+            if cancelled.load(Ordering::SeqCst) {
+                return None;
+            }
+            let processed = task::spawn_blocking(move || {
+                process_file(
+                    &pb,
+                    &path,
+                    input.as_deref().map(Vec::as_slice),
+                    &options,
+                    sender,
+                )
+            })
+            .await;
+            drop(permit);
+            Some(processed)
+        });
+        tasks.push((task, receiver));
+    }
 
-    if !has_po_files {
-        pb.println(format!(
-            "\x1b[0;31m[ERROR] No .po files found in {}\x1b[0m",
-            dir.display()
-        ));
+    if let (false, Some(base)) = (has_po_files, &args.changed) {
+        logging::info(
+            &pb,
+            format!("No catalog of {} changed since {}", checked, base),
+        );
+        pb.finish_and_clear();
+        return Ok(());
+    } else if !has_po_files {
+        logging::error(&pb, format!("No .po or .pot files found in {}", checked));
         pb.finish_and_clear();
-        std::process::exit(1);
+        std::process::exit(USAGE);
     } else {
-        pb.println(format!(
-            "\x1b[0;36m[INFO]  Processing .po files in {}\x1b[0m",
-            dir.display()
-        ));
+        logging::info(&pb, format!("Processing .po files in {}", checked));
+    }
+
+    // Diagnostics are printed file by file, once each one is checked unless --unsorted.
+    let format = if args.github {
+        OutputFormat::Github
+    } else {
+        args.format
+    };
+    // Text and annotations are printed as diagnostics are found, other formats once the run is over.
+    // The browser of --tui shows them once the run is over.
+    let streamed = args.output.is_none()
+        && !args.tui
+        && matches!(format, OutputFormat::Text | OutputFormat::Github);
+    let mut error_count = 0;
+    let mut warning_count = 0;
+    // The catalogs that cannot be read or written, which fail the run whatever else is found.
+    let mut failures = 0;
+    // Whether --fail-fast stopped the run at an error.
+    let stopped = std::cell::Cell::new(false);
+    let mut totals = Totals::default();
+    let mut limit = Limit::new(args.max_errors_per_file, args.deduplicate);
+    let mut reported = Vec::new();
+    // Once a correction of --interactive is answered with q, the later ones are skipped.
+    let mut quit = false;
+    let mut ask = |proposal: &Proposal| {
+        if quit {
+            return Ok(Decision::Quit);
+        }
+        let decision = pb.suspend(|| interactive::ask(proposal))?;
+        quit = decision == Decision::Quit;
+        Ok(decision)
+    };
+    // The place of the first diagnostic shown, for --open.
+    let mut first = None;
+    let mut report = |diagnostic: Diagnostic| {
+        // The stats command only counts the entries of the catalogs.
+        if mode == Mode::Stats || stopped.get() {
+            return;
+        }
+        if baseline
+            .as_mut()
+            .is_some_and(|baseline| baseline.accepts(&diagnostic))
+        {
+            return;
+        }
+        if let Some(recorded) = &mut recorded {
+            recorded.record(&diagnostic);
+        }
+        match diagnostic.severity {
+            Severity::Error => error_count += 1,
+            Severity::Warning => warning_count += 1,
+        }
+        if diagnostic.rule().name == "io" {
+            failures += 1;
+        }
+        stopped.set(args.fail_fast && diagnostic.severity == Severity::Error);
+        totals.diagnostic(&diagnostic);
+        if !limit.shows(&diagnostic) {
+            return;
+        }
+        if first.is_none() {
+            first = Some((
+                diagnostic.path.clone(),
+                diagnostic.line_index,
+                diagnostic.column,
+            ));
+        }
+        if args.tui {
+            reported.push(diagnostic);
+            return;
+        }
+        // The diff of --diff is the only output, for it to be applied as it is printed.
+        if args.diff {
+            return;
+        }
+        // A report written to a file leaves the terminal to the diagnostics as text.
+        if format == OutputFormat::Text || args.output.is_some() {
+            match &args.message_format {
+                Some(template) => logging::output(&pb, template.render(&diagnostic)),
+                None => logging::output(&pb, &diagnostic),
+            }
+        } else if format == OutputFormat::Github {
+            logging::output(&pb, report::github(&diagnostic));
+        }
+        if !streamed {
+            reported.push(diagnostic);
+        }
+    };
+    let mut languages: BTreeMap<String, Stats> = BTreeMap::new();
+    let mut translations: BTreeMap<String, Vec<Translation>> = BTreeMap::new();
+    let mut file_languages = HashMap::new();
+    let mut timings = Vec::new();
+    let mut tasks = tasks.into_iter();
+    for (path, (task, mut receiver)) in files.iter().zip(tasks.by_ref()) {
+        let mut diagnostics = Vec::new();
+        // The corrections of --interactive are asked for before the catalog is checked again.
+        let asked = options.normalize && options.interactive;
+        while let Some(diagnostic) = receiver.recv().await {
+            if asked {
+                continue;
+            }
+            if args.unsorted {
+                report(diagnostic);
+            } else {
+                diagnostics.push(diagnostic);
+            }
+        }
+        sort(&mut diagnostics);
+        diagnostics.into_iter().for_each(&mut report);
+        let Some(processed) = task.await.map_err(io::Error::other)? else {
+            unreachable!("the catalogs are only skipped once the run is stopped");
+        };
+        let Processed {
+            summary,
+            timing,
+            mut patch,
+            broken,
+        } = processed.map_err(io::Error::other)?;
+        if asked {
+            patch = fix(&pb, path, &options, &broken, Some(&mut ask), &mut report);
+            let mut diagnostics = Vec::new();
+            check_file(path, None, &options, &mut 0, &mut |diagnostic| {
+                diagnostics.push(diagnostic)
+            });
+            sort(&mut diagnostics);
+            diagnostics.into_iter().for_each(&mut report);
+        }
+        if !patch.is_empty() {
+            logging::output(&pb, patch.trim_end_matches('\n'));
+        }
+        timings.push(timing);
+        if let Some(summary) = summary {
+            file_languages.insert(path.clone(), summary.language.clone());
+            languages
+                .entry(summary.language.clone())
+                .or_default()
+                .add(&summary.stats);
+            translations
+                .entry(summary.language)
+                .or_default()
+                .extend(summary.translations);
+        }
+        if stopped.get() {
+            break;
+        }
+    }
+    // The catalogs being checked when the run stops are waited for, for those that fix and
+    // --normalize are rewriting to be written whole before the program exits.
+    cancelled.store(true, Ordering::SeqCst);
+    for (task, _) in tasks {
+        task.await.map_err(io::Error::other)?;
+    }
+
+    let mut inconsistencies: Vec<Diagnostic> = translations
+        .iter()
+        .flat_map(|(language, translations)| {
+            consistency::inconsistencies(translations)
+                .into_iter()
+                .filter_map(|diagnostic| options.settings.apply(diagnostic, Some(language)))
+        })
+        .collect();
+    if !args.unsorted {
+        sort(&mut inconsistencies);
     }
+    inconsistencies.into_iter().for_each(&mut report);
+    timings.into_iter().for_each(|timing| totals.file(timing));
 
-    let mut all_errors = vec![];
-    for task in tasks {
-        match task.await {
-            Ok(Ok(errors)) => {
-                for error in &errors {
-                    pb.println(error);
+    if args.tui && mode != Mode::Stats {
+        let acknowledged =
+            pb.suspend(|| tui::browse(&reported, &file_languages, args.editor.as_deref()))?;
+        let mut acknowledged_count = 0;
+        for (diagnostic, _) in reported
+            .iter()
+            .zip(&acknowledged)
+            .filter(|(_, &acknowledged)| acknowledged)
+        {
+            match diagnostic.severity {
+                Severity::Error => error_count -= 1,
+                Severity::Warning => warning_count -= 1,
+            }
+            acknowledged_count += 1;
+        }
+        if acknowledged_count > 0 {
+            let acknowledged = reported
+                .iter()
+                .zip(&acknowledged)
+                .filter(|(_, &acknowledged)| acknowledged)
+                .map(|(diagnostic, _)| diagnostic);
+            match &args.baseline {
+                Some(path) => {
+                    let mut baseline = Baseline::load(path, dir)?;
+                    acknowledged.for_each(|diagnostic| baseline.record(diagnostic));
+                    baseline.save(path)?;
+                    logging::info(
+                        &pb,
+                        format!(
+                            "Added {} to {}",
+                            totals::counted(
+                                acknowledged_count,
+                                "acknowledged diagnostic",
+                                "acknowledged diagnostics"
+                            ),
+                            path.display()
+                        ),
+                    );
                 }
-                all_errors.extend(errors)
+                None => logging::info(
+                    &pb,
+                    format!(
+                        "{}, --baseline FILE would keep them",
+                        totals::counted(
+                            acknowledged_count,
+                            "acknowledged diagnostic",
+                            "acknowledged diagnostics"
+                        )
+                    ),
+                ),
             }
-            Ok(Err(e)) => return Err(e),
-            Err(e) => return Err(io::Error::new(io::ErrorKind::Other, e)),
         }
     }
 
-    if !all_errors.is_empty() {
-        pb.finish_and_clear();
-        std::process::exit(1);
+    let run = report::Run {
+        files: &files,
+        diagnostics: &reported,
+        languages: &languages,
+        file_languages: &file_languages,
+        message_format: args.message_format.as_ref(),
+    };
+    if !streamed && !args.tui && !args.diff && mode != Mode::Stats {
+        let report = report::render(format, &run);
+        match &args.output {
+            Some(path) => {
+                std::fs::write(path, report + "\n")
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+                logging::info(&pb, format!("Wrote the report to {}", path.display()));
+            }
+            None => logging::output(&pb, report),
+        }
+    }
+
+    if args.coverage {
+        logging::output(&pb, stats::table(&languages));
+    }
+    if let Some(min_coverage) = args.min_coverage {
+        for (language, stats) in &languages {
+            if stats.coverage() < min_coverage {
+                logging::error(
+                    &pb,
+                    format!(
+                        "Coverage of {} is {:.1}%, below {}%",
+                        language,
+                        stats.coverage(),
+                        min_coverage
+                    ),
+                );
+                error_count += 1;
+            }
+        }
+    }
+
+    if let (Some(path), Some(recorded)) = (&args.write_baseline, &recorded) {
+        recorded.save(path)?;
+        logging::info(
+            &pb,
+            format!(
+                "Recorded {} diagnostics in {}",
+                recorded.count(),
+                path.display()
+            ),
+        );
+        error_count = 0;
+        warning_count = 0;
+    }
+    if let (Some(path), Some(baseline)) = (&args.baseline, &baseline) {
+        if baseline.count() > 0 {
+            logging::info(
+                &pb,
+                format!(
+                    "{} diagnostics of {} are fixed, --write-baseline would drop them",
+                    baseline.count(),
+                    path.display()
+                ),
+            );
+        }
+    }
+
+    for note in limit.notes() {
+        logging::info(&pb, note);
+    }
+    if stopped.get() {
+        logging::info(&pb, "Stopped at the first error, as --fail-fast asks");
+    }
+    let mut failed = error_count > 0;
+    if let Some(max_errors) = args.max_errors.filter(|_| failed) {
+        failed = error_count > max_errors;
+        if !failed {
+            logging::info(
+                &pb,
+                format!(
+                    "{}, no more than --max-errors {}",
+                    totals::counted(error_count, "error", "errors"),
+                    max_errors
+                ),
+            );
+        }
+    }
+    if let Some(max_warnings) = args.max_warnings.filter(|&max| warning_count > max) {
+        logging::error(
+            &pb,
+            format!(
+                "{}, more than --max-warnings {}",
+                totals::counted(warning_count, "warning", "warnings"),
+                max_warnings
+            ),
+        );
+        failed = true;
+    }
+    if args.stats {
+        logging::status(&pb, totals.details(start.elapsed()));
     } else {
-        pb.println("\x1b[0;36m[INFO]  Done\x1b[0m");
+        logging::info(&pb, totals.footer(start.elapsed()));
+    }
+    if let Some((path, line, column)) = first.filter(|_| args.open) {
+        pb.finish_and_clear();
+        editor::open(args.editor.as_deref(), &path, line, column)?;
+    }
+    if args.watch {
+        pb.finish_and_clear();
+        return watch(&arguments, &args, &walk, &options, dir).await;
     }
+    if failures > 0 {
+        pb.finish_and_clear();
+        std::process::exit(FAILURE);
+    }
+    if failed {
+        pb.finish_and_clear();
+        std::process::exit(FINDINGS);
+    }
+
+    Ok(())
+}
 
+/// Keeps the paths git changed since a commit, in the repository of the first path checked.
+fn retain_changed(arguments: &[PathBuf], base: &str, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    let first = arguments.first().map_or(Path::new("."), PathBuf::as_path);
+    let repository = if first.is_dir() {
+        first
+    } else {
+        first
+            .parent()
+            .filter(|parent| parent.is_dir())
+            .unwrap_or(Path::new("."))
+    };
+    let changed = git::changed(repository, base)?;
+    paths.retain(|path| std::fs::canonicalize(path).is_ok_and(|path| changed.contains(&path)));
     Ok(())
 }
+
+/// Checks the catalogs again whenever they change, until the program is stopped.
+///
+/// The catalogs that are checked are chosen as those of the first run are, by --lang and
+/// --changed, and the diagnostics of --baseline are left out as they are from it.
+async fn watch(
+    arguments: &[PathBuf],
+    args: &Args,
+    walk: &walk::Options,
+    options: &CheckOptions,
+    dir: &Path,
+) -> io::Result<()> {
+    let pb = ProgressBar::hidden();
+    let catalogs = || -> io::Result<Vec<PathBuf>> {
+        let paths = walk::arguments(arguments, args.recursive, walk)?;
+        Ok(paths.into_iter().filter(|path| is_catalog(path)).collect())
+    };
+    let mut snapshot = watch::Snapshot::new(&catalogs()?);
+    logging::info(&pb, "Watching the catalogs for changes, Ctrl-C stops");
+    loop {
+        tokio::time::sleep(watch::INTERVAL).await;
+        // A catalog being renamed, or a pattern matching none for a while, is not an error.
+        let Ok(paths) = catalogs() else {
+            continue;
+        };
+        let mut changed = snapshot.changed(&paths);
+        if changed.is_empty() {
+            continue;
+        }
+        if let Some(base) = &args.changed {
+            retain_changed(arguments, base, &mut changed)?;
+        }
+        if !args.lang.is_empty() {
+            changed.retain(|path| is_of_languages(path, None, &args.lang));
+        }
+        for path in changed {
+            // The baseline is read again, as each diagnostic it records is only accepted once.
+            let mut baseline = match &args.baseline {
+                Some(path) => Some(Baseline::load(path, dir).map_err(settings_error)?),
+                None => None,
+            };
+            let mut diagnostics = Vec::new();
+            let mut entries = 0;
+            check_file(&path, None, options, &mut entries, &mut |diagnostic| {
+                if !baseline
+                    .as_mut()
+                    .is_some_and(|baseline| baseline.accepts(&diagnostic))
+                {
+                    diagnostics.push(diagnostic)
+                }
+            });
+            sort(&mut diagnostics);
+            let mut errors = 0;
+            for diagnostic in &diagnostics {
+                if diagnostic.severity == Severity::Error {
+                    errors += 1;
+                }
+                match &args.message_format {
+                    Some(template) => logging::output(&pb, template.render(diagnostic)),
+                    None => logging::output(&pb, diagnostic),
+                }
+            }
+            logging::info(
+                &pb,
+                format!(
+                    "Checked {}: {}, {}",
+                    path.display(),
+                    totals::counted(errors, "error", "errors"),
+                    totals::counted(diagnostics.len() - errors, "warning", "warnings")
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use clap::{Arg, Command};
+
+/// Escapes text for roff, so that its dashes and backslashes are printed as they are and its lines
+/// are not taken for requests. Blank lines separate paragraphs.
+fn escape(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.replace('\\', r"\e").replace('-', r"\-");
+            if line.is_empty() {
+                String::from(".sp")
+            } else if line.starts_with(['.', '\'']) {
+                format!(r"\&{}", line)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn bold(text: &str) -> String {
+    format!(r"\fB{}\fR", escape(text))
+}
+
+fn italic(text: &str) -> String {
+    format!(r"\fI{}\fR", escape(text))
+}
+
+/// Returns the value name of an argument that takes a value.
+fn value_name(arg: &Arg) -> Option<String> {
+    if !arg.get_action().takes_values() {
+        return None;
+    }
+    let name = arg
+        .get_value_names()
+        .and_then(|names| names.first())
+        .map_or_else(
+            || arg.get_id().to_string().to_uppercase(),
+            ToString::to_string,
+        );
+    Some(name)
+}
+
+/// Returns the `.TP` paragraphs describing the arguments of a command.
+fn arguments(command: &Command) -> String {
+    let mut text = String::new();
+    for arg in command.get_arguments().filter(|arg| !arg.is_hide_set()) {
+        let mut names: Vec<String> = arg
+            .get_short()
+            .map(|short| bold(&format!("-{}", short)))
+            .into_iter()
+            .chain(arg.get_long().map(|long| bold(&format!("--{}", long))))
+            .collect();
+        let value = value_name(arg).map(|name| italic(&name));
+        if names.is_empty() {
+            names.extend(value.clone().map(|value| format!("[{}]", value)));
+        } else if let Some(value) = &value {
+            let last = names.pop().unwrap_or_default();
+            names.push(format!("{} {}", last, value));
+        }
+        text.push_str(&format!(".TP\n{}\n", names.join(", ")));
+        let help = arg
+            .get_long_help()
+            .or(arg.get_help())
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        if !help.is_empty() {
+            text.push_str(&escape(&help));
+            text.push('\n');
+        }
+        let values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        if !values.is_empty() {
+            text.push_str(&format!(
+                ".br\n[possible values: {}]\n",
+                escape(&values.join(", "))
+            ));
+        }
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect();
+        if !defaults.is_empty() && arg.get_action().takes_values() {
+            text.push_str(&format!(
+                ".br\n[default: {}]\n",
+                escape(&defaults.join(", "))
+            ));
+        }
+    }
+    text
+}
+
+/// Returns the manual page of a program, in roff, with its options and those of its commands.
+pub fn page(command: &mut Command) -> String {
+    command.build();
+    let name = command.get_name().to_string();
+    let version = env!("CARGO_PKG_VERSION");
+    let about = command
+        .get_about()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let mut text = format!(
+        ".TH {} 1 \"\" \"{} {}\"\n.SH NAME\n{} \\- {}\n",
+        escape(&name.to_uppercase()),
+        escape(&name),
+        version,
+        escape(&name),
+        escape(about.trim_end_matches('.'))
+    );
+    text.push_str(&format!(
+        ".SH SYNOPSIS\n{} [{}] [{}]...\n.br\n{} {} [{}]\n",
+        bold(&name),
+        italic("OPTIONS"),
+        italic("PATHS"),
+        bold(&name),
+        italic("COMMAND"),
+        italic("ARGS")
+    ));
+    if let Some(long_about) = command.get_long_about() {
+        text.push_str(&format!(
+            ".SH DESCRIPTION\n{}\n",
+            escape(&long_about.to_string())
+        ));
+    }
+    text.push_str(".SH OPTIONS\n");
+    text.push_str(&arguments(command));
+    text.push_str(".SH COMMANDS\n");
+    for subcommand in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        let about = subcommand
+            .get_long_about()
+            .or(subcommand.get_about())
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        text.push_str(&format!(
+            ".TP\n{}\n{}\n",
+            bold(&format!("{} {}", name, subcommand.get_name())),
+            escape(&about)
+        ));
+    }
+    // The commands that take the options of the program are not described again.
+    let ids: Vec<_> = command.get_arguments().map(Arg::get_id).collect();
+    for subcommand in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        if subcommand
+            .get_arguments()
+            .all(|arg| ids.contains(&arg.get_id()))
+        {
+            continue;
+        }
+        let options = arguments(subcommand);
+        text.push_str(&format!(
+            ".SS \"{} {}\"\n{}",
+            escape(&name),
+            escape(subcommand.get_name()),
+            options
+        ));
+    }
+    text.push_str(&format!(".SH VERSION\nv{}\n", version));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::CommandFactory;
+
+    #[test]
+    fn describes_every_long_flag_of_each_command() {
+        let page = page(&mut Cli::command());
+        let mut command = Cli::command();
+        command.build();
+        let commands = std::iter::once(&command)
+            .chain(command.get_subcommands().filter(|sub| !sub.is_hide_set()));
+        for arg in commands.flat_map(Command::get_arguments) {
+            if let (Some(long), false) = (arg.get_long(), arg.is_hide_set()) {
+                let name = bold(&format!("--{}", long));
+                assert!(page.contains(&name), "{} is not in the page", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use regex::Regex;
+
+/// The Markdown constructs whose structure translations must keep.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Code,
+    Link,
+    Image,
+    Strong,
+    Emphasis,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Code => "code span",
+            Kind::Link => "link",
+            Kind::Image => "image",
+            Kind::Strong => "strong emphasis",
+            Kind::Emphasis => "emphasis",
+        }
+    }
+}
+
+/// An inline construct of a Markdown string.
+pub struct Element<'a> {
+    pub kind: Kind,
+    /// The content of a code span, or the destination of a link or image, which are kept as they
+    /// are; empty for emphasis
+    pub text: &'a str,
+    pub offset: usize,
+}
+
+impl Element<'_> {
+    pub fn describe(&self) -> String {
+        match self.kind {
+            Kind::Code => format!("code span `{}`", self.text),
+            Kind::Link | Kind::Image => format!("{} to {}", self.kind.name(), self.text),
+            Kind::Strong | Kind::Emphasis => self.kind.name().to_string(),
+        }
+    }
+}
+
+/// Returns the index of the `]` closing the bracket opened at `start`, skipping nested brackets
+/// and escaped characters.
+fn closing_bracket(value: &str, start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = value[start..].char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the destination of the `(url "title")` part of a link at `start`, and where it ends.
+fn destination(value: &str, start: usize) -> Option<(&str, usize)> {
+    let rest = value[start..].strip_prefix('(')?;
+    let mut depth = 0;
+    let end = rest.char_indices().find_map(|(index, c)| match c {
+        '(' => {
+            depth += 1;
+            None
+        }
+        ')' if depth == 0 => Some(index),
+        ')' => {
+            depth -= 1;
+            None
+        }
+        _ => None,
+    })?;
+    let url = rest[..end].split_whitespace().next().unwrap_or_default();
+    let url = url.trim_start_matches('<').trim_end_matches('>');
+    Some((url, start + 1 + end + 1))
+}
+
+/// Returns the code spans, links and images of a Markdown string, and the string with them
+/// blanked out so that their content is not taken for emphasis.
+fn spans(value: &str) -> (Vec<Element<'_>>, String) {
+    let autolink =
+        Regex::new(r"^<([A-Za-z][A-Za-z0-9+.-]*:[^\s<>]*|[^\s<>@]+@[^\s<>@]+)>").unwrap();
+    let mut elements = Vec::new();
+    let mut blanked = String::with_capacity(value.len());
+    let mut index = 0;
+    while let Some(c) = value[index..].chars().next() {
+        let rest = &value[index..];
+        let mut end = index + c.len_utf8();
+        let mut element = None;
+        match c {
+            '\\' => end += rest[1..].chars().next().map_or(0, char::len_utf8),
+            '`' => {
+                let run = rest.len() - rest.trim_start_matches('`').len();
+                let fence = &rest[..run];
+                // A code span closes at the next run of as many backticks.
+                let mut search = run;
+                while let Some(found) = rest[search..].find(fence) {
+                    let close = search + found;
+                    let after = rest[close + run..]
+                        .bytes()
+                        .take_while(|&b| b == b'`')
+                        .count();
+                    if after == 0 {
+                        let content = &rest[run..close];
+                        let trimmed = match content.strip_prefix(' ') {
+                            Some(inner) if inner.ends_with(' ') && !inner.trim().is_empty() => {
+                                &inner[..inner.len() - 1]
+                            }
+                            _ => content,
+                        };
+                        element = Some((Kind::Code, trimmed));
+                        end = index + close + run;
+                        break;
+                    }
+                    search = close + run + after;
+                }
+                if element.is_none() {
+                    end = index + run;
+                }
+            }
+            '!' | '[' => {
+                let (kind, bracket) = match c {
+                    '!' => (Kind::Image, index + 1),
+                    _ => (Kind::Link, index),
+                };
+                let link = (c == '[' || rest[1..].starts_with('['))
+                    .then(|| closing_bracket(value, bracket))
+                    .flatten()
+                    .and_then(|close| Some((close, destination(value, close + 1)?)));
+                if let Some((close, (url, link_end))) = link {
+                    elements.push(Element {
+                        kind,
+                        text: url,
+                        offset: index,
+                    });
+                    // The text of a link is kept, to find emphasis in it.
+                    let (inner, inner_blanked) = spans(&value[bracket + 1..close]);
+                    elements.extend(inner.into_iter().map(|element| Element {
+                        offset: element.offset + bracket + 1,
+                        ..element
+                    }));
+                    blanked.push_str(&" ".repeat(bracket + 1 - index));
+                    blanked.push_str(&inner_blanked);
+                    blanked.push_str(&" ".repeat(link_end - close));
+                    index = link_end;
+                    continue;
+                }
+            }
+            '<' => {
+                if let Some(captures) = autolink.captures(rest) {
+                    element = Some((Kind::Link, captures.get(1).unwrap().as_str()));
+                    end = index + captures[0].len();
+                }
+            }
+            _ => {}
+        }
+        match element {
+            Some((kind, text)) => {
+                elements.push(Element {
+                    kind,
+                    text,
+                    offset: index,
+                });
+                blanked.push_str(&" ".repeat(end - index));
+            }
+            None if c == '\\' => blanked.push_str(&" ".repeat(end - index)),
+            None => blanked.push_str(&value[index..end]),
+        }
+        index = end;
+    }
+    (elements, blanked)
+}
+
+/// Returns the inline constructs of a Markdown string, in order.
+///
+/// Emphasis is only recognized around text that does not start or end with a space, and `_`
+/// within words, as in `snake_case`, is not taken for it.
+pub fn elements(value: &str) -> Vec<Element<'_>> {
+    let (mut elements, blanked) = spans(value);
+    let strong =
+        Regex::new(r"\*\*[^*\s](?:[^*]*[^*\s])?\*\*|(?:^|[^\w_])(__[^_\s](?:[^_]*[^_\s])?__)")
+            .unwrap();
+    let emphasis =
+        Regex::new(r"\*[^*\s](?:[^*]*[^*\s])?\*|(?:^|[^\w_])(_[^_\s](?:[^_]*[^_\s])?_)").unwrap();
+    let mut remaining = blanked;
+    for (kind, regex) in [(Kind::Strong, strong), (Kind::Emphasis, emphasis)] {
+        let mut next = remaining.clone();
+        for captures in regex.captures_iter(&remaining) {
+            let found = captures.get(1).unwrap_or_else(|| captures.get(0).unwrap());
+            elements.push(Element {
+                kind,
+                text: "",
+                offset: found.start(),
+            });
+            next.replace_range(found.range(), &" ".repeat(found.len()));
+        }
+        remaining = next;
+    }
+    elements.sort_by_key(|element| element.offset);
+    elements
+}
+
+/// Returns the block markers the lines of a Markdown string start with, such as `#`, `-` or `1.`,
+/// numbers of ordered lists being replaced with `1`.
+pub fn block_markers(value: &str) -> Vec<String> {
+    let marker = Regex::new(r"^ {0,3}(#{1,6}|[-*+]|\d+[.)]|>)(?:\s|$)").unwrap();
+    value
+        .split('\n')
+        .filter_map(|line| marker.captures(line))
+        .map(|captures| {
+            let marker = &captures[1];
+            match marker.strip_suffix(['.', ')']) {
+                Some(number) if number.chars().all(|c| c.is_ascii_digit()) => {
+                    format!("1{}", &marker[number.len()..])
+                }
+                _ => marker.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the elements of `actual` that `expected` has fewer of, code spans, links and images
+/// being compared with their content.
+pub fn difference<'a, 'b>(expected: &[Element], actual: &'b [Element<'a>]) -> Vec<&'b Element<'a>> {
+    let mut remaining: Vec<_> = expected.iter().collect();
+    actual
+        .iter()
+        .filter(|element| {
+            match remaining
+                .iter()
+                .position(|other| other.kind == element.kind && other.text == element.text)
+            {
+                Some(index) => {
+                    remaining.remove(index);
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn described(value: &str) -> Vec<String> {
+        elements(value).iter().map(Element::describe).collect()
+    }
+
+    #[test]
+    fn finds_inline_constructs() {
+        assert_eq!(
+            described("Run `npm *install*` then see [the **docs**](https://a.b/c \"Docs\")"),
+            [
+                "code span `npm *install*`",
+                "link to https://a.b/c",
+                "strong emphasis"
+            ]
+        );
+        assert_eq!(
+            described("![logo](<logo.png>) *new* <https://a.b> <me@a.b>"),
+            [
+                "image to logo.png",
+                "emphasis",
+                "link to https://a.b",
+                "link to me@a.b"
+            ]
+        );
+        assert_eq!(
+            described("`` a ` b `` __bold__"),
+            ["code span `a ` b`", "strong emphasis"]
+        );
+    }
+
+    #[test]
+    fn leaves_out_what_is_not_markdown() {
+        assert!(described("snake_case_name, 2 * 3 * 4, \\*literal\\*, [not] a link").is_empty());
+        assert!(described("an `unclosed span").is_empty());
+    }
+
+    #[test]
+    fn reads_the_block_markers_of_lines() {
+        assert_eq!(
+            block_markers("# Title\n- item\n2. step\n10) step\n> quote\ntext\n#hashtag"),
+            ["#", "-", "1.", "1)", ">"]
+        );
+    }
+
+    #[test]
+    fn compares_links_and_code_spans_with_their_content() {
+        let source = elements("[docs](https://a.b) and `code`");
+        let translation = elements("[doc](https://a.b/fr) et `code`");
+        let changed: Vec<_> = difference(&source, &translation)
+            .iter()
+            .map(|element| element.describe())
+            .collect();
+        assert_eq!(changed, ["link to https://a.b/fr"]);
+    }
+}
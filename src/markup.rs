@@ -0,0 +1,125 @@
+use regex::Regex;
+
+/// The HTML elements that have no closing tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// An HTML or XML tag of a string, such as `<b>`, `<a href="...">`, `</b>` or `<br/>`.
+pub struct Tag<'a> {
+    /// The element name, in lowercase
+    pub name: String,
+    pub closing: bool,
+    /// Whether the tag needs no closing tag, being self-closing or of a void element
+    pub empty: bool,
+    pub text: &'a str,
+    pub offset: usize,
+}
+
+impl Tag<'_> {
+    /// Whether two tags are the same apart from their attributes.
+    fn matches(&self, other: &Tag) -> bool {
+        self.name == other.name && self.closing == other.closing
+    }
+}
+
+/// Returns the tags of a string, in order.
+pub fn tags(value: &str) -> Vec<Tag<'_>> {
+    let tag =
+        Regex::new(r#"<(/?)([A-Za-z][A-Za-z0-9:_-]*)(?:\s+(?:[^<>"']|"[^"]*"|'[^']*')*)?(/?)>"#)
+            .unwrap();
+    tag.captures_iter(value)
+        .map(|captures| {
+            let whole = captures.get(0).unwrap();
+            let name = captures[2].to_lowercase();
+            Tag {
+                empty: !captures[3].is_empty() || VOID_ELEMENTS.contains(&name.as_str()),
+                name,
+                closing: !captures[1].is_empty(),
+                text: whole.as_str(),
+                offset: whole.start(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the tags of `actual` that `expected` has fewer of.
+pub fn difference<'a, 'b>(expected: &[Tag], actual: &'b [Tag<'a>]) -> Vec<&'b Tag<'a>> {
+    let mut remaining: Vec<_> = expected.iter().collect();
+    actual
+        .iter()
+        .filter(
+            |tag| match remaining.iter().position(|other| other.matches(tag)) {
+                Some(index) => {
+                    remaining.remove(index);
+                    false
+                }
+                None => true,
+            },
+        )
+        .collect()
+}
+
+/// Returns the closing tags that do not close the last open tag, and the tags left open.
+pub fn unbalanced<'a, 'b>(tags: &'b [Tag<'a>]) -> Vec<&'b Tag<'a>> {
+    let mut open: Vec<&Tag> = Vec::new();
+    let mut unbalanced = Vec::new();
+    for tag in tags.iter().filter(|tag| !tag.empty) {
+        if !tag.closing {
+            open.push(tag);
+        } else if open.last().is_some_and(|last| last.name == tag.name) {
+            open.pop();
+        } else {
+            unbalanced.push(tag);
+        }
+    }
+    unbalanced.extend(open);
+    unbalanced.sort_by_key(|tag| tag.offset);
+    unbalanced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(tags: &[&Tag<'a>]) -> Vec<&'a str> {
+        tags.iter().map(|tag| tag.text).collect()
+    }
+
+    #[test]
+    fn reads_tags_with_their_attributes() {
+        let tags = tags(r#"<A href="a>b">link</a><br><img src='x'/> 1 < 2 > 0"#);
+        let names: Vec<_> = tags
+            .iter()
+            .map(|tag| (tag.name.as_str(), tag.closing, tag.empty, tag.offset))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("a", false, false, 0),
+                ("a", true, false, 18),
+                ("br", false, true, 22),
+                ("img", false, true, 26)
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_tags_regardless_of_their_attributes() {
+        let source = tags(r#"<a href="/en">Read</a> <b>this</b>"#);
+        let translation = tags(r#"<a href="/fr">Lisez</a> ceci</b>"#);
+        assert!(texts(&difference(&source, &translation)).is_empty());
+        assert_eq!(texts(&difference(&translation, &source)), ["<b>"]);
+    }
+
+    #[test]
+    fn finds_unbalanced_tags() {
+        let balanced = tags("<b><i>x</i></b><br>");
+        assert!(unbalanced(&balanced).is_empty());
+        let crossed = tags("<b><i>x</b></i>");
+        assert_eq!(texts(&unbalanced(&crossed)), ["<b>", "</b>"]);
+        let open = tags("<p>x");
+        assert_eq!(texts(&unbalanced(&open)), ["<p>"]);
+    }
+}
@@ -0,0 +1,92 @@
+use regex::Regex;
+
+/// A sequence of a string that looks like UTF-8 decoded as Windows-1252, such as `Ã©` for `é`.
+pub struct Suspect<'a> {
+    pub text: &'a str,
+    pub offset: usize,
+    /// The characters the sequence was most likely meant to be
+    pub original: String,
+}
+
+/// Returns the sequences of a string that are UTF-8 encoded characters read as Windows-1252 or
+/// Latin-1, as when a UTF-8 catalog is converted to UTF-8 once more.
+pub fn suspects(value: &str) -> Vec<Suspect<'_>> {
+    // A lead byte of a multibyte sequence, followed by continuation bytes, as Windows-1252 shows
+    // them.
+    let sequence = Regex::new(concat!(
+        "[\u{c2}-\u{f4}][\u{80}-\u{bf}\u{152}\u{153}\u{160}\u{161}\u{178}\u{17d}\u{17e}\u{192}",
+        "\u{2c6}\u{2dc}\u{2013}\u{2014}\u{2018}-\u{201e}\u{2020}-\u{2022}\u{2026}\u{2030}",
+        "\u{2039}\u{203a}\u{20ac}\u{2122}]+",
+    ))
+    .unwrap();
+    let mut suspects = Vec::new();
+    for found in sequence.find_iter(value) {
+        let mut bytes = Vec::new();
+        for c in found.as_str().chars() {
+            let mut buffer = [0; 4];
+            let (encoded, _, unmappable) =
+                encoding_rs::WINDOWS_1252.encode(c.encode_utf8(&mut buffer));
+            match (unmappable, c) {
+                // The C1 controls that Windows-1252 leaves undefined, read as Latin-1.
+                (true, '\u{80}'..='\u{9f}') => bytes.push(c as u8),
+                (true, _) => break,
+                (false, _) => bytes.extend_from_slice(&encoded),
+            }
+        }
+        let valid = match std::str::from_utf8(&bytes) {
+            Ok(valid) => valid,
+            Err(e) => std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap(),
+        };
+        if valid.is_empty() {
+            continue;
+        }
+        // Each character of the sequence stands for one byte of the original.
+        let length = found
+            .as_str()
+            .char_indices()
+            .nth(valid.len())
+            .map_or(found.len(), |(offset, _)| offset);
+        suspects.push(Suspect {
+            text: &found.as_str()[..length],
+            offset: found.start(),
+            original: valid.to_string(),
+        });
+    }
+    suspects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn originals(value: &str) -> Vec<(&str, String)> {
+        suspects(value)
+            .into_iter()
+            .map(|suspect| (suspect.text, suspect.original))
+            .collect()
+    }
+
+    #[test]
+    fn finds_utf_8_read_as_windows_1252() {
+        assert_eq!(
+            originals("CafÃ© â€œopenâ€\u{9d}"),
+            [
+                ("Ã©", String::from("é")),
+                ("â€œ", String::from("“")),
+                ("â€\u{9d}", String::from("”"))
+            ]
+        );
+        assert_eq!(
+            originals("ä½\u{a0}å¥½"),
+            [
+                ("ä½\u{a0}", String::from("你")),
+                ("å¥½", String::from("好"))
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_accented_text_alone() {
+        assert!(originals("Café, déjà vu, Ära, Ñandú").is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use crate::unicode_tables::{COMBINING_CLASSES, COMPOSITIONS, DECOMPOSITIONS};
+
+/// The constants of the algorithmic decomposition of precomposed Hangul syllables.
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const N_COUNT: u32 = V_COUNT * T_COUNT;
+const S_COUNT: u32 = L_COUNT * N_COUNT;
+
+fn combining_class(c: char) -> u8 {
+    let c = u32::from(c);
+    COMBINING_CLASSES
+        .binary_search_by(|&(start, end, _)| {
+            if end < c {
+                std::cmp::Ordering::Less
+            } else if start > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .map_or(0, |index| COMBINING_CLASSES[index].2)
+}
+
+/// Appends the full canonical decomposition of a character.
+fn decompose(c: char, decomposed: &mut Vec<char>) {
+    let code = u32::from(c);
+    if (S_BASE..S_BASE + S_COUNT).contains(&code) {
+        let index = code - S_BASE;
+        let jamo = [
+            L_BASE + index / N_COUNT,
+            V_BASE + index % N_COUNT / T_COUNT,
+            T_BASE + index % T_COUNT,
+        ];
+        let count = if jamo[2] == T_BASE { 2 } else { 3 };
+        decomposed.extend(
+            jamo[..count]
+                .iter()
+                .filter_map(|&jamo| char::from_u32(jamo)),
+        );
+        return;
+    }
+    match DECOMPOSITIONS.binary_search_by_key(&code, |&(c, _, _)| c) {
+        Ok(index) => {
+            let (_, first, second) = DECOMPOSITIONS[index];
+            for part in [first, second].into_iter().filter(|&part| part != 0) {
+                decompose(char::from_u32(part).unwrap(), decomposed);
+            }
+        }
+        Err(_) => decomposed.push(c),
+    }
+}
+
+/// Returns the primary composite of two characters, if they have one.
+fn compose(first: char, second: char) -> Option<char> {
+    let (first, second) = (u32::from(first), u32::from(second));
+    if (L_BASE..L_BASE + L_COUNT).contains(&first) && (V_BASE..V_BASE + V_COUNT).contains(&second) {
+        let index = (first - L_BASE) * N_COUNT + (second - V_BASE) * T_COUNT;
+        return char::from_u32(S_BASE + index);
+    }
+    if (S_BASE..S_BASE + S_COUNT).contains(&first)
+        && (first - S_BASE).is_multiple_of(T_COUNT)
+        && (T_BASE + 1..T_BASE + T_COUNT).contains(&second)
+    {
+        return char::from_u32(first + second - T_BASE);
+    }
+    COMPOSITIONS
+        .binary_search_by_key(&(first, second), |&(a, b, _)| (a, b))
+        .ok()
+        .and_then(|index| char::from_u32(COMPOSITIONS[index].2))
+}
+
+/// Returns a string in Normalization Form C, in which characters are canonically decomposed, then
+/// recomposed wherever they can be.
+pub fn nfc(value: &str) -> String {
+    if value.chars().all(|c| c < '\u{300}') {
+        return value.to_string();
+    }
+    let mut decomposed = Vec::with_capacity(value.len());
+    for c in value.chars() {
+        decompose(c, &mut decomposed);
+    }
+    // Combining marks are put in the canonical order of their classes, keeping the order of
+    // those of the same class.
+    let mut index = 0;
+    while index < decomposed.len() {
+        let end = index
+            + decomposed[index..]
+                .iter()
+                .take_while(|&&c| combining_class(c) != 0)
+                .count();
+        decomposed[index..end].sort_by_key(|&c| combining_class(c));
+        index = end + 1;
+    }
+    let mut composed: Vec<char> = Vec::with_capacity(decomposed.len());
+    let mut starter = None;
+    let mut last_class = 0;
+    for c in decomposed {
+        let class = combining_class(c);
+        if let Some(starter) = starter {
+            // A mark is blocked from its starter by a mark of the same or a greater class.
+            let adjacent = composed.len() == starter + 1;
+            if adjacent || last_class < class {
+                if let Some(composite) = compose(composed[starter], c) {
+                    composed[starter] = composite;
+                    continue;
+                }
+            }
+        }
+        if class == 0 {
+            starter = Some(composed.len());
+        }
+        last_class = class;
+        composed.push(c);
+    }
+    composed.into_iter().collect()
+}
+
+/// Whether a string has a precomposed character, one with a canonical decomposition.
+pub fn has_precomposed(value: &str) -> bool {
+    value.chars().any(|c| {
+        let mut decomposed = Vec::new();
+        decompose(c, &mut decomposed);
+        decomposed != [c]
+    })
+}
+
+/// Returns the offset of the first character that normalization changes.
+pub fn first_difference(value: &str, normalized: &str) -> usize {
+    value
+        .char_indices()
+        .zip(normalized.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(value.len(), |((offset, _), _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composes_characters_and_orders_their_marks() {
+        assert_eq!(nfc("e\u{301}t\u{e9}"), "\u{e9}t\u{e9}");
+        assert_eq!(nfc("a\u{323}\u{302}"), "\u{1ead}");
+        assert_eq!(nfc("a\u{302}\u{323}"), "\u{1ead}");
+        assert_eq!(nfc("\u{1100}\u{1161}\u{11a8}"), "\u{ac01}");
+        assert_eq!(nfc("\u{212b}"), "\u{c5}");
+        assert_eq!(nfc("plain text"), "plain text");
+    }
+
+    #[test]
+    fn finds_precomposed_characters_and_changes() {
+        assert!(has_precomposed("\u{e9}"));
+        assert!(has_precomposed("\u{ac01}"));
+        assert!(!has_precomposed("e\u{301}"));
+        let value = "caf\u{65}\u{301}";
+        assert_eq!(first_difference(value, &nfc(value)), 3);
+        assert_eq!(first_difference("same", "same"), 4);
+    }
+}
@@ -0,0 +1,93 @@
+use regex::Regex;
+
+/// The first digit of the scripts whose digits numbers are written with, besides ASCII ones.
+const ZERO_DIGITS: [char; 6] = [
+    '\u{660}', '\u{6f0}', '\u{966}', '\u{9e6}', '\u{e50}', '\u{ff10}',
+];
+
+/// A number of a string, such as `30`, `1,000.50` or `١٢`.
+pub struct Number<'a> {
+    pub text: &'a str,
+    /// The ASCII digits of the number, its separators left out
+    pub digits: String,
+    pub offset: usize,
+}
+
+/// Returns the ASCII digit of a digit of another script.
+fn ascii_digit(c: char) -> Option<char> {
+    if c.is_ascii_digit() {
+        return Some(c);
+    }
+    ZERO_DIGITS.iter().find_map(|&zero| {
+        let value = (c as u32)
+            .checked_sub(zero as u32)
+            .filter(|value| *value < 10)?;
+        char::from_digit(value, 10)
+    })
+}
+
+/// Returns the numbers of a string, in order.
+///
+/// Numbers are compared by their digits alone, so that the group and decimal separators of
+/// locales, as in `1,000.5` and `1.000,5`, do not tell them apart.
+pub fn numbers(value: &str) -> Vec<Number<'_>> {
+    let number = Regex::new(r"\d+(?:[.,'’٫٬]\d+|[\u{a0}\u{202f} ]\d{3}\b)*").unwrap();
+    number
+        .find_iter(value)
+        .map(|found| Number {
+            text: found.as_str(),
+            digits: found.as_str().chars().filter_map(ascii_digit).collect(),
+            offset: found.start(),
+        })
+        .filter(|number| !number.digits.is_empty())
+        .collect()
+}
+
+/// Returns the numbers of `actual` that `expected` has fewer of.
+pub fn difference<'a, 'b>(expected: &[Number], actual: &'b [Number<'a>]) -> Vec<&'b Number<'a>> {
+    let mut remaining: Vec<_> = expected.iter().map(|number| &number.digits).collect();
+    actual
+        .iter()
+        .filter(
+            |number| match remaining.iter().position(|other| **other == number.digits) {
+                Some(index) => {
+                    remaining.remove(index);
+                    false
+                }
+                None => true,
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(value: &str) -> Vec<String> {
+        numbers(value)
+            .into_iter()
+            .map(|number| number.digits)
+            .collect()
+    }
+
+    #[test]
+    fn reads_numbers_whatever_their_separators_and_digits() {
+        assert_eq!(digits("1,000.50 and 1.000,50"), ["100050", "100050"]);
+        assert_eq!(digits("1 000 000 or 1\u{202f}000"), ["1000000", "1000"]);
+        assert_eq!(digits("١٢ and ৩ and ３０"), ["12", "3", "30"]);
+        assert_eq!(digits("version 2, 30 days"), ["2", "30"]);
+        assert!(digits("no numbers").is_empty());
+    }
+
+    #[test]
+    fn compares_numbers_by_their_digits() {
+        let source = numbers("1,000 files in 30 days");
+        let translation = numbers("1 000 fichiers en 31 jours");
+        let changed: Vec<_> = difference(&source, &translation)
+            .iter()
+            .map(|number| number.text)
+            .collect();
+        assert_eq!(changed, ["31"]);
+    }
+}
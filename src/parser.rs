@@ -0,0 +1,954 @@
+use encoding_rs::{Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use regex::bytes;
+use std::io::{self, BufRead};
+
+/// A translated form of an entry: `msgstr`, or `msgstr[N]` for plural entries.
+#[derive(Clone, PartialEq)]
+pub struct Msgstr {
+    pub index: usize,
+    pub value: String,
+    pub line_index: u32,
+    pub segments: Segments,
+}
+
+/// Where the quoted pieces of a string value are in the catalog, to locate offsets of the value.
+#[derive(Clone, Default, PartialEq)]
+pub struct Segments(Vec<Segment>);
+
+#[derive(Clone, PartialEq)]
+struct Segment {
+    /// The byte offset in the value of the first character of the piece
+    offset: usize,
+    line_index: u32,
+    /// The column of the first character after the opening quote
+    column: u32,
+    /// The piece as written in the catalog, escape sequences included
+    raw: String,
+}
+
+impl Segments {
+    /// Returns the line and column in the catalog of the byte offset of the value.
+    pub fn position(&self, offset: usize) -> (u32, u32) {
+        self.0
+            .iter()
+            .rev()
+            .find(|segment| segment.offset <= offset)
+            .map_or((0, 0), |segment| segment.position(offset))
+    }
+
+    /// Whether the value has no quoted piece, such as the msgid of a msgstr without one.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Segment {
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let mut column = self.column;
+        let mut decoded = self.offset;
+        let mut chars = self.raw.chars().peekable();
+        while decoded < offset {
+            let Some(c) = chars.next() else { break };
+            column += 1;
+            if c != '\\' {
+                decoded += c.len_utf8();
+                continue;
+            }
+            // Escape sequences stand for a single character, spelled with several.
+            let mut sequence = String::from('\\');
+            if let Some(c) = chars.next() {
+                sequence.push(c);
+                column += 1;
+                let digit: fn(&char) -> bool = match c {
+                    '0'..='7' => |c| c.is_digit(8),
+                    'x' => char::is_ascii_hexdigit,
+                    _ => |_| false,
+                };
+                while let Some(c) = chars.next_if(digit) {
+                    sequence.push(c);
+                    column += 1;
+                }
+            }
+            decoded += unescape(&sequence).0.len();
+        }
+        (self.line_index, column)
+    }
+}
+
+/// A string of an entry, with its keyword and where it is in the catalog.
+pub struct Field<'a> {
+    pub keyword: String,
+    pub value: &'a str,
+    pub segments: &'a Segments,
+}
+
+impl Field<'_> {
+    /// Returns the line and column in the catalog of a byte offset of the value.
+    pub fn position(&self, offset: usize) -> (u32, u32) {
+        self.segments.position(offset)
+    }
+
+    pub fn quoted(&self) -> String {
+        quoted(&self.keyword, self.value)
+    }
+}
+
+/// An entry of a catalog, with its comments and keywords.
+#[derive(Clone, Default, PartialEq)]
+pub struct Entry {
+    /// Comments written by translators (`# `)
+    pub translator_comments: Vec<String>,
+    /// Comments extracted from the source code (`#.`)
+    pub extracted_comments: Vec<String>,
+    /// Source code references (`#:`), e.g. `src/main.rs:42`
+    pub references: Vec<String>,
+    /// Flags (`#,`), e.g. `fuzzy` or `c-format`
+    pub flags: Vec<String>,
+    /// The msgctxt the entry had before msgmerge made it fuzzy (`#| msgctxt`)
+    pub previous_msgctxt: Option<String>,
+    /// The msgid the entry had before msgmerge made it fuzzy (`#| msgid`)
+    pub previous_msgid: Option<String>,
+    /// The msgid_plural the entry had before msgmerge made it fuzzy (`#| msgid_plural`)
+    pub previous_msgid_plural: Option<String>,
+    pub msgctxt: Option<String>,
+    pub msgctxt_segments: Segments,
+    pub msgid: String,
+    pub msgid_segments: Segments,
+    pub msgid_plural: Option<String>,
+    pub msgid_plural_segments: Segments,
+    pub msgstr: Vec<Msgstr>,
+    /// The line of the msgid keyword
+    pub line_index: u32,
+    /// Whether the entry was commented out with `#~`
+    pub obsolete: bool,
+    /// The text of the entry as read from the catalog, line endings and the blank lines before it
+    /// included
+    pub raw: String,
+    /// Whether the entry contains a line that could not be parsed, the entry being skipped from
+    /// there to the next blank line
+    pub malformed: bool,
+    /// The constructs of the entry that could not be parsed as-is
+    pub problems: Vec<SyntaxProblem>,
+}
+
+/// A malformed construct found while parsing an entry.
+#[derive(Clone, PartialEq)]
+pub struct SyntaxProblem {
+    /// A short description of the problem, e.g. "Invalid escape sequence"
+    pub message: &'static str,
+    pub line_index: u32,
+    pub column: u32,
+    /// The offending text
+    pub text: String,
+}
+
+impl Entry {
+    /// Returns the source string a translated form must be checked against.
+    pub fn source(&self, msgstr: &Msgstr) -> Field<'_> {
+        match &self.msgid_plural {
+            Some(msgid_plural) if msgstr.index > 0 => Field {
+                keyword: String::from("msgid_plural"),
+                value: msgid_plural,
+                segments: &self.msgid_plural_segments,
+            },
+            _ => Field {
+                keyword: String::from("msgid"),
+                value: &self.msgid,
+                segments: &self.msgid_segments,
+            },
+        }
+    }
+
+    /// Returns a translated form as a field of the entry.
+    pub fn translation<'a>(&self, msgstr: &'a Msgstr) -> Field<'a> {
+        Field {
+            keyword: self.msgstr_keyword(msgstr),
+            value: &msgstr.value,
+            segments: &msgstr.segments,
+        }
+    }
+
+    /// Returns the keyword of a translated form, `msgstr[N]` for plural entries.
+    pub fn msgstr_keyword(&self, msgstr: &Msgstr) -> String {
+        match self.msgid_plural {
+            Some(_) => format!("msgstr[{}]", msgstr.index),
+            None => String::from("msgstr"),
+        }
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    pub fn is_fuzzy(&self) -> bool {
+        self.has_flag("fuzzy")
+    }
+
+    /// Whether the entry is the catalog header, whose msgid is empty.
+    pub fn is_header(&self) -> bool {
+        self.msgid.is_empty() && self.msgctxt.is_none() && !self.obsolete
+    }
+
+    /// Returns all the keyword lines of the entry, for display in diagnostics.
+    ///
+    /// Previous-message comments come first, to show what the source string was changed from.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let previous = [
+            ("#| msgctxt", &self.previous_msgctxt),
+            ("#| msgid", &self.previous_msgid),
+            ("#| msgid_plural", &self.previous_msgid_plural),
+        ];
+        for (keyword, value) in previous {
+            if let Some(value) = value {
+                lines.push(quoted(keyword, value));
+            }
+        }
+        if let Some(msgctxt) = &self.msgctxt {
+            lines.push(quoted("msgctxt", msgctxt));
+        }
+        lines.push(quoted("msgid", &self.msgid));
+        if let Some(msgid_plural) = &self.msgid_plural {
+            lines.push(quoted("msgid_plural", msgid_plural));
+        }
+        for msgstr in &self.msgstr {
+            lines.push(quoted(&self.msgstr_keyword(msgstr), &msgstr.value));
+        }
+        lines
+    }
+}
+
+/// Formats a keyword and its string value the way it appears in the catalog.
+pub fn quoted(keyword: &str, value: &str) -> String {
+    format!("{} \"{}\"", keyword, escape(value))
+}
+
+/// Encodes a string value as the content of a PO string literal.
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Decodes the escape sequences of a PO string literal, returning the invalid ones apart with
+/// their offset in the decoded value.
+///
+/// Invalid escape sequences are kept verbatim in the decoded value.
+pub fn unescape(value: &str) -> (String, Vec<(usize, String)>) {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut invalid = Vec::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let decoded = match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('a') => '\x07',
+            Some('b') => '\x08',
+            Some('f') => '\x0c',
+            Some('v') => '\x0b',
+            Some(c @ ('\\' | '"')) => c,
+            Some(c @ '0'..='7') => {
+                let mut code = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+            }
+            Some('x') => {
+                let mut digits = String::new();
+                while let Some(digit) = chars.peek().filter(|c| c.is_ascii_hexdigit()) {
+                    digits.push(*digit);
+                    chars.next();
+                }
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(c) => c,
+                    None => {
+                        invalid.push((unescaped.len(), format!("\\x{}", digits)));
+                        unescaped.push_str("\\x");
+                        unescaped.push_str(&digits);
+                        continue;
+                    }
+                }
+            }
+            other => {
+                let sequence: String = std::iter::once('\\').chain(other).collect();
+                invalid.push((unescaped.len(), sequence.clone()));
+                unescaped.push_str(&sequence);
+                continue;
+            }
+        };
+        unescaped.push(decoded);
+    }
+    (unescaped, invalid)
+}
+
+/// The keyword whose string value the following continuation lines extend.
+enum Keyword {
+    None,
+    Msgctxt,
+    Msgid,
+    MsgidPlural,
+    Msgstr,
+}
+
+/// Strips the surrounding quotes of a PO string literal.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Parses the `N] "..."` remainder of a `msgstr[N]` line.
+fn parse_msgstr_index(value: &str) -> Option<(usize, &str)> {
+    let (index, value) = value.split_once(']')?;
+    Some((index.trim().parse().ok()?, value))
+}
+
+/// How the bytes of a catalog are decoded into lines.
+enum Decoding {
+    /// Not known until the start of the catalog has been sniffed
+    Unknown,
+    /// Each line is decoded on its own, which is sound for ASCII-compatible encodings
+    Lines(&'static Encoding),
+    /// The catalog is decoded chunk by chunk, for UTF-16 catalogs
+    Transcoded(Transcoder),
+}
+
+/// Decodes a catalog whose encoding is not ASCII-compatible, a buffered chunk at a time.
+struct Transcoder {
+    decoder: Decoder,
+    /// The text decoded but not yet split into lines
+    text: String,
+    finished: bool,
+}
+
+impl Transcoder {
+    /// Returns the next line with its terminator, and whether it had invalid bytes.
+    fn read_line<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<(String, bool)>> {
+        let mut had_errors = false;
+        loop {
+            if let Some(end) = self.text.find('\n') {
+                let rest = self.text.split_off(end + 1);
+                return Ok(Some((std::mem::replace(&mut self.text, rest), had_errors)));
+            }
+            if self.finished {
+                let line = std::mem::take(&mut self.text);
+                return Ok((!line.is_empty()).then_some((line, had_errors)));
+            }
+            let chunk = reader.fill_buf()?;
+            let last = chunk.is_empty();
+            if let Some(length) = self.decoder.max_utf8_buffer_length(chunk.len()) {
+                self.text.reserve(length);
+            }
+            let (_, read, errors) = self.decoder.decode_to_string(chunk, &mut self.text, last);
+            had_errors |= errors;
+            reader.consume(read);
+            self.finished = last;
+        }
+    }
+}
+
+/// A decoded line of the catalog.
+struct Line {
+    index: u32,
+    text: String,
+    /// The line terminator, empty for a last line without one
+    ending: &'static str,
+}
+
+/// Splits the line terminator off a line.
+fn split_ending(line: &[u8]) -> (&[u8], &'static str) {
+    if let Some(line) = line.strip_suffix(b"\r\n") {
+        (line, "\r\n")
+    } else if let Some(line) = line.strip_suffix(b"\n") {
+        (line, "\n")
+    } else {
+        (line, "")
+    }
+}
+
+/// Reads the entries of a catalog one at a time.
+///
+/// The catalog is transcoded to UTF-8 from the encoding given by its byte order mark, or else by
+/// the charset declared in its header.
+pub struct Parser<R> {
+    reader: R,
+    decoding: Decoding,
+    line_index: u32,
+    /// A line read ahead that starts the next entry
+    pending: Option<Line>,
+    /// The text after the last entry
+    trailer: String,
+    encoding: &'static Encoding,
+    bom: bool,
+    /// The problems found while reading lines, reported with the entry they belong to
+    problems: Vec<SyntaxProblem>,
+    strict: bool,
+}
+
+impl<R: BufRead> Parser<R> {
+    pub fn new(reader: R) -> Self {
+        Parser {
+            reader,
+            decoding: Decoding::Unknown,
+            line_index: 0,
+            pending: None,
+            trailer: String::new(),
+            encoding: UTF_8,
+            bom: false,
+            problems: Vec::new(),
+            strict: false,
+        }
+    }
+
+    /// Also reports the malformed constructs that are otherwise parsed on a best-effort basis:
+    /// unterminated strings, translations without msgid, stray continuation lines and duplicate
+    /// keywords.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the encoding the catalog is decoded from, once the first entry has been read.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    /// Whether the catalog starts with a byte order mark.
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Returns the comments and blank lines following the last entry, once all were read.
+    pub fn trailer(&self) -> &str {
+        &self.trailer
+    }
+
+    /// Completes an entry whose last line has been parsed.
+    fn finish(&self, mut entry: Entry, keyword: Keyword) -> Entry {
+        if self.strict && matches!(keyword, Keyword::Msgid | Keyword::MsgidPlural) {
+            entry.problems.push(SyntaxProblem {
+                message: "Missing msgstr",
+                line_index: entry.line_index,
+                column: 1,
+                text: quoted("msgid", &entry.msgid),
+            });
+        }
+        entry
+    }
+
+    /// Picks the encoding of the catalog from the bytes buffered at its start.
+    fn sniff(&mut self) -> io::Result<()> {
+        let head = self.reader.fill_buf()?;
+        let (encoding, bom_length) = match Encoding::for_bom(head) {
+            Some(bom) => bom,
+            None => (self.declared_encoding(), 0),
+        };
+        self.encoding = encoding;
+        self.bom = bom_length > 0;
+        self.reader.consume(bom_length);
+        self.decoding = if encoding == UTF_16LE || encoding == UTF_16BE {
+            Decoding::Transcoded(Transcoder {
+                decoder: encoding.new_decoder_without_bom_handling(),
+                text: String::new(),
+                finished: false,
+            })
+        } else {
+            Decoding::Lines(encoding)
+        };
+        Ok(())
+    }
+
+    /// Returns the encoding of the charset declared by the header, defaulting to UTF-8.
+    fn declared_encoding(&mut self) -> &'static Encoding {
+        let charset = bytes::Regex::new(r"(?i)charset=([A-Za-z0-9_.:-]+)").unwrap();
+        let label = match self.reader.fill_buf() {
+            Ok(head) => charset.captures(head).map(|captures| captures[1].to_vec()),
+            Err(_) => None,
+        };
+        match label {
+            // Templates leave the charset to be filled in by translators.
+            Some(label) if label != b"CHARSET" => {
+                Encoding::for_label(&label).unwrap_or_else(|| {
+                    self.problems.push(SyntaxProblem {
+                        message: "Unknown charset",
+                        line_index: 1,
+                        column: 1,
+                        text: String::from_utf8_lossy(&label).into_owned(),
+                    });
+                    UTF_8
+                })
+            }
+            _ => UTF_8,
+        }
+    }
+
+    fn next_line(&mut self) -> Option<io::Result<Line>> {
+        if let Some(pending) = self.pending.take() {
+            return Some(Ok(pending));
+        }
+        if let Decoding::Unknown = self.decoding {
+            if let Err(e) = self.sniff() {
+                return Some(Err(e));
+            }
+        }
+        let (text, ending) = match &mut self.decoding {
+            Decoding::Transcoded(transcoder) => match transcoder.read_line(&mut self.reader) {
+                Ok(Some((line, had_errors))) => {
+                    if had_errors {
+                        let encoding = transcoder.decoder.encoding();
+                        self.problems
+                            .push(invalid_bytes(encoding, self.line_index + 1));
+                    }
+                    let (line, ending) = split_ending(line.as_bytes());
+                    (String::from_utf8_lossy(line).into_owned(), ending)
+                }
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            },
+            Decoding::Lines(encoding) => {
+                let mut bytes = Vec::new();
+                match self.reader.read_until(b'\n', &mut bytes) {
+                    Ok(0) => return None,
+                    Ok(_) => {}
+                    Err(e) => return Some(Err(e)),
+                }
+                let (bytes, ending) = split_ending(&bytes);
+                let (line, had_errors) = encoding.decode_without_bom_handling(bytes);
+                if had_errors {
+                    self.problems
+                        .push(invalid_bytes(encoding, self.line_index + 1));
+                }
+                (line.into_owned(), ending)
+            }
+            Decoding::Unknown => unreachable!(),
+        };
+        self.line_index += 1;
+        Some(Ok(Line {
+            index: self.line_index,
+            text,
+            ending,
+        }))
+    }
+}
+
+fn invalid_bytes(encoding: &'static Encoding, line_index: u32) -> SyntaxProblem {
+    SyntaxProblem {
+        message: "Invalid byte sequence",
+        line_index,
+        column: 1,
+        text: format!("not valid {}", encoding.name()),
+    }
+}
+
+impl<R: BufRead> Iterator for Parser<R> {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut entry = Entry::default();
+        let mut keyword = Keyword::None;
+        let mut previous = Keyword::None;
+        while let Some(next) = self.next_line() {
+            let Line {
+                index: line_index,
+                text: raw,
+                ending,
+            } = match next {
+                Ok(next) => next,
+                Err(e) => return Some(Err(e)),
+            };
+            // Obsolete entries are made of regular keyword lines commented out with `#~`, and
+            // their previous messages of `#|` lines written `#~|`.
+            let previous_line;
+            let (line, obsolete) = match raw.strip_prefix("#~") {
+                Some(rest) if rest.starts_with('|') => {
+                    previous_line = format!("#{}", rest);
+                    (previous_line.as_str(), true)
+                }
+                Some(line) => (line.trim_start(), true),
+                None => (raw.as_str(), false),
+            };
+            // Blank lines separate entries, comments and msgctxt open a new one, as does a msgid
+            // not preceded by a msgctxt.
+            let starts_entry = line.trim().is_empty()
+                || line.starts_with('#')
+                || line.starts_with("msgctxt ")
+                || (line.starts_with("msgid ") && !matches!(keyword, Keyword::Msgctxt));
+            if starts_entry && !matches!(keyword, Keyword::None) {
+                self.pending = Some(Line {
+                    index: line_index,
+                    text: raw,
+                    ending,
+                });
+                return Some(Ok(self.finish(entry, keyword)));
+            }
+            entry.problems.append(&mut self.problems);
+            entry.raw.push_str(&raw);
+            entry.raw.push_str(ending);
+            if obsolete && !line.starts_with('"') {
+                entry.obsolete = true;
+            }
+            let strict = self.strict;
+            let syntax_problem = |message| SyntaxProblem {
+                message,
+                line_index,
+                column: 1,
+                text: raw.clone(),
+            };
+            let string = |value: &str, problems: &mut Vec<SyntaxProblem>| {
+                if strict && !is_terminated(value) {
+                    problems.push(syntax_problem("Unterminated string"));
+                }
+                let content = unquote(value);
+                // The string is a suffix of the raw line, minus the closing quote.
+                let start = raw.len() - value.trim_start().len() + 1;
+                let (value, invalid) = unescape(content);
+                let segment = Segment {
+                    offset: 0,
+                    line_index,
+                    column: raw[..start.min(raw.len())].chars().count() as u32 + 1,
+                    raw: content.to_string(),
+                };
+                for (offset, text) in invalid {
+                    let (line_index, column) = segment.position(offset);
+                    problems.push(SyntaxProblem {
+                        message: "Invalid escape sequence",
+                        line_index,
+                        column,
+                        text,
+                    });
+                }
+                (value, segment)
+            };
+            if let Some(line) = line.strip_prefix("#|") {
+                parse_previous(&mut entry, line.trim_start(), &mut previous);
+            } else if let Some(comment) = line.strip_prefix('#') {
+                parse_comment(&mut entry, comment);
+            } else if let Some(value) = line.strip_prefix("msgid_plural ") {
+                if strict && entry.msgid_plural.is_some() {
+                    entry.problems.push(syntax_problem("Duplicate keyword"));
+                }
+                let (value, segment) = string(value, &mut entry.problems);
+                entry.msgid_plural = Some(value);
+                entry.msgid_plural_segments = Segments(vec![segment]);
+                keyword = Keyword::MsgidPlural;
+            } else if let Some(value) = line.strip_prefix("msgctxt ") {
+                let (value, segment) = string(value, &mut entry.problems);
+                entry.msgctxt = Some(value);
+                entry.msgctxt_segments = Segments(vec![segment]);
+                keyword = Keyword::Msgctxt;
+            } else if let Some(value) = line.strip_prefix("msgid ") {
+                let (value, segment) = string(value, &mut entry.problems);
+                entry.msgid = value;
+                entry.msgid_segments = Segments(vec![segment]);
+                entry.line_index = line_index;
+                keyword = Keyword::Msgid;
+            } else if let Some((index, value)) = line
+                .strip_prefix("msgstr[")
+                .and_then(parse_msgstr_index)
+                .or_else(|| Some((0, line.strip_prefix("msgstr ")?)))
+            {
+                if strict && matches!(keyword, Keyword::None | Keyword::Msgctxt) {
+                    entry.problems.push(syntax_problem("Missing msgid"));
+                }
+                if strict && entry.msgstr.iter().any(|msgstr| msgstr.index == index) {
+                    entry.problems.push(syntax_problem("Duplicate keyword"));
+                }
+                let (value, segment) = string(value, &mut entry.problems);
+                entry.msgstr.push(Msgstr {
+                    index,
+                    value,
+                    line_index,
+                    segments: Segments(vec![segment]),
+                });
+                keyword = Keyword::Msgstr;
+            } else if line.starts_with('"') {
+                if strict && matches!(keyword, Keyword::None) {
+                    entry
+                        .problems
+                        .push(syntax_problem("Stray continuation line"));
+                }
+                let (continuation, segment) = string(line, &mut entry.problems);
+                let value = match keyword {
+                    Keyword::Msgctxt => entry
+                        .msgctxt
+                        .as_mut()
+                        .map(|msgctxt| (msgctxt, &mut entry.msgctxt_segments)),
+                    Keyword::Msgid => Some((&mut entry.msgid, &mut entry.msgid_segments)),
+                    Keyword::MsgidPlural => entry
+                        .msgid_plural
+                        .as_mut()
+                        .map(|msgid_plural| (msgid_plural, &mut entry.msgid_plural_segments)),
+                    Keyword::Msgstr => entry
+                        .msgstr
+                        .last_mut()
+                        .map(|msgstr| (&mut msgstr.value, &mut msgstr.segments)),
+                    Keyword::None => None,
+                };
+                if let Some((value, segments)) = value {
+                    segments.0.push(Segment {
+                        offset: value.len(),
+                        ..segment
+                    });
+                    value.push_str(&continuation);
+                }
+            } else if !line.trim().is_empty() {
+                entry.malformed = true;
+                entry.problems.push(syntax_problem("Malformed entry"));
+                // Recover at the next blank line, dropping the rest of the entry.
+                while let Some(next) = self.next_line() {
+                    match next {
+                        Ok(line) => {
+                            entry.raw.push_str(&line.text);
+                            entry.raw.push_str(line.ending);
+                            if line.text.trim().is_empty() {
+                                break;
+                            }
+                        }
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                entry.problems.append(&mut self.problems);
+                return Some(Ok(entry));
+            }
+        }
+        entry.problems.append(&mut self.problems);
+        match keyword {
+            // Lines after the last entry with problems, such as a stray continuation line, are
+            // reported as an entry rather than kept as the trailer, left as they are.
+            Keyword::None if !entry.problems.is_empty() => {
+                entry.malformed = true;
+                Some(Ok(entry))
+            }
+            Keyword::None => {
+                self.trailer = entry.raw;
+                None
+            }
+            _ => Some(Ok(self.finish(entry, keyword))),
+        }
+    }
+}
+
+/// Whether a string literal is enclosed in quotes, the closing one not being escaped.
+fn is_terminated(value: &str) -> bool {
+    let value = value.trim();
+    let Some(content) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return false;
+    };
+    let backslashes = content.chars().rev().take_while(|&c| c == '\\').count();
+    backslashes % 2 == 0
+}
+
+/// Parses a comment line, without its leading `#`, into the entry.
+fn parse_comment(entry: &mut Entry, comment: &str) {
+    if let Some(comment) = comment.strip_prefix('.') {
+        entry.extracted_comments.push(comment.trim().to_string());
+    } else if let Some(references) = comment.strip_prefix(':') {
+        entry
+            .references
+            .extend(references.split_whitespace().map(String::from));
+    } else if let Some(flags) = comment.strip_prefix(',') {
+        entry.flags.extend(
+            flags
+                .split(',')
+                .map(|flag| flag.trim().to_string())
+                .filter(|flag| !flag.is_empty()),
+        );
+    } else {
+        let comment = comment.strip_prefix(' ').unwrap_or(comment);
+        entry.translator_comments.push(comment.to_string());
+    }
+}
+
+/// Parses a previous-message comment line, without its leading `#|`, into the entry.
+///
+/// These are kept apart from the keywords of the entry so that they are never checked.
+fn parse_previous(entry: &mut Entry, line: &str, keyword: &mut Keyword) {
+    let value = if let Some(value) = line.strip_prefix("msgctxt ") {
+        *keyword = Keyword::Msgctxt;
+        entry.previous_msgctxt = Some(String::new());
+        value
+    } else if let Some(value) = line.strip_prefix("msgid_plural ") {
+        *keyword = Keyword::MsgidPlural;
+        entry.previous_msgid_plural = Some(String::new());
+        value
+    } else if let Some(value) = line.strip_prefix("msgid ") {
+        *keyword = Keyword::Msgid;
+        entry.previous_msgid = Some(String::new());
+        value
+    } else if line.starts_with('"') {
+        line
+    } else {
+        return;
+    };
+    let previous = match keyword {
+        Keyword::Msgctxt => entry.previous_msgctxt.as_mut(),
+        Keyword::Msgid => entry.previous_msgid.as_mut(),
+        Keyword::MsgidPlural => entry.previous_msgid_plural.as_mut(),
+        _ => None,
+    };
+    if let Some(previous) = previous {
+        previous.push_str(&unescape(unquote(value)).0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str, strict: bool) -> (Vec<Entry>, String) {
+        let mut parser = Parser::new(text.as_bytes()).strict(strict);
+        let entries = parser.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+        (entries, parser.trailer().to_string())
+    }
+
+    #[test]
+    fn parses_the_comments_and_keywords_of_entries() {
+        let text = "# A translator\n#. An extractor\n#: a.c:1 b.c:2\n#, fuzzy, c-format\n\
+                    #| msgid \"Old %d\"\nmsgctxt \"menu\"\nmsgid \"%d file\"\n\
+                    msgid_plural \"%d files\"\nmsgstr[0] \"%d fichier\"\nmsgstr[1] \"\"\n\"%d \"\n\"fichiers\"\n";
+        let (entries, trailer) = parse(text, true);
+        let entry = &entries[0];
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entry.translator_comments, ["A translator"]);
+        assert_eq!(entry.extracted_comments, ["An extractor"]);
+        assert_eq!(entry.references, ["a.c:1", "b.c:2"]);
+        assert_eq!(entry.flags, ["fuzzy", "c-format"]);
+        assert_eq!(entry.previous_msgid.as_deref(), Some("Old %d"));
+        assert_eq!(entry.msgctxt.as_deref(), Some("menu"));
+        assert_eq!(entry.msgid, "%d file");
+        assert_eq!(entry.msgid_plural.as_deref(), Some("%d files"));
+        let msgstr: Vec<_> = entry
+            .msgstr
+            .iter()
+            .map(|msgstr| (msgstr.index, msgstr.value.as_str()))
+            .collect();
+        assert_eq!(msgstr, [(0, "%d fichier"), (1, "%d fichiers")]);
+        assert_eq!(entry.line_index, 7);
+        assert!(entry.problems.is_empty());
+        assert_eq!(entry.raw, text);
+        assert_eq!(trailer, "");
+    }
+
+    #[test]
+    fn locates_the_offsets_of_values() {
+        let text = "msgid \"\"\n\"a\\tb\"\n\"{x}\"\nmsgstr \"\"\n";
+        let (entries, _) = parse(text, false);
+        let entry = &entries[0];
+        assert_eq!(entry.msgid, "a\tb{x}");
+        // The escape sequence takes two columns for one character.
+        assert_eq!(entry.msgid_segments.position(2), (2, 5));
+        assert_eq!(entry.msgid_segments.position(3), (3, 2));
+    }
+
+    #[test]
+    fn separates_entries_at_blank_lines_and_comments() {
+        let text = "msgid \"a\"\nmsgstr \"1\"\n# Next\nmsgid \"b\"\nmsgstr \"2\"\n\n\nmsgid \"c\"\nmsgstr \"3\"\n\n# The end\n";
+        let (entries, trailer) = parse(text, false);
+        let msgids: Vec<_> = entries.iter().map(|entry| entry.msgid.as_str()).collect();
+        assert_eq!(msgids, ["a", "b", "c"]);
+        assert_eq!(entries[2].raw, "\n\nmsgid \"c\"\nmsgstr \"3\"\n");
+        assert_eq!(trailer, "\n# The end\n");
+    }
+
+    #[test]
+    fn decodes_the_charset_of_the_header() {
+        let text = b"msgid \"\"\nmsgstr \"Content-Type: text/plain; charset=ISO-8859-1\\n\"\n\nmsgid \"caf\xe9\"\nmsgstr \"\"\n";
+        let mut parser = Parser::new(&text[..]);
+        let entries = parser.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(parser.encoding(), encoding_rs::WINDOWS_1252);
+        assert_eq!(entries[1].msgid, "café");
+        let text = b"\xef\xbb\xbfmsgid \"a\"\r\nmsgstr \"b\"\r\n";
+        let mut parser = Parser::new(&text[..]);
+        let entries = parser.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+        assert!(parser.has_bom());
+        assert_eq!(entries[0].msgstr[0].value, "b");
+        assert_eq!(entries[0].raw, "msgid \"a\"\r\nmsgstr \"b\"\r\n");
+    }
+
+    #[test]
+    fn reports_malformed_constructs_when_strict() {
+        let text = "msgid \"a\nmsgstr \"\\q\"\nmsgstr \"c\"\n\nmsgid \"d\"\n";
+        let problems = |strict| {
+            let (entries, _) = parse(text, strict);
+            entries
+                .iter()
+                .flat_map(|entry| entry.problems.iter().map(|problem| problem.message))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            problems(true),
+            [
+                "Unterminated string",
+                "Invalid escape sequence",
+                "Duplicate keyword",
+                "Missing msgstr"
+            ]
+        );
+        assert_eq!(problems(false), ["Invalid escape sequence"]);
+    }
+
+    #[test]
+    fn skips_malformed_entries_to_the_next_blank_line() {
+        let text = "msgid \"a\"\nbogus\nmsgstr \"b\"\n\nmsgid \"c\"\nmsgstr \"d\"\n";
+        let (entries, _) = parse(text, false);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].malformed);
+        assert!(entries[0].msgstr.is_empty());
+        assert_eq!(entries[0].raw, "msgid \"a\"\nbogus\nmsgstr \"b\"\n\n");
+        assert_eq!(entries[1].msgid, "c");
+    }
+
+    #[test]
+    fn reports_a_stray_line_after_the_last_entry() {
+        let text = "msgid \"a\"\nmsgstr \"b\"\n\n\"stray\"\n";
+        let (entries, trailer) = parse(text, true);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[1].malformed);
+        assert_eq!(entries[1].problems[0].message, "Stray continuation line");
+        assert_eq!(entries[1].problems[0].line_index, 4);
+        assert_eq!(trailer, "");
+        let (entries, trailer) = parse(text, false);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(trailer, "\n\"stray\"\n");
+    }
+
+    #[test]
+    fn parses_the_previous_messages_of_obsolete_entries() {
+        let text = "#~| msgid \"Old\"\n#~ msgid \"New\"\n#~ msgstr \"Nouveau\"\n";
+        let (entries, _) = parse(text, true);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].obsolete && !entries[0].malformed);
+        assert_eq!(entries[0].previous_msgid.as_deref(), Some("Old"));
+        assert_eq!(entries[0].msgid, "New");
+        assert_eq!(entries[0].msgstr[0].value, "Nouveau");
+    }
+}
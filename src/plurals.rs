@@ -0,0 +1,116 @@
+use crate::punctuation::base_language;
+
+/// The numbers of plural forms catalogs of a language may declare, from the plural categories CLDR
+/// defines for it.
+///
+/// Catalogs commonly leave out the categories only used by fractions or large numbers, such as the
+/// `many` of Russian or French, so that both counts are allowed.
+const PLURAL_FORMS: [(&str, usize, usize); 62] = [
+    ("ja", 1, 1),
+    ("zh", 1, 1),
+    ("ko", 1, 1),
+    ("vi", 1, 1),
+    ("th", 1, 1),
+    ("lo", 1, 1),
+    ("km", 1, 1),
+    ("id", 1, 1),
+    ("ms", 1, 1),
+    ("my", 1, 1),
+    ("tr", 1, 2),
+    ("az", 1, 2),
+    ("ka", 1, 2),
+    ("kk", 1, 2),
+    ("ky", 1, 2),
+    ("en", 2, 2),
+    ("de", 2, 2),
+    ("nl", 2, 2),
+    ("sv", 2, 2),
+    ("da", 2, 2),
+    ("nb", 2, 2),
+    ("nn", 2, 2),
+    ("no", 2, 2),
+    ("fi", 2, 2),
+    ("et", 2, 2),
+    ("el", 2, 2),
+    ("hu", 2, 2),
+    ("bg", 2, 2),
+    ("eu", 2, 2),
+    ("gl", 2, 2),
+    ("hi", 2, 2),
+    ("bn", 2, 2),
+    ("ur", 2, 2),
+    ("fa", 2, 2),
+    ("af", 2, 2),
+    ("sq", 2, 2),
+    ("hy", 2, 2),
+    ("is", 2, 2),
+    ("eo", 2, 2),
+    ("fr", 2, 3),
+    ("es", 2, 3),
+    ("it", 2, 3),
+    ("pt", 2, 3),
+    ("ca", 2, 3),
+    ("mk", 2, 3),
+    ("he", 2, 4),
+    ("ro", 3, 3),
+    ("lv", 3, 3),
+    ("hr", 3, 3),
+    ("sr", 3, 3),
+    ("bs", 3, 3),
+    ("ru", 3, 4),
+    ("uk", 3, 4),
+    ("be", 3, 4),
+    ("pl", 3, 4),
+    ("cs", 3, 4),
+    ("sk", 3, 4),
+    ("lt", 3, 4),
+    ("sl", 4, 4),
+    ("gd", 4, 4),
+    ("ga", 5, 5),
+    ("ar", 6, 6),
+];
+
+/// Returns the least and greatest numbers of plural forms of a `Language` header value, when
+/// known.
+pub fn expected(language: &str) -> Option<(usize, usize)> {
+    let language = base_language(language);
+    PLURAL_FORMS
+        .iter()
+        .find(|(code, _, _)| *code == language)
+        .map(|&(_, min, max)| (min, max))
+}
+
+/// Whether a Plural-Forms expression is the one of English and the other languages with a
+/// singular for 1 alone, which is kept when headers are copied from an English catalog.
+pub fn is_english(plural_forms: &str) -> bool {
+    let expression: String = plural_forms
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("plural="))
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    matches!(expression.as_str(), "n!=1" | "(n!=1)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knows_the_plural_forms_of_languages() {
+        assert_eq!(expected("ja"), Some((1, 1)));
+        assert_eq!(expected("fr_CA"), Some((2, 3)));
+        assert_eq!(expected("sr@latin"), Some((3, 3)));
+        assert_eq!(expected("ar"), Some((6, 6)));
+        assert_eq!(expected("tlh"), None);
+    }
+
+    #[test]
+    fn tells_the_english_expression() {
+        assert!(is_english("nplurals=2; plural=(n != 1);"));
+        assert!(is_english("nplurals=2; plural=n!=1;"));
+        assert!(!is_english("nplurals=2; plural=(n > 1);"));
+        assert!(!is_english("nplurals=1; plural=0;"));
+    }
+}
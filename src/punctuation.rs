@@ -0,0 +1,68 @@
+/// The languages whose scripts have no full stop, so that translations may leave it out.
+const WITHOUT_FULL_STOP: [&str; 3] = ["th", "lo", "km"];
+
+/// Returns the language code of a `Language` header value, e.g. `pt` for `pt_BR`.
+pub fn base_language(language: &str) -> &str {
+    language.split(['_', '-', '@']).next().unwrap_or(language)
+}
+
+/// Returns the terminal punctuation mark of a string, mapping the marks of other scripts to the
+/// Latin mark of the same meaning, e.g. `。` to `.` or `؟` to `?`.
+///
+/// In Greek, `;` is the question mark.
+pub fn ending(value: &str, language: Option<&str>) -> Option<char> {
+    let value = value.trim_end();
+    if value.ends_with("...") {
+        return Some('…');
+    }
+    let greek = language.is_some_and(|language| base_language(language) == "el");
+    match value.chars().last()? {
+        '.' | '。' | '．' | '।' | '۔' | '։' | '။' | '።' => Some('.'),
+        ':' | '：' => Some(':'),
+        '?' | '？' | '؟' | '՞' | '\u{37e}' => Some('?'),
+        ';' if greek => Some('?'),
+        '!' | '！' | '՜' => Some('!'),
+        '…' | '⋯' => Some('…'),
+        _ => None,
+    }
+}
+
+/// Whether a translation into the language may leave out a punctuation mark of its source.
+///
+/// Japanese questions may end with the particle か alone.
+pub fn may_drop(mark: char, language: Option<&str>) -> bool {
+    let Some(language) = language.map(base_language) else {
+        return false;
+    };
+    match mark {
+        '.' => WITHOUT_FULL_STOP.contains(&language),
+        '?' => language == "ja",
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_the_marks_of_other_scripts_to_latin_ones() {
+        assert_eq!(ending("Done. ", None), Some('.'));
+        assert_eq!(ending("完了。", None), Some('.'));
+        assert_eq!(ending("هل أنت متأكد؟", None), Some('?'));
+        assert_eq!(ending("Loading...", None), Some('…'));
+        assert_eq!(ending("Είστε σίγουροι;", Some("el_GR")), Some('?'));
+        assert_eq!(ending("a;", None), None);
+        assert_eq!(ending("Done", None), None);
+        assert_eq!(ending("", None), None);
+    }
+
+    #[test]
+    fn lets_some_languages_drop_some_marks() {
+        assert!(may_drop('.', Some("th")));
+        assert!(may_drop('?', Some("ja_JP")));
+        assert!(!may_drop('!', Some("ja")));
+        assert!(!may_drop('.', Some("fr")));
+        assert!(!may_drop('.', None));
+    }
+}
@@ -0,0 +1,871 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::html;
+use crate::json::Value;
+use crate::rules::{RuleInfo, RULES};
+use crate::stats::Stats;
+use crate::template::Template;
+use clap::ValueEnum;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// How diagnostics are printed.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored messages followed by the lines of the entry they are about
+    Text,
+    /// An array of objects with the file, position, rule, severity and message of each
+    /// diagnostic
+    Json,
+    /// A SARIF 2.1.0 log, for GitHub code scanning
+    Sarif,
+    /// A JUnit XML report, with a test suite for each catalog and a failed test case for each of
+    /// its diagnostics
+    Junit,
+    /// A Checkstyle XML report, for Jenkins, SonarQube and IDE plugins
+    Checkstyle,
+    /// A GitLab Code Quality report, for merge request widgets
+    Gitlab,
+    /// Comma-separated values, with a row for each diagnostic
+    Csv,
+    /// Tab-separated values, with a row for each diagnostic
+    Tsv,
+    /// A summary table and collapsible details by file, to post as a pull request comment
+    Markdown,
+    /// TAP version 13, with a test point for each catalog
+    Tap,
+    /// A standalone HTML page, with the coverage of each language and the diagnostics of each
+    /// catalog
+    Html,
+    /// GitHub Actions workflow commands, which annotate the lines of pull requests
+    Github,
+}
+
+/// What a run found, for the reports printed once every catalog is checked.
+pub struct Run<'a> {
+    /// The catalogs that were checked
+    pub files: &'a [PathBuf],
+    pub diagnostics: &'a [Diagnostic],
+    /// The translation progress of each language
+    pub languages: &'a BTreeMap<String, Stats>,
+    /// The language of each catalog, templates aside
+    pub file_languages: &'a HashMap<PathBuf, String>,
+    /// The template of text diagnostics, if they are not printed as usual
+    pub message_format: Option<&'a Template>,
+}
+
+/// Returns the report of the diagnostics of a run, as it is written to --output or printed once
+/// the run is over.
+pub fn render(format: OutputFormat, run: &Run) -> String {
+    let Run {
+        files, diagnostics, ..
+    } = *run;
+    match format {
+        OutputFormat::Text => match run.message_format {
+            Some(template) => lines(diagnostics, |diagnostic| template.render(diagnostic)),
+            None => lines(diagnostics, |diagnostic| diagnostic.text(false)),
+        },
+        OutputFormat::Github => lines(diagnostics, github),
+        OutputFormat::Json => json(diagnostics).pretty(),
+        OutputFormat::Sarif => sarif(diagnostics).pretty(),
+        OutputFormat::Junit => junit(files, diagnostics),
+        OutputFormat::Checkstyle => checkstyle(files, diagnostics),
+        OutputFormat::Gitlab => code_quality(diagnostics).pretty(),
+        OutputFormat::Html => html::report(run),
+        OutputFormat::Markdown => markdown(run),
+        OutputFormat::Tap => tap(run),
+        OutputFormat::Csv => table(run, ','),
+        OutputFormat::Tsv => table(run, '\t'),
+    }
+}
+
+/// Returns the diagnostics of a run one per line, for the formats that stream them.
+fn lines(diagnostics: &[Diagnostic], line: impl Fn(&Diagnostic) -> String) -> String {
+    diagnostics.iter().map(line).collect::<Vec<_>>().join("\n")
+}
+
+/// Returns the diagnostics of a run by file, with the files that have none.
+pub fn by_file<'a>(
+    files: &'a [PathBuf],
+    diagnostics: &'a [Diagnostic],
+) -> BTreeMap<&'a Path, Vec<&'a Diagnostic>> {
+    let mut by_file: BTreeMap<&Path, Vec<&Diagnostic>> = files
+        .iter()
+        .map(|file| (file.as_path(), Vec::new()))
+        .collect();
+    for diagnostic in diagnostics {
+        by_file
+            .entry(&diagnostic.path)
+            .or_default()
+            .push(diagnostic);
+    }
+    by_file
+}
+
+/// Escapes text for XML attributes and elements, writing the control characters XML cannot hold
+/// as `\u{1b}`.
+pub fn xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' || c == '\u{FFFE}' || c == '\u{FFFF}' => {
+                escaped.push_str(&format!("\\u{{{:x}}}", u32::from(c)))
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the lines of a diagnostic that explain it, without those showing the entry.
+pub fn details(diagnostic: &Diagnostic) -> Vec<&str> {
+    let located = format!("{}:", diagnostic.path.display());
+    diagnostic
+        .lines
+        .iter()
+        .filter(|line| !line.starts_with(&located) && !line.starts_with("msgctxt "))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Returns a path as a relative URI reference, or a `file` URI when it is absolute.
+fn uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = path.strip_prefix("./").unwrap_or(&path);
+    let mut uri = String::new();
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(char::from(byte))
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    if uri.starts_with('/') {
+        format!("file://{}", uri)
+    } else {
+        uri
+    }
+}
+
+fn object(members: Vec<(&str, Value)>) -> Value {
+    Value::Object(
+        members
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    )
+}
+
+/// Returns the diagnostics of a run as a SARIF log, with the metadata of every rule, those of the
+/// checks of library users after the others.
+pub fn sarif(diagnostics: &[Diagnostic]) -> Value {
+    let mut reported: Vec<&RuleInfo> = RULES.iter().collect();
+    for diagnostic in diagnostics {
+        if !reported.contains(&diagnostic.rule()) {
+            reported.push(diagnostic.rule());
+        }
+    }
+    let rules = reported
+        .iter()
+        .map(|rule| {
+            object(vec![
+                ("id", rule.code.into()),
+                ("name", rule.name.into()),
+                (
+                    "shortDescription",
+                    object(vec![("text", rule.description.into())]),
+                ),
+                (
+                    "defaultConfiguration",
+                    object(vec![("level", rule.severity.name().into())]),
+                ),
+            ])
+        })
+        .collect();
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let rule = diagnostic.rule();
+            let mut text = diagnostic.message.to_string();
+            for line in details(diagnostic) {
+                text.push('\n');
+                text.push_str(line);
+            }
+            let region = object(vec![
+                ("startLine", diagnostic.line_index.max(1).into()),
+                ("startColumn", diagnostic.column.max(1).into()),
+            ]);
+            let location = object(vec![(
+                "physicalLocation",
+                object(vec![
+                    (
+                        "artifactLocation",
+                        object(vec![("uri", uri(&diagnostic.path).into())]),
+                    ),
+                    ("region", region),
+                ]),
+            )]);
+            object(vec![
+                ("ruleId", rule.code.into()),
+                (
+                    "ruleIndex",
+                    reported.iter().position(|r| *r == rule).unwrap_or(0).into(),
+                ),
+                ("level", diagnostic.severity.name().into()),
+                ("message", object(vec![("text", text.into())])),
+                ("locations", Value::Array(vec![location])),
+            ])
+        })
+        .collect();
+    let driver = object(vec![
+        ("name", "po-parser".into()),
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        ("rules", Value::Array(rules)),
+    ]);
+    object(vec![
+        (
+            "$schema",
+            "https://json.schemastore.org/sarif-2.1.0.json".into(),
+        ),
+        ("version", "2.1.0".into()),
+        (
+            "runs",
+            Value::Array(vec![object(vec![
+                ("tool", object(vec![("driver", driver)])),
+                ("results", Value::Array(results)),
+            ])]),
+        ),
+    ])
+}
+
+/// Returns a diagnostic as a JSON object.
+fn diagnostic_json(diagnostic: &Diagnostic) -> Value {
+    let rule = diagnostic.rule();
+    let context = diagnostic.context.as_ref();
+    let strings =
+        |values: &[String]| Value::Array(values.iter().cloned().map(Value::from).collect());
+    Value::Object(vec![
+        (
+            String::from("file"),
+            diagnostic.path.to_string_lossy().into_owned().into(),
+        ),
+        (String::from("line"), diagnostic.line_index.into()),
+        (String::from("column"), diagnostic.column.into()),
+        (String::from("rule"), rule.code.into()),
+        (String::from("name"), rule.name.into()),
+        (String::from("severity"), diagnostic.severity.name().into()),
+        (String::from("message"), diagnostic.message.into()),
+        (
+            String::from("msgctxt"),
+            context.and_then(|context| context.msgctxt.clone()).into(),
+        ),
+        (
+            String::from("msgid"),
+            context.map(|context| context.msgid.clone()).into(),
+        ),
+        (
+            String::from("msgstr"),
+            context.and_then(|context| context.msgstr.clone()).into(),
+        ),
+        (
+            String::from("references"),
+            strings(context.map_or(&[], |context| &context.references)),
+        ),
+        (String::from("missing"), strings(&diagnostic.missing)),
+        (String::from("unexpected"), strings(&diagnostic.unexpected)),
+        (String::from("details"), strings(&diagnostic.lines)),
+    ])
+}
+
+/// Returns the diagnostics of a run as a JSON array.
+pub fn json(diagnostics: &[Diagnostic]) -> Value {
+    Value::Array(diagnostics.iter().map(diagnostic_json).collect())
+}
+
+/// Returns the diagnostics of a run as a JUnit XML report, each catalog being a test suite whose
+/// diagnostics are failed test cases, or which passes a single test case when it has none.
+pub fn junit(files: &[PathBuf], diagnostics: &[Diagnostic]) -> String {
+    let by_file = by_file(files, diagnostics);
+    let tests: usize = by_file
+        .values()
+        .map(|diagnostics| diagnostics.len().max(1))
+        .sum();
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    report.push_str(&format!(
+        "<testsuites name=\"po-parser\" tests=\"{}\" failures=\"{}\">\n",
+        tests,
+        diagnostics.len()
+    ));
+    for (path, diagnostics) in by_file {
+        let name = xml(&path.display().to_string());
+        report.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+            name,
+            diagnostics.len().max(1),
+            diagnostics.len()
+        ));
+        if diagnostics.is_empty() {
+            report.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"/>\n",
+                name, name
+            ));
+        }
+        for diagnostic in diagnostics {
+            let rule = diagnostic.rule();
+            let text = diagnostic.lines.join("\n");
+            report.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{} {} at line {}\">\n",
+                name,
+                rule.code,
+                xml(diagnostic.message),
+                diagnostic.line_index
+            ));
+            report.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                diagnostic.severity.name(),
+                xml(diagnostic.message),
+                xml(&text)
+            ));
+            report.push_str("    </testcase>\n");
+        }
+        report.push_str("  </testsuite>\n");
+    }
+    report.push_str("</testsuites>");
+    report
+}
+
+/// Escapes text for a GitHub workflow command, and also the separators of its properties when it
+/// is the value of one.
+fn workflow_escape(text: &str, property: bool) -> String {
+    let mut escaped = text
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    if property {
+        escaped = escaped.replace(':', "%3A").replace(',', "%2C");
+    }
+    escaped
+}
+
+/// Returns a diagnostic as a GitHub Actions workflow command, such as
+/// `::error file=fr.po,line=12,col=8,title=PO001 Missing interpolation::missing: {name}`.
+pub fn github(diagnostic: &Diagnostic) -> String {
+    let rule = diagnostic.rule();
+    let title = format!("{} {}", rule.code, diagnostic.message);
+    let details = details(diagnostic);
+    let message = if details.is_empty() {
+        diagnostic.message.to_string()
+    } else {
+        details.join("\n")
+    };
+    format!(
+        "::{} file={},line={},col={},title={}::{}",
+        diagnostic.severity.name(),
+        workflow_escape(&diagnostic.path.to_string_lossy(), true),
+        diagnostic.line_index,
+        diagnostic.column,
+        workflow_escape(&title, true),
+        workflow_escape(&message, false)
+    )
+}
+
+/// Returns the diagnostics of a run as a Checkstyle XML report, with an element for each catalog,
+/// those without diagnostics included.
+pub fn checkstyle(files: &[PathBuf], diagnostics: &[Diagnostic]) -> String {
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    report.push_str("<checkstyle version=\"4.3\">\n");
+    for (path, diagnostics) in by_file(files, diagnostics) {
+        let name = xml(&path.display().to_string());
+        if diagnostics.is_empty() {
+            report.push_str(&format!("  <file name=\"{}\"/>\n", name));
+            continue;
+        }
+        report.push_str(&format!("  <file name=\"{}\">\n", name));
+        for diagnostic in diagnostics {
+            let details = details(diagnostic);
+            let message = if details.is_empty() {
+                diagnostic.message.to_string()
+            } else {
+                format!("{}: {}", diagnostic.message, details.join("; "))
+            };
+            report.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"po-parser.{}\"/>\n",
+                diagnostic.line_index,
+                diagnostic.column,
+                diagnostic.severity.name(),
+                xml(&message),
+                diagnostic.rule().name
+            ));
+        }
+        report.push_str("  </file>\n");
+    }
+    report.push_str("</checkstyle>");
+    report
+}
+
+/// Returns the 64-bit FNV-1a hash of some text, which unlike the hasher of the standard library is
+/// the same in every version of the program.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Returns the diagnostics of a run as a GitLab Code Quality report.
+///
+/// Their fingerprints are made of the file, rule, message and details they are about instead of
+/// their line, so as to stay the same when entries are added above them.
+pub fn code_quality(diagnostics: &[Diagnostic]) -> Value {
+    let mut seen: HashMap<u64, usize> = HashMap::new();
+    let issues = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let rule = diagnostic.rule();
+            let path = diagnostic.path.to_string_lossy().replace('\\', "/");
+            let path = path.strip_prefix("./").unwrap_or(&path).to_string();
+            let context = diagnostic.context.clone().unwrap_or_default();
+            let details = details(diagnostic);
+            let key = [
+                path.as_str(),
+                rule.code,
+                context.msgctxt.as_deref().unwrap_or_default(),
+                &context.msgid,
+                &details.join("\n"),
+            ]
+            .join("\u{0}");
+            // Diagnostics that are the same but for their line are told apart by their order.
+            let occurrence = seen.entry(fnv1a(&key)).or_default();
+            *occurrence += 1;
+            let fingerprint = format!("{:016x}{:016x}", fnv1a(&key), *occurrence);
+            let description = if details.is_empty() {
+                diagnostic.message.to_string()
+            } else {
+                format!("{}: {}", diagnostic.message, details.join("; "))
+            };
+            object(vec![
+                ("description", description.into()),
+                ("check_name", rule.name.into()),
+                ("fingerprint", fingerprint.into()),
+                (
+                    "severity",
+                    match diagnostic.severity {
+                        Severity::Error => "major",
+                        Severity::Warning => "minor",
+                    }
+                    .into(),
+                ),
+                (
+                    "location",
+                    object(vec![
+                        ("path", path.into()),
+                        (
+                            "lines",
+                            object(vec![("begin", diagnostic.line_index.max(1).into())]),
+                        ),
+                    ]),
+                ),
+            ])
+        })
+        .collect();
+    Value::Array(issues)
+}
+
+/// Quotes a CSV field when it holds a separator, quote or line break, or writes the tabs and line
+/// breaks of a TSV field as `\t` and `\n`, which TSV cannot quote.
+fn field(value: &str, separator: char) -> String {
+    if separator == '\t' {
+        value
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    } else if value.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Returns the diagnostics of a run as a table of comma or tab-separated values, with a header
+/// row.
+pub fn table(run: &Run, separator: char) -> String {
+    let columns = [
+        "file", "line", "language", "rule", "severity", "message", "msgctxt", "msgid", "msgstr",
+        "detail",
+    ];
+    let mut rows = vec![columns.join(&separator.to_string())];
+    for diagnostic in run.diagnostics {
+        let context = diagnostic.context.clone().unwrap_or_default();
+        let language = run
+            .file_languages
+            .get(&diagnostic.path)
+            .map_or("", String::as_str);
+        let values = [
+            diagnostic.path.display().to_string(),
+            diagnostic.line_index.to_string(),
+            language.to_string(),
+            diagnostic.rule().code.to_string(),
+            diagnostic.severity.name().to_string(),
+            diagnostic.message.to_string(),
+            context.msgctxt.unwrap_or_default(),
+            context.msgid,
+            context.msgstr.unwrap_or_default(),
+            details(diagnostic).join("\n"),
+        ];
+        let values: Vec<_> = values.iter().map(|value| field(value, separator)).collect();
+        rows.push(values.join(&separator.to_string()));
+    }
+    rows.join("\n")
+}
+
+/// The most diagnostics of a file the Markdown report lists, so that comments stay readable and
+/// under the size limit of code hosts.
+const MARKDOWN_ROWS: usize = 50;
+
+/// Escapes text for a cell of a Markdown table.
+fn cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('<', "&lt;")
+        .replace('\n', "<br>")
+}
+
+/// Returns text as a Markdown code span, fenced with more backticks than it has in a row, or
+/// nothing when it is empty.
+fn code(text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    let padding = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!(
+        "{fence}{padding}{}{padding}{fence}",
+        text.replace('\n', "\\n")
+    )
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Returns a run as Markdown: a table of the files that have diagnostics, then their diagnostics
+/// in sections that expand when clicked.
+pub fn markdown(run: &Run) -> String {
+    let by_file = by_file(run.files, run.diagnostics);
+    let count = |diagnostics: &[&Diagnostic], severity| {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    };
+    let all: Vec<_> = run.diagnostics.iter().collect();
+    let failing: Vec<_> = by_file
+        .iter()
+        .filter(|(_, diagnostics)| !diagnostics.is_empty())
+        .collect();
+    let mut markdown = String::from("## Translation check\n\n");
+    if failing.is_empty() {
+        markdown.push_str(&format!(
+            "No problems found in {}.",
+            plural(by_file.len(), "file")
+        ));
+        return markdown;
+    }
+    markdown.push_str(&format!(
+        "{} and {} in {} of {}.\n\n",
+        plural(count(&all, Severity::Error), "error"),
+        plural(count(&all, Severity::Warning), "warning"),
+        failing.len(),
+        plural(by_file.len(), "file")
+    ));
+    markdown.push_str("| File | Errors | Warnings |\n| --- | ---: | ---: |\n");
+    for (path, diagnostics) in &failing {
+        markdown.push_str(&format!(
+            "| {} | {} | {} |\n",
+            code(&path.display().to_string()),
+            count(diagnostics, Severity::Error),
+            count(diagnostics, Severity::Warning)
+        ));
+    }
+    for (path, diagnostics) in &failing {
+        markdown.push_str(&format!(
+            "\n<details>\n<summary>{}: {}, {}</summary>\n\n",
+            cell(&path.display().to_string()),
+            plural(count(diagnostics, Severity::Error), "error"),
+            plural(count(diagnostics, Severity::Warning), "warning")
+        ));
+        markdown.push_str("| Line | Rule | Problem | Source | Translation |\n");
+        markdown.push_str("| ---: | --- | --- | --- | --- |\n");
+        for diagnostic in diagnostics.iter().take(MARKDOWN_ROWS) {
+            let context = diagnostic.context.as_ref();
+            let mut problem = diagnostic.message.to_string();
+            for line in details(diagnostic) {
+                problem.push('\n');
+                problem.push_str(line);
+            }
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                diagnostic.line_index,
+                diagnostic.rule().code,
+                cell(&problem),
+                cell(
+                    &context
+                        .map(|context| code(&context.msgid))
+                        .unwrap_or_default()
+                ),
+                cell(
+                    &context
+                        .and_then(|context| context.msgstr.as_deref())
+                        .map(code)
+                        .unwrap_or_default()
+                )
+            ));
+        }
+        if diagnostics.len() > MARKDOWN_ROWS {
+            markdown.push_str(&format!(
+                "\nand {} more.\n",
+                plural(diagnostics.len() - MARKDOWN_ROWS, "diagnostic")
+            ));
+        }
+        markdown.push_str("\n</details>\n");
+    }
+    markdown
+}
+
+/// Returns a run as TAP version 13, each catalog being a test point that fails when it has errors,
+/// with its diagnostics in a YAML block.
+pub fn tap(run: &Run) -> String {
+    let by_file = by_file(run.files, run.diagnostics);
+    let mut tap = format!("TAP version 13\n1..{}", by_file.len());
+    for (index, (path, diagnostics)) in by_file.iter().enumerate() {
+        let failed = diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error);
+        let status = if failed { "not ok" } else { "ok" };
+        tap.push_str(&format!("\n{} {} - {}", status, index + 1, path.display()));
+        if diagnostics.is_empty() {
+            continue;
+        }
+        // Strings are written as JSON, which YAML reads as double-quoted scalars.
+        tap.push_str("\n  ---\n  diagnostics:");
+        for diagnostic in diagnostics {
+            tap.push_str(&format!(
+                "\n    - line: {}\n      rule: {}\n      severity: {}\n      message: {}",
+                diagnostic.line_index,
+                diagnostic.rule().code,
+                diagnostic.severity.name(),
+                Value::from(diagnostic.message)
+            ));
+            let details = details(diagnostic);
+            if !details.is_empty() {
+                tap.push_str(&format!(
+                    "\n      detail: {}",
+                    Value::from(details.join("\n"))
+                ));
+            }
+        }
+        tap.push_str("\n  ...");
+    }
+    tap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{Context, Severity};
+    use std::path::PathBuf;
+
+    /// Returns a missing interpolation in `fr.po`, with the translation it is about.
+    fn missing(line_index: u32, severity: Severity) -> Diagnostic {
+        Diagnostic {
+            message: "Missing interpolation",
+            severity,
+            context: Some(Context {
+                msgctxt: None,
+                msgid: String::from("Hello {user}"),
+                msgstr: Some(String::from("Bonjour, | <b>")),
+                references: vec![String::from("src/app.py:12")],
+            }),
+            missing: vec![String::from("{user}")],
+            unexpected: Vec::new(),
+            path: PathBuf::from("fr.po"),
+            line_index,
+            column: 8,
+            lines: vec![
+                String::from("fr.po:3:8: msgid \"Hello {user}\""),
+                String::from("missing: {user}"),
+            ],
+            rule: None,
+        }
+    }
+
+    /// Returns a run of catalogs, those of `fr.po` being in French.
+    fn run<'a>(
+        files: &'a [PathBuf],
+        diagnostics: &'a [Diagnostic],
+        file_languages: &'a HashMap<PathBuf, String>,
+    ) -> Run<'a> {
+        static LANGUAGES: BTreeMap<String, Stats> = BTreeMap::new();
+        Run {
+            files,
+            diagnostics,
+            languages: &LANGUAGES,
+            file_languages,
+            message_format: None,
+        }
+    }
+
+    fn french() -> HashMap<PathBuf, String> {
+        HashMap::from([(PathBuf::from("fr.po"), String::from("fr"))])
+    }
+
+    #[test]
+    fn escapes_xml() {
+        assert_eq!(
+            xml("<a href=\"x\">&'\u{1b}\n"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;\\u{1b}\n"
+        );
+    }
+
+    #[test]
+    fn reports_diagnostics_as_json() {
+        let report = json(&[missing(3, Severity::Error)]);
+        let diagnostic = &report.as_array().unwrap()[0];
+        let field = |name| diagnostic.get(name).cloned();
+        assert_eq!(field("rule"), Some(Value::from("PO001")));
+        assert_eq!(field("name"), Some(Value::from("missing-interpolation")));
+        assert_eq!(field("line"), Some(Value::Number(3.0)));
+        assert_eq!(field("msgctxt"), Some(Value::Null));
+        assert_eq!(
+            field("missing"),
+            Some(Value::Array(vec![Value::from("{user}")]))
+        );
+    }
+
+    #[test]
+    fn passes_the_catalogs_without_diagnostics_in_junit() {
+        let files = [PathBuf::from("de.po"), PathBuf::from("fr.po")];
+        let report = junit(&files, &[missing(3, Severity::Error)]);
+        assert!(report.contains("<testsuites name=\"po-parser\" tests=\"2\" failures=\"1\">"));
+        assert!(report.contains("<testcase classname=\"de.po\" name=\"de.po\"/>"));
+        assert!(report.contains("name=\"PO001 Missing interpolation at line 3\">"));
+    }
+
+    #[test]
+    fn lists_every_catalog_in_checkstyle() {
+        let files = [PathBuf::from("de.po"), PathBuf::from("fr.po")];
+        let report = checkstyle(&files, &[missing(3, Severity::Warning)]);
+        assert!(report.contains("  <file name=\"de.po\"/>\n"));
+        assert!(report.contains(
+            "<error line=\"3\" column=\"8\" severity=\"warning\" message=\"Missing interpolation: missing: {user}\" source=\"po-parser.missing-interpolation\"/>"
+        ));
+    }
+
+    #[test]
+    fn escapes_the_properties_of_workflow_commands() {
+        let mut diagnostic = missing(3, Severity::Error);
+        diagnostic.path = PathBuf::from("a,b:c.po");
+        diagnostic.lines[0] = String::from("a,b:c.po:3:8: msgid \"Hello {user}\"");
+        diagnostic.lines.push(String::from("100% done"));
+        assert_eq!(
+            github(&diagnostic),
+            "::error file=a%2Cb%3Ac.po,line=3,col=8,title=PO001 Missing interpolation::missing: {user}%0A100%25 done"
+        );
+    }
+
+    #[test]
+    fn quotes_the_fields_of_tables() {
+        let files = [PathBuf::from("fr.po")];
+        let diagnostics = [missing(3, Severity::Error)];
+        let csv = table(&run(&files, &diagnostics, &french()), ',');
+        let rows: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            rows[0],
+            "file,line,language,rule,severity,message,msgctxt,msgid,msgstr,detail"
+        );
+        assert_eq!(rows[1], "fr.po,3,fr,PO001,error,Missing interpolation,,Hello {user},\"Bonjour, | <b>\",missing: {user}");
+        let tsv = table(&run(&files, &diagnostics, &french()), '\t');
+        assert!(tsv.ends_with("\tBonjour, | <b>\tmissing: {user}"));
+    }
+
+    #[test]
+    fn summarizes_runs_in_markdown() {
+        let files = [PathBuf::from("de.po"), PathBuf::from("fr.po")];
+        assert_eq!(
+            markdown(&run(&files, &[], &french())),
+            "## Translation check\n\nNo problems found in 2 files."
+        );
+        let diagnostics = [missing(3, Severity::Error), missing(9, Severity::Warning)];
+        let report = markdown(&run(&files, &diagnostics, &french()));
+        assert!(report.contains("1 error and 1 warning in 1 of 2 files."));
+        assert!(report.contains("| `fr.po` | 1 | 1 |\n"));
+        assert!(report.contains(
+            "| 3 | PO001 | Missing interpolation<br>missing: {user} | `Hello {user}` | `Bonjour, \\| &lt;b>` |"
+        ));
+    }
+
+    #[test]
+    fn fails_the_catalogs_with_errors_in_tap() {
+        let files = [PathBuf::from("de.po"), PathBuf::from("fr.po")];
+        let report = tap(&run(&files, &[missing(3, Severity::Warning)], &french()));
+        assert!(report.starts_with("TAP version 13\n1..2\nok 1 - de.po\nok 2 - fr.po\n"));
+        let report = tap(&run(&files, &[missing(3, Severity::Error)], &french()));
+        assert!(report.contains(
+            "\nnot ok 2 - fr.po\n  ---\n  diagnostics:\n    - line: 3\n      rule: PO001\n"
+        ));
+        assert!(report.contains("      detail: \"missing: {user}\""));
+    }
+
+    static CUSTOM: RuleInfo = RuleInfo {
+        code: "X001",
+        name: "custom",
+        messages: &["Custom problem"],
+        description: "A problem a library user checks.",
+        flag: None,
+        needs_value: false,
+        severity: Severity::Error,
+    };
+
+    #[test]
+    fn lists_the_rules_of_library_users_in_sarif() {
+        let diagnostic = Diagnostic {
+            message: "Custom problem",
+            severity: Severity::Error,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: PathBuf::from("fr.po"),
+            line_index: 1,
+            column: 1,
+            lines: Vec::new(),
+            rule: Some(&CUSTOM),
+        };
+        let log = sarif(&[diagnostic]);
+        let run = &log.get("runs").and_then(Value::as_array).unwrap()[0];
+        let rules = run
+            .get("tool")
+            .and_then(|tool| tool.get("driver"))
+            .and_then(|driver| driver.get("rules"))
+            .and_then(Value::as_array)
+            .unwrap();
+        let result = &run.get("results").and_then(Value::as_array).unwrap()[0];
+        let index = result.get("ruleIndex").and_then(Value::as_f64).unwrap() as usize;
+        assert_eq!(rules.len(), RULES.len() + 1);
+        assert_eq!(rules[index].get("id").and_then(Value::as_str), Some("X001"));
+    }
+}
@@ -0,0 +1,482 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::punctuation::base_language;
+
+/// A check, identified by a stable code and name that can be given to `--enable`, `--disable`
+/// and `--severity`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct RuleInfo {
+    /// The code the rule is reported with, e.g. "PO001"
+    pub code: &'static str,
+    /// The name of the rule in kebab case, e.g. "missing-interpolation"
+    pub name: &'static str,
+    /// The messages of the diagnostics the rule reports
+    pub messages: &'static [&'static str],
+    /// What the rule reports, in a sentence
+    pub description: &'static str,
+    /// The flag that turns on the rule when it is off by default
+    pub flag: Option<&'static str>,
+    /// Whether the flag needs a value, without which `--enable` cannot turn on the rule
+    pub needs_value: bool,
+    /// The severity of the diagnostics of the rule, unless `--severity` changes it
+    pub severity: Severity,
+}
+
+const fn rule(
+    code: &'static str,
+    name: &'static str,
+    messages: &'static [&'static str],
+    description: &'static str,
+) -> RuleInfo {
+    RuleInfo {
+        code,
+        name,
+        messages,
+        description,
+        flag: None,
+        needs_value: false,
+        severity: Severity::Error,
+    }
+}
+
+impl RuleInfo {
+    /// Marks the rule as off unless a flag is given.
+    const fn enabled_by(self, flag: &'static str) -> RuleInfo {
+        RuleInfo {
+            flag: Some(flag),
+            ..self
+        }
+    }
+
+    /// Marks the diagnostics of the rule as warnings, which do not fail the run.
+    const fn warning(self) -> RuleInfo {
+        RuleInfo {
+            severity: Severity::Warning,
+            ..self
+        }
+    }
+
+    /// Marks the rule as off unless a flag is given a value.
+    const fn configured_by(self, flag: &'static str) -> RuleInfo {
+        RuleInfo {
+            flag: Some(flag),
+            needs_value: true,
+            ..self
+        }
+    }
+}
+
+/// Every rule, in the order of their codes, which are never reused.
+pub static RULES: [RuleInfo; 35] = [
+    rule(
+        "PO001",
+        "missing-interpolation",
+        &["Missing interpolation"],
+        "A placeholder of the source is missing from the translation.",
+    ),
+    rule(
+        "PO002",
+        "extra-interpolation",
+        &["Extra interpolation"],
+        "The translation has a placeholder that is not in the source.",
+    ),
+    rule(
+        "PO003",
+        "format-mismatch",
+        &["Format mismatch"],
+        "The format directives of the translation do not match those of the source.",
+    ),
+    rule(
+        "PO004",
+        "invalid-format",
+        &["Invalid format directive"],
+        "A string flagged with a format language has a directive it does not accept.",
+    ),
+    rule(
+        "PO005",
+        "tag-mismatch",
+        &["Tag mismatch"],
+        "The translation does not keep the HTML tags and entities of the source.",
+    ),
+    rule(
+        "PO006",
+        "date-format-mismatch",
+        &["Date format mismatch"],
+        "The strftime conversions of the translation do not match those of the source.",
+    ),
+    rule(
+        "PO007",
+        "invalid-date-format",
+        &["Invalid date format directive"],
+        "A date format has a conversion that strftime does not accept.",
+    ),
+    rule(
+        "PO008",
+        "accelerator",
+        &["Missing accelerator", "Duplicate accelerator"],
+        "The translation does not have exactly one accelerator when its source has one.",
+    )
+    .configured_by("--accelerator"),
+    rule(
+        "PO009",
+        "whitespace",
+        &["Newline mismatch", "Whitespace mismatch"],
+        "The translation does not start or end with the same whitespace as its source.",
+    ),
+    rule(
+        "PO010",
+        "punctuation",
+        &["Punctuation mismatch"],
+        "The translation drops, adds or changes the punctuation ending its source.",
+    ),
+    rule(
+        "PO011",
+        "plural-forms",
+        &["Plural form count mismatch"],
+        "A plural entry does not have as many translations as the header declares forms.",
+    ),
+    rule(
+        "PO012",
+        "fuzzy",
+        &["Fuzzy entry"],
+        "The entry is flagged as fuzzy.",
+    )
+    .enabled_by("--fail-on-fuzzy"),
+    rule(
+        "PO013",
+        "untranslated",
+        &["Untranslated entry"],
+        "The entry has no translation.",
+    )
+    .enabled_by("--fail-on-untranslated"),
+    rule(
+        "PO014",
+        "duplicate-entry",
+        &["Duplicate entry"],
+        "An entry has the same context and source as one earlier in the file.",
+    ),
+    rule(
+        "PO015",
+        "syntax",
+        &[
+            "Missing msgid",
+            "Missing msgstr",
+            "Duplicate keyword",
+            "Unterminated string",
+            "Invalid escape sequence",
+            "Stray continuation line",
+            "Malformed entry",
+        ],
+        "A line of the catalog cannot be parsed as it is.",
+    ),
+    rule(
+        "PO016",
+        "encoding",
+        &["Unknown charset", "Invalid byte sequence"],
+        "The catalog is not in the charset its header declares.",
+    ),
+    rule(
+        "PO017",
+        "header",
+        &[
+            "Missing header",
+            "Missing header field",
+            "Invalid header field",
+            "Inconsistent header field",
+        ],
+        "The header of the catalog is missing, or one of its fields is missing or wrong.",
+    ),
+    rule(
+        "PO018",
+        "io",
+        &["Unreadable file", "Unwritable file"],
+        "A catalog cannot be read, or rewritten by --normalize or fix.",
+    ),
+    rule(
+        "PO019",
+        "invisible-character",
+        &["Invisible character"],
+        "The translation has a control, zero-width or byte order mark character.",
+    ),
+    rule(
+        "PO020",
+        "unnormalized",
+        &["Unnormalized translation"],
+        "The translation is not in Unicode Normalization Form C.",
+    ),
+    rule(
+        "PO021",
+        "mojibake",
+        &["Mojibake"],
+        "The translation has characters of UTF-8 text decoded as Windows-1252.",
+    ),
+    rule(
+        "PO022",
+        "bidi-control",
+        &["Unbalanced directional control"],
+        "A directional embedding, override or isolate of the translation is not closed.",
+    ),
+    rule(
+        "PO023",
+        "bidi-placeholder",
+        &["Unisolated placeholder"],
+        "A placeholder of a right-to-left translation is not isolated from the text around it.",
+    )
+    .warning(),
+    rule(
+        "PO024",
+        "typography",
+        &["Typography mismatch"],
+        "The translation does not follow the typography of its language.",
+    ),
+    rule(
+        "PO025",
+        "link-mismatch",
+        &["Link mismatch"],
+        "The translation drops or changes the URLs and email addresses of its source.",
+    ),
+    rule(
+        "PO026",
+        "number-mismatch",
+        &["Number mismatch"],
+        "The translation drops or changes the numbers of its source.",
+    )
+    .enabled_by("--numbers"),
+    rule(
+        "PO027",
+        "markdown-mismatch",
+        &["Markdown mismatch"],
+        "The translation does not keep the Markdown structure of its source.",
+    )
+    .enabled_by("--markdown"),
+    rule(
+        "PO028",
+        "length-mismatch",
+        &["Length mismatch"],
+        "The translation is much shorter or longer than its source.",
+    )
+    .configured_by("--length-ratio"),
+    rule(
+        "PO029",
+        "glossary-mismatch",
+        &["Glossary mismatch"],
+        "The translation does not use the translation of a term the glossary mandates.",
+    )
+    .configured_by("--glossary"),
+    rule(
+        "PO030",
+        "protected-term",
+        &["Protected term mismatch"],
+        "The translation does not keep a protected term as it is.",
+    )
+    .configured_by("--protect"),
+    rule(
+        "PO031",
+        "misspelled-word",
+        &["Misspelled word"],
+        "A word of the translation is not in the dictionary of its language.",
+    )
+    .enabled_by("--spellcheck")
+    .warning(),
+    rule(
+        "PO032",
+        "missing-dictionary",
+        &["Missing dictionary"],
+        "The language of a catalog has no dictionary to spellcheck it with.",
+    )
+    .enabled_by("--spellcheck")
+    .warning(),
+    rule(
+        "PO033",
+        "same-as-source",
+        &["Same as source"],
+        "The translation is identical to its source.",
+    )
+    .enabled_by("--same-as-source"),
+    rule(
+        "PO034",
+        "inconsistent-translation",
+        &["Inconsistent translation"],
+        "A message is translated differently in catalogs of the same language.",
+    )
+    .enabled_by("--cross-file"),
+    rule(
+        "PO035",
+        "unused-suppression",
+        &["Unused suppression"],
+        "A `# po-parser:ignore` comment suppresses no diagnostic of its entry.",
+    )
+    .warning(),
+];
+
+/// Returns the rule of a code, such as `PO001`, or a name, such as `missing-interpolation`.
+pub fn find(value: &str) -> Option<&'static RuleInfo> {
+    RULES
+        .iter()
+        .find(|rule| rule.code.eq_ignore_ascii_case(value) || rule.name == value)
+}
+
+/// Returns the rule reporting diagnostics with a message, unless it is none of `RULES`.
+pub fn of(message: &str) -> Option<&'static RuleInfo> {
+    RULES.iter().find(|rule| rule.messages.contains(&message))
+}
+
+/// The rule of the diagnostics of library users that name none, and whose message is not one of
+/// `RULES`.
+pub static CUSTOM: RuleInfo = rule(
+    "PO000",
+    "custom",
+    &[],
+    "A problem reported by a check of a library user.",
+);
+
+/// Which rules are reported, and with which severity.
+#[derive(Clone, Default)]
+pub struct Settings {
+    pub enabled: Vec<&'static RuleInfo>,
+    pub disabled: Vec<&'static RuleInfo>,
+    pub severities: Vec<(&'static RuleInfo, Severity)>,
+    /// The settings of the catalogs of a language, such as `fr`, which take precedence
+    pub languages: Vec<(String, Settings)>,
+    /// The rules off by default that only the settings of some languages turn on
+    pub restricted: Vec<&'static RuleInfo>,
+}
+
+impl Settings {
+    /// Whether `--enable` was given a rule, which then runs as if its flag was given, for every
+    /// language or some of them.
+    pub fn enables(&self, name: &str) -> bool {
+        self.enabled
+            .iter()
+            .chain(
+                self.languages
+                    .iter()
+                    .flat_map(|(_, settings)| &settings.enabled),
+            )
+            .any(|rule| rule.name == name)
+    }
+
+    /// Returns the settings of a language, or else of its base language, as `fr` for `fr_CA`.
+    fn language(&self, language: &str) -> Option<&Settings> {
+        let find = |language: &str| {
+            self.languages
+                .iter()
+                .find(|(name, _)| name == language)
+                .map(|(_, settings)| settings)
+        };
+        find(language).or_else(|| find(base_language(language)))
+    }
+
+    /// Returns a diagnostic with the severity its rule is given, unless the rule is turned off,
+    /// in the catalogs of its language when it is known.
+    ///
+    /// Rules turned on by their own flag, rather than `--enable`, are reported as well.
+    pub fn apply(&self, mut diagnostic: Diagnostic, language: Option<&str>) -> Option<Diagnostic> {
+        let rule = diagnostic.rule();
+        let overrides = language.and_then(|language| self.language(language));
+        let enabled = overrides.is_some_and(|settings| settings.enabled.contains(&rule));
+        if overrides.is_some_and(|settings| settings.disabled.contains(&rule))
+            || !enabled && (self.disabled.contains(&rule) || self.restricted.contains(&rule))
+        {
+            return None;
+        }
+        let severities = overrides.map_or(&[][..], |settings| &settings.severities);
+        if let Some((_, severity)) = self
+            .severities
+            .iter()
+            .chain(severities)
+            .rev()
+            .find(|(r, _)| *r == rule)
+        {
+            diagnostic.severity = *severity;
+        }
+        Some(diagnostic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    fn missing_interpolation() -> Diagnostic {
+        Diagnostic {
+            message: "Missing interpolation",
+            severity: Severity::Error,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: PathBuf::from("fr.po"),
+            line_index: 1,
+            column: 1,
+            lines: Vec::new(),
+            rule: None,
+        }
+    }
+
+    /// Returns the severity a diagnostic is reported with in a language, if it is reported.
+    fn reported(settings: &Settings, language: Option<&str>) -> Option<Severity> {
+        settings
+            .apply(missing_interpolation(), language)
+            .map(|diagnostic| diagnostic.severity)
+    }
+
+    #[test]
+    fn each_message_has_a_single_rule() {
+        let mut codes = HashSet::new();
+        let mut names = HashSet::new();
+        let mut messages = HashSet::new();
+        for rule in &RULES {
+            assert!(codes.insert(rule.code), "{}", rule.code);
+            assert!(names.insert(rule.name), "{}", rule.name);
+            for message in rule.messages {
+                assert!(messages.insert(message), "{}", message);
+            }
+        }
+    }
+
+    #[test]
+    fn finds_rules_by_code_or_name() {
+        let rule = of("Missing interpolation").unwrap();
+        assert_eq!(find("PO001"), Some(rule));
+        assert_eq!(find("po001"), Some(rule));
+        assert_eq!(find("missing-interpolation"), Some(rule));
+        assert_eq!(find("Missing-Interpolation"), None);
+    }
+
+    #[test]
+    fn applies_the_severity_and_the_disabled_rules() {
+        let rule = of("Missing interpolation").unwrap();
+        assert_eq!(reported(&Settings::default(), None), Some(Severity::Error));
+        let settings = Settings {
+            severities: vec![(rule, Severity::Warning)],
+            ..Settings::default()
+        };
+        assert_eq!(reported(&settings, Some("fr")), Some(Severity::Warning));
+        let settings = Settings {
+            disabled: vec![rule],
+            ..Settings::default()
+        };
+        assert_eq!(reported(&settings, Some("fr")), None);
+    }
+
+    #[test]
+    fn the_settings_of_a_language_take_precedence() {
+        let rule = of("Missing interpolation").unwrap();
+        let french = Settings {
+            enabled: vec![rule],
+            severities: vec![(rule, Severity::Warning)],
+            ..Settings::default()
+        };
+        let settings = Settings {
+            restricted: vec![rule],
+            languages: vec![(String::from("fr"), french)],
+            ..Settings::default()
+        };
+        assert!(settings.enables("missing-interpolation"));
+        assert_eq!(reported(&settings, Some("fr")), Some(Severity::Warning));
+        assert_eq!(reported(&settings, Some("fr_CA")), Some(Severity::Warning));
+        assert_eq!(reported(&settings, Some("de")), None);
+        assert_eq!(reported(&settings, None), None);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::punctuation::base_language;
+use encoding_rs::Encoding;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// How the flags of words and affixes are written in a dictionary.
+#[derive(Clone, Copy)]
+enum FlagKind {
+    /// One character per flag
+    Char,
+    /// Two characters per flag
+    Long,
+    /// Comma-separated numbers
+    Number,
+}
+
+impl FlagKind {
+    fn parse(self, flags: &str) -> Vec<u32> {
+        match self {
+            FlagKind::Char => flags.chars().map(u32::from).collect(),
+            FlagKind::Long => {
+                let chars: Vec<_> = flags.chars().collect();
+                chars
+                    .chunks(2)
+                    .map(|pair| pair.iter().fold(0, |flag, &c| flag << 16 | u32::from(c)))
+                    .collect()
+            }
+            FlagKind::Number => flags
+                .split(',')
+                .filter_map(|flag| flag.trim().parse().ok())
+                .collect(),
+        }
+    }
+}
+
+/// A prefix or suffix rule of a dictionary, such as `SFX S y ies [^aeiou]y`.
+struct Affix {
+    flag: u32,
+    /// Whether the rule combines with affixes of the other kind
+    cross: bool,
+    /// The characters the affix replaces in the stem
+    strip: String,
+    add: String,
+    /// Matches the stems the rule applies to
+    condition: Regex,
+}
+
+/// A Hunspell dictionary, of which only the word list and the prefix and suffix rules are used.
+///
+/// Compound words are not recognized, so that languages forming them freely need a wordlist.
+pub struct Dictionary {
+    pub name: String,
+    words: HashMap<String, Vec<u32>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    /// The flag of words that are only valid with an affix
+    need_affix: Option<u32>,
+    forbidden: Option<u32>,
+}
+
+/// Decodes a dictionary file in the encoding its affix file declares.
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> String {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+/// Returns the characters an affix rule strips or adds, `0` standing for none.
+fn empty_affix(affix: &str) -> String {
+    match affix {
+        "0" => String::new(),
+        _ => affix.to_string(),
+    }
+}
+
+/// Converts the condition of an affix rule, written with `.`, `[...]` and `[^...]`, to a regex.
+fn condition(condition: &str, suffix: bool) -> Regex {
+    let mut pattern = String::new();
+    let mut in_class = false;
+    for c in condition.chars() {
+        match c {
+            '[' => {
+                in_class = true;
+                pattern.push('[');
+            }
+            ']' => {
+                in_class = false;
+                pattern.push(']');
+            }
+            '^' if in_class => pattern.push('^'),
+            '.' if !in_class => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    let pattern = if condition == "." {
+        String::new()
+    } else if suffix {
+        format!("{}$", pattern)
+    } else {
+        format!("^{}", pattern)
+    };
+    Regex::new(&pattern).unwrap_or_else(|_| Regex::new("").unwrap())
+}
+
+impl Dictionary {
+    /// Reads the `.dic` and `.aff` files of a dictionary.
+    pub fn load(dic: &Path, aff: &Path) -> io::Result<Dictionary> {
+        let aff_bytes = fs::read(aff)?;
+        let encoding = String::from_utf8_lossy(&aff_bytes)
+            .lines()
+            .find_map(|line| line.strip_prefix("SET "))
+            .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+            .unwrap_or(encoding_rs::UTF_8);
+        let aff_text = decode(&aff_bytes, encoding);
+        let mut flags = FlagKind::Char;
+        let mut aliases = Vec::new();
+        let mut dictionary = Dictionary {
+            name: dic
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned()),
+            words: HashMap::new(),
+            prefixes: Vec::new(),
+            suffixes: Vec::new(),
+            need_affix: None,
+            forbidden: None,
+        };
+        // Whether the rules of each affix flag combine with the other kind
+        let mut cross = HashMap::new();
+        for line in aff_text.lines() {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => flags = FlagKind::Long,
+                ["FLAG", "num", ..] => flags = FlagKind::Number,
+                ["AF", alias, ..] if alias.parse::<usize>().is_err() => {
+                    aliases.push(flags.parse(alias))
+                }
+                ["NEEDAFFIX", flag, ..] => {
+                    dictionary.need_affix = flags.parse(flag).first().copied()
+                }
+                ["FORBIDDENWORD", flag, ..] => {
+                    dictionary.forbidden = flags.parse(flag).first().copied()
+                }
+                [kind @ ("PFX" | "SFX"), flag, combine, count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    let flag = flags.parse(flag).first().copied().unwrap_or_default();
+                    cross.insert((*kind, flag), *combine == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let flag = flags.parse(flag).first().copied().unwrap_or_default();
+                    let suffix = *kind == "SFX";
+                    // Continuation flags of twofold affixes are not followed.
+                    let add = add.split('/').next().unwrap_or_default();
+                    let affix = Affix {
+                        flag,
+                        cross: cross.get(&(*kind, flag)).copied().unwrap_or_default(),
+                        strip: empty_affix(strip),
+                        add: empty_affix(add),
+                        condition: condition(rest.first().unwrap_or(&"."), suffix),
+                    };
+                    if suffix {
+                        dictionary.suffixes.push(affix);
+                    } else {
+                        dictionary.prefixes.push(affix);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let dic_text = decode(&fs::read(dic)?, encoding);
+        // The first line is the number of words.
+        for line in dic_text.lines().skip(1) {
+            let entry = line.split(['\t', ' ']).next().unwrap_or_default();
+            if entry.is_empty() {
+                continue;
+            }
+            let (word, word_flags) = match entry.split_once('/') {
+                Some((word, word_flags)) => {
+                    let word_flags = if aliases.is_empty() {
+                        flags.parse(word_flags)
+                    } else {
+                        word_flags
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| aliases.get(index.checked_sub(1)?))
+                            .cloned()
+                            .unwrap_or_default()
+                    };
+                    (word, word_flags)
+                }
+                None => (entry, Vec::new()),
+            };
+            dictionary
+                .words
+                .entry(word.to_string())
+                .or_default()
+                .extend(word_flags);
+        }
+        Ok(dictionary)
+    }
+
+    fn has_flag(&self, stem: &str, flag: u32) -> bool {
+        self.words
+            .get(stem)
+            .is_some_and(|flags| flags.contains(&flag) && !self.is_forbidden(flags))
+    }
+
+    fn is_forbidden(&self, flags: &[u32]) -> bool {
+        self.forbidden
+            .is_some_and(|forbidden| flags.contains(&forbidden))
+    }
+
+    /// Returns the stems a suffix rule may have formed a word from.
+    fn suffix_stems<'a>(&'a self, word: &'a str) -> impl Iterator<Item = (String, &'a Affix)> {
+        self.suffixes.iter().filter_map(move |affix| {
+            let stem = word.strip_suffix(affix.add.as_str())?;
+            let stem = format!("{}{}", stem, affix.strip);
+            (!stem.is_empty() && affix.condition.is_match(&stem)).then_some((stem, affix))
+        })
+    }
+
+    /// Whether the dictionary has a word, as it is or formed with its affixes.
+    fn contains(&self, word: &str) -> bool {
+        if let Some(flags) = self.words.get(word) {
+            let bare = self
+                .need_affix
+                .is_some_and(|need_affix| flags.contains(&need_affix));
+            if !bare && !self.is_forbidden(flags) {
+                return true;
+            }
+        }
+        if self
+            .suffix_stems(word)
+            .any(|(stem, affix)| self.has_flag(&stem, affix.flag))
+        {
+            return true;
+        }
+        self.prefixes.iter().any(|prefix| {
+            let Some(rest) = word.strip_prefix(prefix.add.as_str()) else {
+                return false;
+            };
+            let stem = format!("{}{}", prefix.strip, rest);
+            if stem.is_empty() || !prefix.condition.is_match(&stem) {
+                return false;
+            }
+            self.has_flag(&stem, prefix.flag)
+                || prefix.cross
+                    && self.suffix_stems(&stem).any(|(stem, suffix)| {
+                        suffix.cross
+                            && self.has_flag(&stem, prefix.flag)
+                            && self.has_flag(&stem, suffix.flag)
+                    })
+        })
+    }
+
+    /// Whether a word is spelled correctly, allowing a capital first letter, or all capitals, for
+    /// a word written in lowercase.
+    pub fn check(&self, word: &str) -> bool {
+        if self.contains(word) {
+            return true;
+        }
+        let lowercase = word.to_lowercase();
+        let mut chars = lowercase.chars();
+        let capitalized: String = chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default();
+        self.contains(&lowercase) || self.contains(&capitalized)
+    }
+}
+
+/// Finds the dictionaries of languages in a folder and keeps those loaded.
+pub struct Spellchecker {
+    dir: PathBuf,
+    /// Words that are correct in every language, such as product names
+    wordlist: HashSet<String>,
+    dictionaries: Mutex<HashMap<String, Option<Arc<Dictionary>>>>,
+}
+
+impl Spellchecker {
+    pub fn new(dir: PathBuf, wordlist: Option<&Path>) -> io::Result<Spellchecker> {
+        let wordlist = match wordlist {
+            Some(path) => fs::read_to_string(path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty() && !word.starts_with('#'))
+                .map(String::from)
+                .collect(),
+            None => HashSet::new(),
+        };
+        Ok(Spellchecker {
+            dir,
+            wordlist,
+            dictionaries: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the dictionary of a `Language` header value, such as `pt_BR.dic`, that of its
+    /// language, or one of its regional variants, such as `de_DE.dic` for `de`.
+    pub fn dictionary(&self, language: &str) -> Option<Arc<Dictionary>> {
+        let mut dictionaries = self.dictionaries.lock().unwrap();
+        dictionaries
+            .entry(language.to_string())
+            .or_insert_with(|| self.find(language).map(Arc::new))
+            .clone()
+    }
+
+    fn find(&self, language: &str) -> Option<Dictionary> {
+        let base = base_language(language);
+        let mut variants: Vec<_> = fs::read_dir(&self.dir)
+            .ok()?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|name| name.strip_suffix(".dic").map(String::from))
+            .filter(|name| name.starts_with(&format!("{}_", base)))
+            .collect();
+        variants.sort();
+        let name = [language.replace('-', "_"), base.to_string()]
+            .into_iter()
+            .chain(variants)
+            .find(|name| self.dir.join(format!("{}.dic", name)).is_file())?;
+        let dic = self.dir.join(format!("{}.dic", name));
+        let aff = self.dir.join(format!("{}.aff", name));
+        Dictionary::load(&dic, &aff).ok()
+    }
+
+    /// Returns the words of a string that are misspelled, with their offsets.
+    ///
+    /// Words with digits or capitals after their first letter, which are mostly names and
+    /// identifiers, and single letters are not checked.
+    pub fn misspelled<'a>(&self, dictionary: &Dictionary, value: &'a str) -> Vec<(usize, &'a str)> {
+        let word = Regex::new(r"\w+(?:['’]\w+)*").unwrap();
+        word.find_iter(value)
+            .filter(|found| {
+                let mut chars = found.as_str().chars();
+                chars.next().is_some_and(char::is_alphabetic)
+                    && found.as_str().chars().count() > 1
+                    && chars.all(|c| !c.is_uppercase() && !c.is_numeric() && c != '_')
+            })
+            .filter(|found| {
+                let text = found.as_str();
+                !self.wordlist.contains(text)
+                    && !self.wordlist.contains(&text.to_lowercase())
+                    && !dictionary.check(text)
+            })
+            .map(|found| (found.start(), found.as_str()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nNEEDAFFIX X\nFORBIDDENWORD F\n\
+                       SFX S Y 2\nSFX S y ies [^aeiou]y\nSFX S 0 s [^y]\n\
+                       PFX U Y 1\nPFX U 0 un .\n";
+    const DIC: &str = "6\nfile/S\nreply/S\ndo/U\nlock/US\ncolour/F\nbas/X\n";
+
+    /// Writes a dictionary named `en_US` to a new folder, returning the folder.
+    fn folder(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("po-parser-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("en_US.aff"), AFF).unwrap();
+        fs::write(dir.join("en_US.dic"), DIC).unwrap();
+        dir
+    }
+
+    #[test]
+    fn recognizes_words_formed_with_affixes() {
+        let dir = folder("dictionary");
+        let dictionary = Dictionary::load(&dir.join("en_US.dic"), &dir.join("en_US.aff")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        for word in [
+            "file", "files", "replies", "undo", "unlocks", "File", "FILES",
+        ] {
+            assert!(dictionary.check(word), "{}", word);
+        }
+        for word in ["replys", "dos", "unfile", "colour", "bas", "fiel"] {
+            assert!(!dictionary.check(word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn finds_the_dictionary_of_a_language_or_of_a_variant() {
+        let dir = folder("spellchecker");
+        let wordlist = dir.join("words.txt");
+        fs::write(&wordlist, "# product names\nGitLab\n").unwrap();
+        let spellchecker = Spellchecker::new(dir.clone(), Some(&wordlist)).unwrap();
+        let english = spellchecker.dictionary("en").unwrap();
+        assert_eq!(english.name, "en_US");
+        assert!(spellchecker.dictionary("en-US").is_some());
+        assert!(spellchecker.dictionary("fr").is_none());
+        assert_eq!(
+            spellchecker.misspelled(&english, "Unlock files, fiels, GitLab, iOS, x2 {a}"),
+            [(14, "fiels")]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+use crate::parser::Entry;
+use std::collections::BTreeMap;
+
+/// The translation progress of catalogs.
+#[derive(Clone, Copy, Default)]
+pub struct Stats {
+    pub files: usize,
+    pub translated: usize,
+    pub fuzzy: usize,
+    pub untranslated: usize,
+}
+
+impl Stats {
+    /// Counts a message of a catalog, the header and obsolete entries aside.
+    pub fn count(&mut self, entry: &Entry) {
+        if entry.is_fuzzy() {
+            self.fuzzy += 1;
+        } else if entry.msgstr.is_empty()
+            || entry.msgstr.iter().any(|msgstr| msgstr.value.is_empty())
+        {
+            self.untranslated += 1;
+        } else {
+            self.translated += 1;
+        }
+    }
+
+    pub fn add(&mut self, other: &Stats) {
+        self.files += other.files;
+        self.translated += other.translated;
+        self.fuzzy += other.fuzzy;
+        self.untranslated += other.untranslated;
+    }
+
+    pub fn total(&self) -> usize {
+        self.translated + self.fuzzy + self.untranslated
+    }
+
+    /// Returns the percentage of messages that are translated, fuzzy ones excluded.
+    pub fn coverage(&self) -> f64 {
+        match self.total() {
+            0 => 100.0,
+            total => self.translated as f64 * 100.0 / total as f64,
+        }
+    }
+}
+
+/// Renders the progress of each language as a table.
+pub fn table(languages: &BTreeMap<String, Stats>) -> String {
+    let width = languages
+        .keys()
+        .map(|language| language.chars().count())
+        .chain([8])
+        .max()
+        .unwrap_or(8);
+    let mut table = format!(
+        "{:<width$}  {:>5}  {:>10}  {:>5}  {:>12}  {:>8}",
+        "Language", "Files", "Translated", "Fuzzy", "Untranslated", "Coverage"
+    );
+    for (language, stats) in languages {
+        table.push_str(&format!(
+            "\n{:<width$}  {:>5}  {:>10}  {:>5}  {:>12}  {:>7.1}%",
+            language,
+            stats.files,
+            stats.translated,
+            stats.fuzzy,
+            stats.untranslated,
+            stats.coverage()
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{Msgstr, Segments};
+
+    fn entry(flags: &[&str], msgstr: &[&str]) -> Entry {
+        Entry {
+            msgid: String::from("Save"),
+            flags: flags.iter().map(|flag| flag.to_string()).collect(),
+            msgstr: msgstr
+                .iter()
+                .enumerate()
+                .map(|(index, value)| Msgstr {
+                    index,
+                    value: value.to_string(),
+                    line_index: 0,
+                    segments: Segments::default(),
+                })
+                .collect(),
+            ..Entry::default()
+        }
+    }
+
+    #[test]
+    fn counts_translated_fuzzy_and_untranslated_messages() {
+        let mut stats = Stats::default();
+        stats.count(&entry(&[], &["Enregistrer"]));
+        stats.count(&entry(&["fuzzy"], &["Enregistrer"]));
+        stats.count(&entry(&[], &[""]));
+        stats.count(&entry(&[], &["fichier", ""]));
+        assert_eq!(
+            (stats.translated, stats.fuzzy, stats.untranslated),
+            (1, 1, 2)
+        );
+        assert_eq!(stats.coverage(), 25.0);
+        assert_eq!(Stats::default().coverage(), 100.0);
+    }
+
+    #[test]
+    fn renders_a_row_per_language() {
+        let stats = Stats {
+            files: 2,
+            translated: 3,
+            fuzzy: 0,
+            untranslated: 1,
+        };
+        let table = table(&BTreeMap::from([(String::from("pt_BR"), stats)]));
+        assert_eq!(
+            table,
+            "Language  Files  Translated  Fuzzy  Untranslated  Coverage\n\
+             pt_BR         2           3      0             1     75.0%"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use regex::Regex;
+
+/// The conversions of `strftime`, as glibc and most C libraries know them.
+const CONVERSIONS: &str = "aAbBcCdDeFgGhHIjklmMnpPrRsStTuUVwWxXyYzZ+";
+
+/// The conversions that are not printf conversions too, which tell date formats apart.
+const DATE_CONVERSIONS: &str = "BCDHIjklMpPrRSTUVWyYzZ";
+
+/// A `strftime` directive, such as `%Y`, `%-d` or `%Ey`.
+pub struct Token<'a> {
+    pub text: &'a str,
+    /// The conversion, flags, width and modifiers aside, which locales may change
+    pub conversion: char,
+    pub offset: usize,
+}
+
+impl Token<'_> {
+    pub fn is_valid(&self) -> bool {
+        CONVERSIONS.contains(self.conversion)
+    }
+}
+
+/// Returns the directives of a string, `%%` aside.
+pub fn tokens(value: &str) -> Vec<Token<'_>> {
+    let token = Regex::new(r"%[-_0^#]?[0-9]*[EO]?(.)").unwrap();
+    token
+        .captures_iter(value)
+        .filter(|captures| &captures[1] != "%")
+        .map(|captures| {
+            let whole = captures.get(0).unwrap();
+            Token {
+                text: whole.as_str(),
+                conversion: captures[1].chars().next().unwrap_or_default(),
+                offset: whole.start(),
+            }
+        })
+        .collect()
+}
+
+/// Whether a string looks like a date format, having a directive that only dates use.
+pub fn is_date_format(value: &str) -> bool {
+    tokens(value)
+        .iter()
+        .any(|token| DATE_CONVERSIONS.contains(token.conversion))
+}
+
+/// Returns the directives of `actual` whose conversion `expected` has fewer of.
+pub fn difference<'a, 'b>(expected: &[Token], actual: &'b [Token<'a>]) -> Vec<&'b Token<'a>> {
+    let mut remaining: Vec<_> = expected.iter().map(|token| token.conversion).collect();
+    actual
+        .iter()
+        .filter(|token| {
+            match remaining
+                .iter()
+                .position(|other| *other == token.conversion)
+            {
+                Some(index) => {
+                    remaining.remove(index);
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_directives_with_their_flags_and_modifiers() {
+        let tokens = tokens("%-d/%m/%Ey %H:%M 100%% %q");
+        let read: Vec<_> = tokens
+            .iter()
+            .map(|token| (token.text, token.conversion, token.is_valid()))
+            .collect();
+        assert_eq!(
+            read,
+            [
+                ("%-d", 'd', true),
+                ("%m", 'm', true),
+                ("%Ey", 'y', true),
+                ("%H", 'H', true),
+                ("%M", 'M', true),
+                ("%q", 'q', false)
+            ]
+        );
+    }
+
+    #[test]
+    fn tells_date_formats_from_printf_formats() {
+        assert!(is_date_format("%d %B %Y"));
+        assert!(!is_date_format("%d files in %s"));
+        assert!(!is_date_format("100%"));
+    }
+
+    #[test]
+    fn compares_directives_by_their_conversion() {
+        let source = tokens("%m/%d/%Y");
+        let translation = tokens("%d.%-m.%y");
+        let changed: Vec<_> = difference(&source, &translation)
+            .iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(changed, ["%y"]);
+    }
+}
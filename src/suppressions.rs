@@ -0,0 +1,185 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::parser::Entry;
+use crate::rules::{self, RuleInfo};
+use std::path::Path;
+
+/// The translator comment that keeps the diagnostics of an entry from being reported.
+const DIRECTIVE: &str = "po-parser:ignore";
+
+/// A `# po-parser:ignore` comment of an entry.
+struct Suppression<'a> {
+    comment: &'a str,
+    /// The rules named by the comment, with whether they suppressed a diagnostic
+    rules: Vec<(&'static RuleInfo, bool)>,
+    /// The names of the comment that are not those of a rule
+    unknown: Vec<&'a str>,
+    /// Whether the comment names no rule, suppressing them all, and whether it suppressed a
+    /// diagnostic
+    all: Option<bool>,
+}
+
+/// Returns the `# po-parser:ignore` comments of an entry, which name the rules they suppress by
+/// code or name, separated by spaces or commas.
+fn suppressions(entry: &Entry) -> Vec<Suppression<'_>> {
+    entry
+        .translator_comments
+        .iter()
+        .filter_map(|comment| {
+            let names = comment.trim().strip_prefix(DIRECTIVE)?;
+            if !names.is_empty() && !names.starts_with([' ', '\t']) {
+                return None;
+            }
+            let names: Vec<_> = names
+                .split([' ', '\t', ','])
+                .filter(|name| !name.is_empty())
+                .collect();
+            Some(Suppression {
+                comment,
+                rules: names
+                    .iter()
+                    .filter_map(|name| Some((rules::find(name)?, false)))
+                    .collect(),
+                unknown: names
+                    .iter()
+                    .copied()
+                    .filter(|name| rules::find(name).is_none())
+                    .collect(),
+                all: names.is_empty().then_some(false),
+            })
+        })
+        .collect()
+}
+
+/// Returns the line of a translator comment of an entry, counting back from its msgid.
+fn comment_line(entry: &Entry, comment: &str) -> u32 {
+    let lines: Vec<_> = entry.raw.lines().collect();
+    let comment = lines
+        .iter()
+        .position(|line| line.starts_with('#') && line[1..].trim() == comment.trim());
+    let msgid = lines
+        .iter()
+        .position(|line| line.starts_with("msgid ") || line.starts_with("msgid\""));
+    match (comment, msgid) {
+        (Some(comment), Some(msgid)) if comment < msgid => {
+            entry.line_index.saturating_sub((msgid - comment) as u32)
+        }
+        _ => entry.line_index,
+    }
+}
+
+/// Drops the diagnostics of an entry that its `# po-parser:ignore` comments suppress, and reports
+/// those comments that suppress nothing, so that they are removed once the entry is fixed.
+///
+/// The diagnostics comparing catalogs with each other cannot be suppressed this way.
+pub fn apply(path: &Path, entry: &Entry, diagnostics: &mut Vec<Diagnostic>) {
+    let mut suppressions = suppressions(entry);
+    if suppressions.is_empty() {
+        return;
+    }
+    diagnostics.retain(|diagnostic| {
+        let rule = diagnostic.rule();
+        for suppression in &mut suppressions {
+            if let Some(used) = &mut suppression.all {
+                *used = true;
+                return false;
+            }
+            if let Some((_, used)) = suppression.rules.iter_mut().find(|(r, _)| *r == rule) {
+                *used = true;
+                return false;
+            }
+        }
+        true
+    });
+    for suppression in suppressions {
+        let mut lines = vec![format!("# {}", suppression.comment.trim())];
+        if suppression.all == Some(false) {
+            lines.push(String::from("no diagnostic of the entry is suppressed"));
+        }
+        for (rule, _) in suppression.rules.iter().filter(|(_, used)| !used) {
+            lines.push(format!(
+                "no {} {} diagnostic to suppress",
+                rule.code, rule.name
+            ));
+        }
+        lines.extend(
+            suppression
+                .unknown
+                .iter()
+                .map(|name| format!("unknown rule: {}", name)),
+        );
+        if lines.len() == 1 {
+            continue;
+        }
+        diagnostics.push(Diagnostic {
+            message: "Unused suppression",
+            severity: Severity::Warning,
+            context: None,
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            path: path.to_path_buf(),
+            line_index: comment_line(entry, suppression.comment),
+            column: 1,
+            lines,
+            rule: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::check::{check, CheckOptions};
+    use std::path::Path;
+
+    const HEADER: &str = "msgid \"\"\nmsgstr \"\"\n\"Language: fr\\n\"\n\"PO-Revision-Date: 2024-01-01 00:00+0000\\n\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\n";
+
+    /// Returns the message of each diagnostic of an entry, with the details of unused
+    /// suppressions.
+    fn diagnostics(entry: &str) -> Vec<String> {
+        let text = format!("{}{}", HEADER, entry);
+        check(
+            Path::new("fr.po"),
+            Some(text.as_bytes()),
+            &CheckOptions::default(),
+        )
+        .iter()
+        .map(|diagnostic| match diagnostic.message {
+            "Unused suppression" => {
+                format!(
+                    "{}: {}",
+                    diagnostic.line_index,
+                    diagnostic.lines[1..].join("; ")
+                )
+            }
+            message => message.to_string(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn suppresses_the_rules_named_by_code_or_name() {
+        let entry = "msgid \"Hello {user}\"\nmsgstr \"Bonjour\"\n";
+        assert_eq!(diagnostics(entry), ["Missing interpolation"]);
+        for comment in ["PO001", "missing-interpolation", ""] {
+            let entry = format!("# po-parser:ignore {}\n{}", comment, entry);
+            assert!(diagnostics(&entry).is_empty(), "{}", comment);
+        }
+        let other = format!("# po-parser:ignored\n{}", entry);
+        assert_eq!(diagnostics(&other), ["Missing interpolation"]);
+    }
+
+    #[test]
+    fn reports_suppressions_that_suppress_nothing() {
+        let entry =
+            "# po-parser:ignore PO001, PO010,bogus\nmsgid \"Hello {user}\"\nmsgstr \"Bonjour\"\n";
+        assert_eq!(
+            diagnostics(entry),
+            ["7: no PO010 punctuation diagnostic to suppress; unknown rule: bogus"]
+        );
+        let entry =
+            "# translators: greeting\n# po-parser:ignore\nmsgid \"Hello\"\nmsgstr \"Bonjour\"\n";
+        assert_eq!(
+            diagnostics(entry),
+            ["8: no diagnostic of the entry is suppressed"]
+        );
+    }
+}
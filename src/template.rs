@@ -0,0 +1,159 @@
+use crate::diagnostic::Diagnostic;
+use crate::parser::escape;
+use crate::report;
+
+/// The fields a `--message-format` template can have, in braces.
+const FIELDS: [&str; 12] = [
+    "file",
+    "line",
+    "column",
+    "rule",
+    "name",
+    "severity",
+    "message",
+    "detail",
+    "msgctxt",
+    "msgid",
+    "msgstr",
+    "references",
+];
+
+#[derive(Clone)]
+enum Part {
+    Text(String),
+    Field(&'static str),
+}
+
+/// A template diagnostics are printed with on a single line, such as
+/// `{file}:{line}: {rule}: {detail}`, to match the error format of an editor or tool.
+///
+/// Literal braces are written `{{` and `}}`.
+#[derive(Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(value: &str) -> Result<Template, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut rest = value;
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+                text.push(c);
+                rest = after;
+            } else if c == '{' {
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| String::from("unclosed { in the template"))?;
+                let name = &rest[1..end];
+                let field = FIELDS.iter().find(|field| **field == name).ok_or_else(|| {
+                    format!(
+                        "unknown field {{{}}}, expected one of {}",
+                        name,
+                        FIELDS.join(", ")
+                    )
+                })?;
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(Part::Field(field));
+                rest = &rest[end + 1..];
+            } else if c == '}' {
+                return Err(String::from(
+                    "unmatched } in the template, write }} for a brace",
+                ));
+            } else {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Template { parts })
+    }
+
+    /// Returns a diagnostic written with the template, its strings escaped as in a catalog so
+    /// that it stays on a line.
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let context = diagnostic.context.as_ref();
+        let mut line = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => line.push_str(text),
+                Part::Field(field) => line.push_str(&match *field {
+                    "file" => diagnostic.path.display().to_string(),
+                    "line" => diagnostic.line_index.to_string(),
+                    "column" => diagnostic.column.to_string(),
+                    "rule" => diagnostic.rule().code.to_string(),
+                    "name" => diagnostic.rule().name.to_string(),
+                    "severity" => diagnostic.severity.name().to_string(),
+                    "message" => diagnostic.message.to_string(),
+                    "detail" => escape(&report::details(diagnostic).join("; ")),
+                    "msgctxt" => escape(context.and_then(|c| c.msgctxt.as_deref()).unwrap_or("")),
+                    "msgid" => escape(context.map_or("", |c| &c.msgid)),
+                    "msgstr" => escape(context.and_then(|c| c.msgstr.as_deref()).unwrap_or("")),
+                    "references" => context.map_or(String::new(), |c| c.references.join(" ")),
+                    _ => unreachable!("{} is not a field", field),
+                }),
+            }
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{Context, Severity};
+    use crate::rules;
+    use std::path::PathBuf;
+
+    fn missing() -> Diagnostic {
+        Diagnostic {
+            message: "Missing interpolation",
+            severity: Severity::Error,
+            context: Some(Context {
+                msgctxt: None,
+                msgid: String::from("Hello\n{user}"),
+                msgstr: Some(String::from("Bonjour \"toi\"")),
+                references: vec![String::from("src/app.py:12"), String::from("src/cli.py:3")],
+            }),
+            missing: vec![String::from("{user}")],
+            unexpected: Vec::new(),
+            path: PathBuf::from("fr.po"),
+            line_index: 3,
+            column: 8,
+            lines: vec![
+                String::from("fr.po:3:8: msgid \"Hello {user}\""),
+                String::from("missing: {user}"),
+            ],
+            rule: rules::of("missing-interpolation"),
+        }
+    }
+
+    #[test]
+    fn renders_the_fields_of_a_diagnostic() {
+        let render = |template| Template::parse(template).unwrap().render(&missing());
+        assert_eq!(
+            render("{file}:{line}:{column}: {severity}: {rule} {name}: {detail}"),
+            "fr.po:3:8: error: PO001 missing-interpolation: missing: {user}"
+        );
+        assert_eq!(
+            render("{{{message}}} {msgctxt}|{msgid}|{msgstr}|{references}"),
+            "{Missing interpolation} |Hello\\n{user}|Bonjour \\\"toi\\\"|src/app.py:12 src/cli.py:3"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        let error = |template| Template::parse(template).err().unwrap();
+        assert_eq!(error("{file"), "unclosed { in the template");
+        assert_eq!(
+            error("{file}}"),
+            "unmatched } in the template, write }} for a brace"
+        );
+        assert!(error("{path}").starts_with("unknown field {path}, expected one of file, line"));
+    }
+}
@@ -0,0 +1,308 @@
+use crate::json::Value;
+
+/// Reads the TOML of configuration files: tables, and keys set to strings, numbers, booleans or
+/// arrays of them, which is what `po-parser.toml` needs. Inline tables, dates and multi-line
+/// strings are not supported.
+struct Reader<'a> {
+    text: &'a str,
+    index: usize,
+}
+
+impl Reader<'_> {
+    fn error<T>(&self, expected: &str) -> Result<T, String> {
+        let line = self.text[..self.index].matches('\n').count() + 1;
+        Err(format!("expected {} at line {}", expected, line))
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.index..]
+    }
+
+    /// Skips spaces and tabs, and newlines and comments too when `lines`.
+    fn skip(&mut self, lines: bool) {
+        loop {
+            let rest = self.rest();
+            let trimmed = if lines {
+                rest.trim_start()
+            } else {
+                rest.trim_start_matches([' ', '\t'])
+            };
+            let comment = if trimmed.starts_with('#') {
+                Some(trimmed.find('\n').unwrap_or(trimmed.len()))
+            } else {
+                None
+            };
+            self.index += rest.len() - trimmed.len();
+            match comment {
+                Some(length) => self.index += length,
+                None => break,
+            }
+        }
+    }
+
+    fn eat(&mut self, token: char) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.index += token.len_utf8();
+        }
+        found
+    }
+
+    /// Reads the end of a line, after a key and its value or a table header.
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.skip(false);
+        if self.rest().is_empty() || self.eat('\n') || self.rest().starts_with("\r\n") {
+            Ok(())
+        } else {
+            self.error("the end of the line")
+        }
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        self.skip(false);
+        if self.rest().starts_with(['"', '\'']) {
+            return self.string();
+        }
+        let length = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(self.rest().len());
+        if length == 0 {
+            return self.error("a key");
+        }
+        let key = self.rest()[..length].to_string();
+        self.index += length;
+        Ok(key)
+    }
+
+    /// Reads the dotted keys of a table header, such as `[language.fr]`.
+    fn keys(&mut self) -> Result<Vec<String>, String> {
+        let mut keys = vec![self.key()?];
+        loop {
+            self.skip(false);
+            if !self.eat('.') {
+                return Ok(keys);
+            }
+            keys.push(self.key()?);
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.eat('\'') {
+            let Some(end) = self
+                .rest()
+                .find(['\'', '\n'])
+                .filter(|&end| self.rest()[end..].starts_with('\''))
+            else {
+                return self.error("'");
+            };
+            let value = self.rest()[..end].to_string();
+            self.index += end + 1;
+            return Ok(value);
+        }
+        if !self.eat('"') {
+            return self.error("a string");
+        }
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.index += offset + 1;
+                    return Ok(value);
+                }
+                '\n' => break,
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('u') => {
+                        let digits: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let code = u32::from_str_radix(&digits, 16).ok();
+                        value.push(code.and_then(char::from_u32).unwrap_or('\u{FFFD}'));
+                    }
+                    _ => return self.error("a valid escape sequence"),
+                },
+                c => value.push(c),
+            }
+        }
+        self.error("'\"'")
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip(false);
+        let text = self.text;
+        let rest = &text[self.index..];
+        if rest.starts_with(['"', '\'']) {
+            Ok(Value::String(self.string()?))
+        } else if rest.starts_with("true") {
+            self.index += 4;
+            Ok(Value::Bool(true))
+        } else if rest.starts_with("false") {
+            self.index += 5;
+            Ok(Value::Bool(false))
+        } else if self.eat('[') {
+            let mut values = Vec::new();
+            loop {
+                self.skip(true);
+                if self.eat(']') {
+                    break;
+                }
+                values.push(self.value()?);
+                self.skip(true);
+                if !self.eat(',') {
+                    self.skip(true);
+                    if !self.eat(']') {
+                        return self.error("',' or ']'");
+                    }
+                    break;
+                }
+            }
+            Ok(Value::Array(values))
+        } else {
+            let length = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.' | '_')))
+                .unwrap_or(rest.len());
+            match rest[..length].replace('_', "").parse() {
+                Ok(number) if length > 0 => {
+                    self.index += length;
+                    Ok(Value::Number(number))
+                }
+                _ => self.error("a value"),
+            }
+        }
+    }
+}
+
+/// Returns the member of an object, inserting an empty table if it has none.
+fn table<'a>(
+    members: &'a mut Vec<(String, Value)>,
+    key: &str,
+) -> Result<&'a mut Vec<(String, Value)>, String> {
+    let index = match members.iter().position(|(name, _)| name == key) {
+        Some(index) => index,
+        None => {
+            members.push((key.to_string(), Value::Object(Vec::new())));
+            members.len() - 1
+        }
+    };
+    match &mut members[index].1 {
+        Value::Object(members) => Ok(members),
+        _ => Err(format!("{} is not a table", key)),
+    }
+}
+
+/// Parses a TOML document as an object whose tables are nested objects.
+pub fn parse(text: &str) -> Result<Value, String> {
+    let mut reader = Reader { text, index: 0 };
+    let mut document = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    loop {
+        reader.skip(true);
+        if reader.rest().is_empty() {
+            break;
+        }
+        if reader.eat('[') {
+            path = reader.keys()?;
+            reader.skip(false);
+            if !reader.eat(']') {
+                return reader.error("']'");
+            }
+            reader.end_of_line()?;
+            let mut members = &mut document;
+            for key in &path {
+                members = table(members, key)?;
+            }
+            continue;
+        }
+        let key = reader.key()?;
+        reader.skip(false);
+        if !reader.eat('=') {
+            return reader.error("'='");
+        }
+        let value = reader.value()?;
+        reader.end_of_line()?;
+        let mut members = &mut document;
+        for name in &path {
+            members = table(members, name)?;
+        }
+        if members.iter().any(|(name, _)| *name == key) {
+            return Err(format!("{} is set twice", key));
+        }
+        members.push((key, value));
+    }
+    Ok(Value::Object(document))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(members: &[(&str, Value)]) -> Value {
+        Value::Object(
+            members
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parses_tables_and_values() {
+        let text = "# Settings\n\
+                    pattern = \"\\\\{\\\\w+\\\\}\\u00e9\" # the default\n\
+                    exclude = [\n  'vendor/**',\n  \"*.pot\",\n]\n\
+                    \n\
+                    [language.fr]\n\
+                    width = 1_000\n\
+                    strict = true\r\n\
+                    [\"language\".de]\n\
+                    ratio = -0.5\n";
+        let fr = object(&[
+            ("width", Value::Number(1000.0)),
+            ("strict", Value::Bool(true)),
+        ]);
+        let de = object(&[("ratio", Value::Number(-0.5))]);
+        let expected = object(&[
+            ("pattern", Value::from("\\{\\w+\\}é")),
+            (
+                "exclude",
+                Value::Array(vec![Value::from("vendor/**"), Value::from("*.pot")]),
+            ),
+            ("language", object(&[("fr", fr), ("de", de)])),
+        ]);
+        assert_eq!(parse(text), Ok(expected));
+    }
+
+    #[test]
+    fn reports_the_line_of_errors() {
+        assert_eq!(
+            parse("a = 1\nb =\n").unwrap_err(),
+            "expected a value at line 2"
+        );
+        assert_eq!(parse("a = 'open\n").unwrap_err(), "expected ' at line 1");
+        assert_eq!(
+            parse("a = [1 2]\n").unwrap_err(),
+            "expected ',' or ']' at line 1"
+        );
+        assert_eq!(
+            parse("a = 1 b = 2\n").unwrap_err(),
+            "expected the end of the line at line 1"
+        );
+        assert_eq!(
+            parse("a = \"\\q\"\n").unwrap_err(),
+            "expected a valid escape sequence at line 1"
+        );
+    }
+
+    #[test]
+    fn rejects_keys_set_twice() {
+        assert_eq!(parse("a = 1\na = 2\n").unwrap_err(), "a is set twice");
+        assert_eq!(
+            parse("a = 1\n[a]\nb = 2\n").unwrap_err(),
+            "a is not a table"
+        );
+    }
+}
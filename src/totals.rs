@@ -0,0 +1,98 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::rules;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How many of the slowest catalogs `--stats` lists.
+const SLOWEST: usize = 5;
+
+/// How long checking a catalog took.
+pub struct Timing {
+    pub path: PathBuf,
+    pub entries: usize,
+    pub elapsed: Duration,
+}
+
+/// What a run checked and reported, summed up once it is over.
+#[derive(Default)]
+pub struct Totals {
+    files: Vec<Timing>,
+    /// The errors and warnings reported by each rule, by code
+    rules: BTreeMap<&'static str, (usize, usize)>,
+}
+
+/// Returns a count followed by a noun, in the plural unless the count is one.
+pub fn counted(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("1 {}", singular)
+    } else {
+        format!("{} {}", count, plural)
+    }
+}
+
+impl Totals {
+    pub fn file(&mut self, timing: Timing) {
+        self.files.push(timing);
+    }
+
+    /// Counts a diagnostic that is reported, once the rules settings and the baseline are
+    /// applied.
+    pub fn diagnostic(&mut self, diagnostic: &Diagnostic) {
+        let counts = self.rules.entry(diagnostic.rule().code).or_default();
+        match diagnostic.severity {
+            Severity::Error => counts.0 += 1,
+            Severity::Warning => counts.1 += 1,
+        }
+    }
+
+    /// Returns the summary of a run in a line, such as `Checked 3 files, 120 entries in 0.04s:
+    /// 2 errors, 1 warning`.
+    pub fn footer(&self, elapsed: Duration) -> String {
+        let entries = self.files.iter().map(|file| file.entries).sum();
+        let errors = self.rules.values().map(|counts| counts.0).sum();
+        let warnings = self.rules.values().map(|counts| counts.1).sum();
+        format!(
+            "Checked {}, {} in {:.2}s: {}, {}",
+            counted(self.files.len(), "file", "files"),
+            counted(entries, "entry", "entries"),
+            elapsed.as_secs_f64(),
+            counted(errors, "error", "errors"),
+            counted(warnings, "warning", "warnings")
+        )
+    }
+
+    /// Returns the summary of a run followed by the errors and warnings of each rule, and the
+    /// catalogs that took the longest to check, as `--stats` prints them.
+    pub fn details(&self, elapsed: Duration) -> String {
+        let mut text = self.footer(elapsed);
+        if !self.rules.is_empty() {
+            let _ = write!(
+                text,
+                "\n\n{:<32}  {:>6}  {:>8}",
+                "Rule", "Errors", "Warnings"
+            );
+            for (code, (errors, warnings)) in &self.rules {
+                let name = rules::find(code).map_or("", |rule| rule.name);
+                let rule = format!("{} {}", code, name);
+                let _ = write!(text, "\n{:<32}  {:>6}  {:>8}", rule, errors, warnings);
+            }
+        }
+        let mut files: Vec<&Timing> = self.files.iter().collect();
+        files.sort_by(|a, b| b.elapsed.cmp(&a.elapsed).then_with(|| a.path.cmp(&b.path)));
+        if !files.is_empty() {
+            text.push_str("\n\nSlowest files:");
+        }
+        for file in files.into_iter().take(SLOWEST) {
+            let _ = write!(
+                text,
+                "\n{:>9.2}ms  {}  ({})",
+                file.elapsed.as_secs_f64() * 1000.0,
+                file.path.display(),
+                counted(file.entries, "entry", "entries")
+            );
+        }
+        text
+    }
+}
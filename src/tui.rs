@@ -0,0 +1,260 @@
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::editor;
+use console::{Key, Style, Term};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+/// The keys of the browser, shown at the top of the screen.
+const KEYS: &str =
+    "↑/↓ move, n/p next or previous file, Enter details, a acknowledge, o open, q quit";
+
+/// A line of the list: the heading of a file, or a diagnostic by its index.
+enum Line {
+    File(String),
+    Diagnostic(usize),
+}
+
+/// Hides the cursor of a terminal while it lives, showing it again when it is dropped, so that
+/// the cursor comes back when the browser fails or is interrupted as well as when it is quit.
+struct HiddenCursor<'a>(&'a Term);
+
+impl HiddenCursor<'_> {
+    fn new(term: &Term) -> io::Result<HiddenCursor<'_>> {
+        term.hide_cursor()?;
+        Ok(HiddenCursor(term))
+    }
+}
+
+impl Drop for HiddenCursor<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.show_cursor();
+    }
+}
+
+/// The state of the browser.
+struct Browser<'a> {
+    diagnostics: &'a [Diagnostic],
+    lines: Vec<Line>,
+    /// The index in `lines` of the selected diagnostic
+    selected: usize,
+    /// The index in `lines` of the first line on the screen
+    top: usize,
+    acknowledged: Vec<bool>,
+}
+
+impl Browser<'_> {
+    /// Selects the closest diagnostic in a direction, from a line.
+    fn select(&mut self, from: usize, forward: bool) {
+        let mut index = from;
+        loop {
+            if let Line::Diagnostic(_) = self.lines[index] {
+                self.selected = index;
+                return;
+            }
+            if forward && index + 1 < self.lines.len() {
+                index += 1;
+            } else if !forward && index > 0 {
+                index -= 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Selects the first diagnostic of the next or previous file.
+    fn jump(&mut self, forward: bool) {
+        let headings: Vec<usize> = (0..self.lines.len())
+            .filter(|&index| matches!(self.lines[index], Line::File(_)))
+            .collect();
+        let current = headings
+            .iter()
+            .rposition(|&heading| heading < self.selected)
+            .unwrap_or(0);
+        let target = if forward {
+            headings.get(current + 1)
+        } else {
+            current.checked_sub(1).and_then(|index| headings.get(index))
+        };
+        if let Some(&heading) = target {
+            self.select(heading, true);
+        }
+    }
+
+    fn line(&self, line: &Line) -> String {
+        match line {
+            Line::File(heading) => Style::new().bold().apply_to(heading).to_string(),
+            Line::Diagnostic(index) => {
+                let diagnostic = &self.diagnostics[*index];
+                let rule = diagnostic.rule();
+                let severity = match diagnostic.severity {
+                    Severity::Error => Style::new().red(),
+                    Severity::Warning => Style::new().yellow(),
+                };
+                let mark = if self.acknowledged[*index] {
+                    "✓"
+                } else {
+                    " "
+                };
+                format!(
+                    "  {} {:>5}:{:<3} {} {}",
+                    mark,
+                    diagnostic.line_index,
+                    diagnostic.column,
+                    severity.apply_to(rule.code),
+                    diagnostic.message
+                )
+            }
+        }
+    }
+
+    fn draw(&mut self, term: &Term) -> io::Result<()> {
+        let (rows, columns) = term.size();
+        let height = usize::from(rows).saturating_sub(2).max(1);
+        // The line above the selected one stays on the screen, so that the heading of the first
+        // file is too.
+        if self.selected <= self.top {
+            self.top = self.selected.saturating_sub(1);
+        } else if self.selected >= self.top + height {
+            self.top = self.selected + 1 - height;
+        }
+        let count = self
+            .acknowledged
+            .iter()
+            .filter(|&&acknowledged| acknowledged)
+            .count();
+        let mut screen = vec![
+            format!(
+                "{} diagnostics, {} acknowledged: {}",
+                self.diagnostics.len(),
+                count,
+                KEYS
+            ),
+            String::new(),
+        ];
+        for (index, line) in self.lines.iter().enumerate().skip(self.top).take(height) {
+            let text = self.line(line);
+            if index == self.selected {
+                screen.push(Style::new().reverse().apply_to(text).to_string());
+            } else {
+                screen.push(text);
+            }
+        }
+        term.clear_screen()?;
+        for line in screen {
+            term.write_line(&console::truncate_str(&line, usize::from(columns), "…"))?;
+        }
+        Ok(())
+    }
+
+    /// Shows the whole of the selected diagnostic, with its entry, until a key is pressed.
+    fn details(&self, term: &Term) -> io::Result<()> {
+        let Line::Diagnostic(index) = self.lines[self.selected] else {
+            return Ok(());
+        };
+        term.clear_screen()?;
+        term.write_line(&self.diagnostics[index].text(console::colors_enabled()))?;
+        term.write_line("")?;
+        term.write_line("Press a key to go back to the list")?;
+        term.read_key()?;
+        Ok(())
+    }
+}
+
+/// Lists diagnostics by file, with the language of each one, on the terminal, for them to be
+/// looked at one by one and acknowledged.
+///
+/// The selected diagnostic is opened in the editor of `editor`, as --open does. Returns whether
+/// each diagnostic was acknowledged once the browser is quit.
+pub fn browse(
+    diagnostics: &[Diagnostic],
+    languages: &HashMap<PathBuf, String>,
+    editor: Option<&str>,
+) -> io::Result<Vec<bool>> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--tui needs a terminal",
+        ));
+    }
+    let mut order: Vec<usize> = (0..diagnostics.len()).collect();
+    order.sort_by_key(|&index| {
+        let diagnostic = &diagnostics[index];
+        (&diagnostic.path, diagnostic.line_index, diagnostic.column)
+    });
+    let mut lines = Vec::new();
+    for index in order {
+        let path = &diagnostics[index].path;
+        let new_file = match lines.last() {
+            Some(Line::Diagnostic(last)) => diagnostics[*last].path != *path,
+            _ => true,
+        };
+        if new_file {
+            let heading = match languages.get(path) {
+                Some(language) => format!("{} ({})", path.display(), language),
+                None => path.display().to_string(),
+            };
+            lines.push(Line::File(heading));
+        }
+        lines.push(Line::Diagnostic(index));
+    }
+    let mut browser = Browser {
+        diagnostics,
+        lines,
+        selected: 0,
+        top: 0,
+        acknowledged: vec![false; diagnostics.len()],
+    };
+    if diagnostics.is_empty() {
+        return Ok(browser.acknowledged);
+    }
+    browser.select(0, true);
+    let _cursor = HiddenCursor::new(&term)?;
+    loop {
+        browser.draw(&term)?;
+        let (rows, _) = term.size();
+        let page = usize::from(rows).saturating_sub(3).max(1);
+        match term.read_key()? {
+            Key::ArrowDown | Key::Char('j') if browser.selected + 1 < browser.lines.len() => {
+                browser.select(browser.selected + 1, true)
+            }
+            Key::ArrowUp | Key::Char('k') if browser.selected > 0 => {
+                browser.select(browser.selected - 1, false)
+            }
+            Key::PageDown => {
+                let target = (browser.selected + page).min(browser.lines.len() - 1);
+                browser.select(target, true);
+            }
+            Key::PageUp => browser.select(browser.selected.saturating_sub(page), false),
+            Key::Home => browser.select(0, true),
+            Key::End => browser.select(browser.lines.len() - 1, false),
+            Key::Char('n') => browser.jump(true),
+            Key::Char('p') => browser.jump(false),
+            Key::Enter => browser.details(&term)?,
+            Key::Char('a') | Key::Char(' ') => {
+                if let Line::Diagnostic(index) = browser.lines[browser.selected] {
+                    browser.acknowledged[index] = !browser.acknowledged[index];
+                }
+            }
+            Key::Char('o') => {
+                if let Line::Diagnostic(index) = browser.lines[browser.selected] {
+                    let diagnostic = &diagnostics[index];
+                    term.clear_screen()?;
+                    term.show_cursor()?;
+                    editor::open(
+                        editor,
+                        &diagnostic.path,
+                        diagnostic.line_index,
+                        diagnostic.column,
+                    )?;
+                    term.hide_cursor()?;
+                }
+            }
+            Key::Char('q') | Key::Escape => break,
+            _ => {}
+        }
+    }
+    term.clear_screen()?;
+    Ok(browser.acknowledged)
+}
@@ -0,0 +1,197 @@
+use clap::ValueEnum;
+use std::ops::Range;
+
+/// The typography rules of French, each of which can be disabled.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Rule {
+    /// A no-break space before `?`, `!`, `;` and `:`, and inside guillemets
+    Spacing,
+    /// Guillemets `« »` instead of straight double quotes
+    Guillemets,
+    /// A single apostrophe style in each translation, `'` or `’`
+    Apostrophes,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 3] = [Rule::Spacing, Rule::Guillemets, Rule::Apostrophes];
+}
+
+/// A departure of a translation from a typography rule.
+pub struct Issue {
+    pub offset: usize,
+    pub description: String,
+}
+
+fn is_no_break_space(c: char) -> bool {
+    matches!(c, '\u{a0}' | '\u{202f}')
+}
+
+/// Returns the departures of a French string from a rule.
+///
+/// Canadian French only puts a space before the colon. The punctuation of times, URLs and
+/// placeholders, whose ranges are given, is not checked.
+pub fn check(rule: Rule, value: &str, language: &str, placeholders: &[Range<usize>]) -> Vec<Issue> {
+    let issues = match rule {
+        Rule::Spacing => spacing(value, language),
+        Rule::Guillemets => guillemets(value),
+        Rule::Apostrophes => apostrophes(value),
+    };
+    issues
+        .into_iter()
+        .filter(|issue| {
+            !placeholders
+                .iter()
+                .any(|range| range.contains(&issue.offset))
+        })
+        .collect()
+}
+
+fn spacing(value: &str, language: &str) -> Vec<Issue> {
+    let canadian = language.replace('-', "_").eq_ignore_ascii_case("fr_CA");
+    let chars: Vec<_> = value.char_indices().collect();
+    let mut issues = Vec::new();
+    for (index, &(offset, c)) in chars.iter().enumerate() {
+        let before = index.checked_sub(1).map(|index| chars[index].1);
+        let after = chars.get(index + 1).map(|&(_, c)| c);
+        let spaced = match c {
+            ':' => true,
+            '?' | '!' | ';' => !canadian,
+            '»' => {
+                if before.is_some_and(|before| !is_no_break_space(before)) {
+                    issues.push(Issue {
+                        offset,
+                        description: String::from("'»' must follow a no-break space"),
+                    });
+                }
+                continue;
+            }
+            '«' => {
+                if after.is_some_and(|after| !is_no_break_space(after)) {
+                    issues.push(Issue {
+                        offset,
+                        description: String::from("'«' must be followed by a no-break space"),
+                    });
+                }
+                continue;
+            }
+            _ => false,
+        };
+        let Some(before) = before else {
+            continue;
+        };
+        // Times such as 10:30, URLs such as https://, and marks following one another.
+        let exempt = (c == ':'
+            && (before.is_ascii_digit() && after.is_some_and(|after| after.is_ascii_digit())
+                || after == Some('/')))
+            || matches!(before, '?' | '!' | ';' | ':' | '.' | '\n')
+            || is_no_break_space(before);
+        if !spaced || exempt {
+            continue;
+        }
+        let description = match before {
+            ' ' => format!("the space before '{}' must be a no-break space", c),
+            _ => format!("'{}' must follow a no-break space", c),
+        };
+        issues.push(Issue {
+            offset,
+            description,
+        });
+    }
+    issues
+}
+
+fn guillemets(value: &str) -> Vec<Issue> {
+    value
+        .match_indices('"')
+        .map(|(offset, _)| Issue {
+            offset,
+            description: String::from("'\"' must be written as guillemets, '«' and '»'"),
+        })
+        .collect()
+}
+
+fn apostrophes(value: &str) -> Vec<Issue> {
+    let chars: Vec<_> = value.char_indices().collect();
+    // Apostrophes are between letters, unlike single quotes.
+    let apostrophes: Vec<_> = chars
+        .windows(3)
+        .filter(|window| {
+            matches!(window[1].1, '\'' | '’')
+                && window[0].1.is_alphabetic()
+                && window[2].1.is_alphabetic()
+        })
+        .map(|window| window[1])
+        .collect();
+    let Some(&(_, first)) = apostrophes.first() else {
+        return Vec::new();
+    };
+    apostrophes
+        .iter()
+        .filter(|(_, c)| *c != first)
+        .map(|&(offset, c)| Issue {
+            offset,
+            description: format!(
+                "'{}' is not the apostrophe '{}' used before in the translation",
+                c, first
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptions(rule: Rule, value: &str, language: &str) -> Vec<String> {
+        check(rule, value, language, &[])
+            .into_iter()
+            .map(|issue| issue.description)
+            .collect()
+    }
+
+    #[test]
+    fn wants_no_break_spaces_before_high_punctuation() {
+        assert!(descriptions(Rule::Spacing, "Prêt\u{202f}? Oui\u{a0}!", "fr").is_empty());
+        assert_eq!(
+            descriptions(Rule::Spacing, "Prêt ? Oui!", "fr"),
+            [
+                "the space before '?' must be a no-break space",
+                "'!' must follow a no-break space"
+            ]
+        );
+        assert!(descriptions(Rule::Spacing, "Prêt? À 10:30, https://a.fr?!", "fr_CA").is_empty());
+        assert_eq!(
+            descriptions(Rule::Spacing, "Note: «oui»", "fr-CA"),
+            [
+                "':' must follow a no-break space",
+                "'«' must be followed by a no-break space",
+                "'»' must follow a no-break space"
+            ]
+        );
+    }
+
+    #[test]
+    fn wants_guillemets_and_a_single_apostrophe() {
+        assert_eq!(
+            descriptions(Rule::Guillemets, "Le \"fichier\"", "fr").len(),
+            2
+        );
+        assert!(descriptions(Rule::Guillemets, "Le «\u{a0}fichier\u{a0}»", "fr").is_empty());
+        assert!(descriptions(Rule::Apostrophes, "l’écran d’accueil, 'cité'", "fr").is_empty());
+        assert_eq!(
+            descriptions(Rule::Apostrophes, "l’écran d'accueil", "fr"),
+            ["''' is not the apostrophe '’' used before in the translation"]
+        );
+    }
+
+    #[test]
+    fn leaves_placeholders_out() {
+        let value = "{a:b} et {c!}";
+        let placeholders = [0..5, 9..13];
+        assert!(check(Rule::Spacing, value, "fr", &placeholders).is_empty());
+        assert_eq!(
+            check(Rule::Spacing, value, "fr", &placeholders[..1]).len(),
+            1
+        );
+    }
+}
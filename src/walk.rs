@@ -0,0 +1,259 @@
+use crate::gitignore::Ignore;
+use crate::glob::{self, Pattern};
+use clap::ValueEnum;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file listing the patterns of the paths that are not checked, in a folder that is walked.
+pub const IGNORE_FILE: &str = ".poparserignore";
+
+/// How the catalogs of the folders symbolic links point to are checked, when they are followed.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Symlinks {
+    /// By every path they are found at, such as the folder of each brand sharing them
+    Each,
+    /// Once, by the first of their paths
+    Once,
+}
+
+/// Which files of the folders that are walked are skipped.
+#[derive(Default)]
+pub struct Options {
+    /// The patterns of `--exclude`, matched against the paths relative to the folder walked
+    pub excluded: Vec<Pattern>,
+    /// Whether the files git ignores are skipped
+    pub gitignore: bool,
+    /// Whether symbolic links to folders are followed, and how
+    pub symlinks: Option<Symlinks>,
+}
+
+/// Returns the path a path resolves to, with its symbolic links followed.
+fn canonical(path: &Path) -> io::Result<PathBuf> {
+    fs::canonicalize(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+/// Returns the patterns of an ignore file, one per line, blank lines and `#` comments aside.
+pub fn ignore_file(path: &Path) -> io::Result<Vec<Pattern>> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            Pattern::new(line).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), e),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Returns the files of a folder, then those of its subfolders when `recursive`, sorted by path
+/// so that catalogs are checked in the same order whatever the file system.
+///
+/// Hidden folders, such as `.git`, are skipped, and symbolic links to folders are only followed
+/// with `options.symlinks`, a link to a folder being walked, such as a parent, being skipped so
+/// that it does not loop. Files and folders an excluded pattern matches, or one of the ignore
+/// file of the folder, or that git ignores, are skipped too; patterns match their path relative
+/// to the folder, so that `vendor/**` does not depend on where it is walked from.
+pub fn files(dir: &Path, recursive: bool, options: &Options) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let root = dir;
+    let mut excluded = options.excluded.clone();
+    let path = dir.join(IGNORE_FILE);
+    if path.is_file() {
+        // A malformed ignore file is a wrong setting, as a malformed `--exclude` is.
+        excluded.extend(
+            ignore_file(&path)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?,
+        );
+    }
+    let ignore = if options.gitignore {
+        Some(Ignore::new(dir)?)
+    } else {
+        None
+    };
+    // The folders each folder is in, resolved, to tell the links that loop.
+    let ancestors = match options.symlinks {
+        Some(_) => vec![canonical(dir)?],
+        None => Vec::new(),
+    };
+    let mut dirs = vec![(dir.to_path_buf(), ignore, ancestors)];
+    while let Some((dir, ignore, ancestors)) = dirs.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", dir.display(), e)))?;
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            // A broken link is left to be reported as a file, if it is named as a catalog.
+            let is_dir = if file_type.is_symlink() && options.symlinks.is_some() {
+                fs::metadata(&path).is_ok_and(|metadata| metadata.is_dir())
+            } else {
+                file_type.is_dir()
+            };
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            if excluded.iter().any(|pattern| pattern.excludes(relative))
+                || ignore
+                    .as_ref()
+                    .is_some_and(|ignore| ignore.ignores(&path, is_dir))
+            {
+                continue;
+            }
+            if is_dir {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if recursive && !hidden {
+                    let mut ancestors = ancestors.clone();
+                    if options.symlinks.is_some() {
+                        let target = canonical(&path)?;
+                        if ancestors.contains(&target) {
+                            continue;
+                        }
+                        ancestors.push(target);
+                    }
+                    let ignore = match &ignore {
+                        Some(ignore) => Some(ignore.enter(&path)?),
+                        None => None,
+                    };
+                    dirs.push((path, ignore, ancestors));
+                }
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the files of the paths given on the command line, which are files, folders whose files
+/// are checked, or patterns such as `locales/**/django.po`, sorted and each one once.
+///
+/// The files that are given are checked even if they are excluded or ignored. With
+/// `Symlinks::Once`, a file found at several paths is only returned at the first one.
+pub fn arguments(
+    paths: &[PathBuf],
+    recursive: bool,
+    options: &Options,
+) -> io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    for path in paths {
+        let value = path.to_string_lossy();
+        if glob::is_pattern(&value) && !path.exists() {
+            let pattern =
+                Pattern::new(&value).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let (base, nested) = pattern.base();
+            let matched: Vec<PathBuf> = files(&base, nested, options)?
+                .into_iter()
+                .filter(|file| pattern.matches(file))
+                // Patterns of the current folder match the files the way they are written.
+                .map(|file| match file.strip_prefix(".") {
+                    Ok(relative) => relative.to_path_buf(),
+                    Err(_) => file,
+                })
+                .collect();
+            if matched.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{}: no file matches the pattern", value),
+                ));
+            }
+            found.extend(matched);
+        } else if fs::metadata(path)
+            .map_err(|e| {
+                // A path that does not exist is a wrong argument rather than a failure to read.
+                let kind = match e.kind() {
+                    io::ErrorKind::NotFound => io::ErrorKind::InvalidInput,
+                    kind => kind,
+                };
+                io::Error::new(kind, format!("{}: {}", path.display(), e))
+            })?
+            .is_dir()
+        {
+            found.extend(files(path, recursive, options)?);
+        } else {
+            found.push(path.clone());
+        }
+    }
+    found.sort();
+    found.dedup();
+    if options.symlinks == Some(Symlinks::Once) {
+        let mut seen = HashSet::new();
+        found.retain(|file| seen.insert(fs::canonicalize(file).unwrap_or_else(|_| file.clone())));
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a folder of empty catalogs in the temporary folder, one named `vendor` among those
+    /// it is in so that patterns have to be matched relative to it.
+    fn tree(name: &str, files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("po-parser-walk-{}-{}", name, std::process::id()))
+            .join("vendor")
+            .join("root");
+        let _ = fs::remove_dir_all(&dir);
+        for file in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        dir
+    }
+
+    fn relative(dir: &Path, files: Vec<PathBuf>) -> Vec<String> {
+        files
+            .iter()
+            .map(|file| glob::normalized(file.strip_prefix(dir).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn excludes_paths_relative_to_the_folder_walked() {
+        let dir = tree("exclude", &["vendor/x.po", "fr/y.po", "de.po"]);
+        let options = Options {
+            excluded: vec![Pattern::new("vendor/**").unwrap()],
+            ..Options::default()
+        };
+        let found = files(&dir, true, &options).unwrap();
+        assert_eq!(relative(&dir, found), ["de.po", "fr/y.po"]);
+        // A pattern without a `/` does not match the folders the walked one is in.
+        let options = Options {
+            excluded: vec![Pattern::new("vendor").unwrap()],
+            ..Options::default()
+        };
+        let found = files(&dir, true, &options).unwrap();
+        assert_eq!(relative(&dir, found), ["de.po", "fr/y.po"]);
+        fs::remove_dir_all(dir.parent().unwrap().parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn reads_the_ignore_file_of_the_folder_walked() {
+        let dir = tree("ignore", &["vendor/x.po", "fr/y.pot", "de.po"]);
+        fs::write(dir.join(IGNORE_FILE), "# Not ours\nvendor/**\n\n*.pot\n").unwrap();
+        let found = files(&dir, true, &Options::default()).unwrap();
+        assert_eq!(relative(&dir, found), [IGNORE_FILE, "de.po"]);
+        fs::write(dir.join(IGNORE_FILE), "[abc\n").unwrap();
+        let e = files(&dir, true, &Options::default()).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+        fs::remove_dir_all(dir.parent().unwrap().parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn only_walks_subfolders_when_recursive() {
+        let dir = tree("recursive", &["fr/y.po", "de.po", ".hidden/z.po"]);
+        let found = files(&dir, false, &Options::default()).unwrap();
+        assert_eq!(relative(&dir, found), ["de.po"]);
+        let found = files(&dir, true, &Options::default()).unwrap();
+        assert_eq!(relative(&dir, found), ["de.po", "fr/y.po"]);
+        fs::remove_dir_all(dir.parent().unwrap().parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How often the catalogs are looked at for changes.
+pub const INTERVAL: Duration = Duration::from_millis(500);
+
+/// The modification times of the catalogs that are watched.
+///
+/// Polling them keeps watching the same on every platform, which a few hundred catalogs are cheap
+/// enough for.
+pub struct Snapshot {
+    times: HashMap<PathBuf, SystemTime>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl Snapshot {
+    pub fn new(paths: &[PathBuf]) -> Snapshot {
+        let times = paths
+            .iter()
+            .filter_map(|path| Some((path.clone(), modified(path)?)))
+            .collect();
+        Snapshot { times }
+    }
+
+    /// Returns the catalogs that are new or were modified since the last time, forgetting those
+    /// that are gone.
+    pub fn changed(&mut self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        let mut times = HashMap::new();
+        for path in paths {
+            let Some(time) = modified(path) else {
+                continue;
+            };
+            if self.times.get(path) != Some(&time) {
+                changed.push(path.clone());
+            }
+            times.insert(path.clone(), time);
+        }
+        self.times = times;
+        changed
+    }
+}
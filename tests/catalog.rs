@@ -0,0 +1,50 @@
+use po_parser::Catalog;
+
+/// A catalog with a byte order mark and CRLF line endings, previous and obsolete entries.
+const FIXTURE: &[u8] = include_bytes!("fixtures/roundtrip.po");
+
+fn written(catalog: &Catalog) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    catalog.write(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn unchanged_catalogs_are_written_byte_for_byte() {
+    let catalog = Catalog::parse(FIXTURE).unwrap();
+    assert!(catalog.bom);
+    assert_eq!(catalog.line_ending, "\r\n");
+    assert!(catalog.entries.iter().any(|entry| entry.obsolete));
+    assert!(catalog
+        .entries
+        .iter()
+        .any(|entry| entry.previous_msgid.is_some()));
+    assert_eq!(written(&catalog), FIXTURE);
+}
+
+#[test]
+fn changed_entries_keep_the_format_of_the_catalog() {
+    let mut catalog = Catalog::parse(FIXTURE).unwrap();
+    let long = "Une salutation qui a été supprimée du programme, mais dont la traduction est \
+                gardée au cas où elle reviendrait";
+    for entry in &mut catalog.entries {
+        if entry.obsolete || entry.msgid == "Hello {user}" {
+            entry.msgstr[0].value = long.to_string();
+        }
+    }
+    let bytes = written(&catalog);
+    let text = std::str::from_utf8(&bytes).unwrap();
+    let text = text.strip_prefix('\u{feff}').unwrap();
+    assert!(!text.replace("\r\n", "").contains('\n'));
+    for line in text.split("\r\n") {
+        assert!(line.chars().count() <= catalog.width, "{}", line);
+    }
+    assert!(text.contains("\r\n#| msgid \"Hello {name}\"\r\nmsgid \"Hello {user}\"\r\n"));
+    assert!(text.contains("\r\n#~| msgid \"Old greeting\"\r\n#~ msgid \"Removed greeting\"\r\n"));
+    assert!(text.contains("\r\nmsgid   \"Spaced\"\r\n"));
+    // The rewritten catalog reads back as it was changed.
+    let reread = Catalog::parse(&bytes[..]).unwrap();
+    let obsolete = reread.entries.iter().find(|entry| entry.obsolete).unwrap();
+    assert_eq!(obsolete.msgstr[0].value, long);
+    assert_eq!(written(&reread), bytes);
+}
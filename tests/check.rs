@@ -0,0 +1,57 @@
+use po_parser::check::combined;
+use po_parser::{check, Catalog, CheckOptions, Severity};
+use std::path::Path;
+
+const CATALOG: &str = r#"msgid ""
+msgstr ""
+"Language: fr\n"
+"PO-Revision-Date: 2024-01-01 00:00+0000\n"
+"Content-Type: text/plain; charset=UTF-8\n"
+
+msgid "Hello {user}"
+msgstr "Bonjour"
+
+msgid "Save"
+msgstr "Enregistrer"
+"#;
+
+#[test]
+fn check_returns_the_diagnostics_of_a_catalog() {
+    let diagnostics = check(
+        Path::new("fr.po"),
+        Some(CATALOG.as_bytes()),
+        &CheckOptions::default(),
+    );
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.message, "Missing interpolation");
+    assert_eq!(diagnostic.rule().code, "PO001");
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.line_index, 8);
+    assert_eq!(diagnostic.missing, ["{user}"]);
+}
+
+#[test]
+fn check_uses_the_pattern_of_the_options() {
+    let options = CheckOptions {
+        pattern: Some(combined(&["%[a-z]+"]).unwrap()),
+        ..CheckOptions::default()
+    };
+    assert!(check(Path::new("fr.po"), Some(CATALOG.as_bytes()), &options).is_empty());
+}
+
+#[test]
+fn an_invalid_pattern_is_an_error() {
+    assert!(combined(&["{unclosed("]).is_err());
+}
+
+#[test]
+fn catalogs_are_parsed_entry_by_entry() {
+    let catalog = Catalog::parse(CATALOG.as_bytes()).unwrap();
+    let msgids: Vec<_> = catalog
+        .entries
+        .iter()
+        .map(|entry| entry.msgid.as_str())
+        .collect();
+    assert_eq!(msgids, ["", "Hello {user}", "Save"]);
+}
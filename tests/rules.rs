@@ -0,0 +1,122 @@
+use clap::ValueEnum;
+use po_parser::report::{self, OutputFormat, Run};
+use po_parser::rules::{RuleInfo, Settings};
+use po_parser::{check, CheckContext, CheckOptions, Diagnostic, Entry, Rule, Severity};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const CATALOG: &str = r#"msgid ""
+msgstr ""
+"Language: fr\n"
+"PO-Revision-Date: 2024-01-01 00:00+0000\n"
+"Content-Type: text/plain; charset=UTF-8\n"
+
+msgid "Save"
+msgstr "TODO"
+"#;
+
+static TODO: RuleInfo = RuleInfo {
+    code: "ACME001",
+    name: "todo",
+    messages: &["TODO in translation"],
+    description: "A translation is still marked TODO.",
+    flag: None,
+    needs_value: false,
+    severity: Severity::Warning,
+};
+
+/// A rule of a project, reporting the translations left to do.
+struct Todo;
+
+impl Rule for Todo {
+    fn info(&self) -> &'static RuleInfo {
+        &TODO
+    }
+
+    fn check(&self, entry: &Entry, ctx: &CheckContext) -> Vec<Diagnostic> {
+        entry
+            .msgstr
+            .iter()
+            .filter(|msgstr| msgstr.value.contains("TODO"))
+            .map(|msgstr| Diagnostic {
+                message: "TODO in translation",
+                severity: Severity::Warning,
+                context: None,
+                missing: Vec::new(),
+                unexpected: Vec::new(),
+                path: ctx.path.to_path_buf(),
+                line_index: msgstr.line_index,
+                column: 1,
+                lines: Vec::new(),
+                rule: None,
+            })
+            .collect()
+    }
+}
+
+/// Returns the report of some diagnostics of `fr.po` in every format.
+fn reports(diagnostics: &[Diagnostic]) -> Vec<String> {
+    let files = [PathBuf::from("fr.po")];
+    let languages = BTreeMap::new();
+    let file_languages = HashMap::from([(PathBuf::from("fr.po"), String::from("fr"))]);
+    let run = Run {
+        files: &files,
+        diagnostics,
+        languages: &languages,
+        file_languages: &file_languages,
+        message_format: None,
+    };
+    OutputFormat::value_variants()
+        .iter()
+        .map(|format| report::render(*format, &run))
+        .collect()
+}
+
+#[test]
+fn rules_of_library_users_are_reported_with_their_code() {
+    let mut options = CheckOptions::default();
+    options.rules.push(Arc::new(Todo));
+    let diagnostics = check(Path::new("fr.po"), Some(CATALOG.as_bytes()), &options);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule().code, "ACME001");
+    assert_eq!(diagnostics[0].line_index, 8);
+    for report in reports(&diagnostics) {
+        assert!(
+            report.contains("ACME001") || report.contains("todo"),
+            "{}",
+            report
+        );
+    }
+
+    options.settings = Arc::new(Settings {
+        severities: vec![(&TODO, Severity::Error)],
+        ..Settings::default()
+    });
+    let diagnostics = check(Path::new("fr.po"), Some(CATALOG.as_bytes()), &options);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
+
+#[test]
+fn diagnostics_without_a_known_rule_are_rendered() {
+    let diagnostic = Diagnostic {
+        message: "Made up by hand",
+        severity: Severity::Error,
+        context: None,
+        missing: Vec::new(),
+        unexpected: Vec::new(),
+        path: PathBuf::from("fr.po"),
+        line_index: 3,
+        column: 1,
+        lines: Vec::new(),
+        rule: None,
+    };
+    assert_eq!(diagnostic.rule().name, "custom");
+    for report in reports(&[diagnostic]) {
+        assert!(
+            report.contains("PO000") || report.contains("custom"),
+            "{}",
+            report
+        );
+    }
+}